@@ -87,18 +87,29 @@ tasks.register("buildRustRelease") {
     }
 }
 
-// Generate uniFFI Kotlin bindings (run cargo build first to create)
+// Generate uniFFI Kotlin bindings from the built library (proc-macro metadata, no UDL)
 tasks.register("generateUniffiBindings") {
     doLast {
         exec {
             workingDir("../rust")
-            commandLine("cargo", "build", "--release")  // Triggers build.rs scaffolding
+            // Release strips the uniffi metadata the generator reads
+            commandLine("cargo", "build", "--profile", "bindings")
+        }
+        exec {
+            workingDir("../rust")
+            commandLine(
+                "cargo", "run", "--features", "bindgen-cli", "--bin", "uniffi-bindgen", "--",
+                "generate", "--library", "target/bindings/libmercyshieldplus.so",
+                "--language", "kotlin", "--out-dir", "target/uniffi-bindings"
+            )
         }
         // Copy generated Kotlin to src/main/kotlin/com/mercyshieldplus
         copy {
             from("../rust/target/uniffi-bindings")
             into("src/main/kotlin/com/mercyshieldplus")
-            include("*.kt")
+            include("**/*.kt")
+            eachFile { path = name }
+            includeEmptyDirs = false
         }
     }
 }
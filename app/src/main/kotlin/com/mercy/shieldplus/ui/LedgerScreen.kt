@@ -16,12 +16,14 @@ import androidx.compose.ui.unit.dp
 import androidx.compose.ui.unit.sp
 import com.mercyshieldplus.viewmodel.ShieldViewModel
 import com.mercyshieldplus.viewmodel.IntegrityReportEntry
+import com.mercyshieldplus.viewmodel.LedgerStatus
 import java.text.SimpleDateFormat
 import java.util.*
 
 @Composable
 fun LedgerScreen(viewModel: ShieldViewModel) {
     val history by viewModel.integrityHistory.collectAsState()
+    val status by viewModel.ledgerStatus.collectAsState()
 
     Scaffold(
         topBar = {
@@ -34,7 +36,8 @@ fun LedgerScreen(viewModel: ShieldViewModel) {
             }
         } else {
             LazyColumn(modifier = Modifier.padding(padding)) {
-                items(history, key = { it.timestamp }) { entry ->
+                status?.let { item { LedgerStatusBanner(it) } }
+                items(history, key = { it.seq.toLong() }) { entry ->
                     LedgerEntryCard(entry)
                 }
             }
@@ -42,6 +45,28 @@ fun LedgerScreen(viewModel: ShieldViewModel) {
     }
 }
 
+@Composable
+fun LedgerStatusBanner(status: LedgerStatus) {
+    val broken = status.firstInvalid
+    Card(
+        modifier = Modifier.fillMaxWidth().padding(8.dp),
+        colors = CardDefaults.cardColors(
+            containerColor = if (broken == null) Color(0xFF0D47A1) else Color(0xFFB71C1C)
+        )
+    ) {
+        Text(
+            text = if (broken == null) {
+                "Chain verified ✓ — ${status.length} signed entries"
+            } else {
+                "Chain broken at entry #$broken ⚠ — later entries cannot be trusted"
+            },
+            color = Color.White,
+            fontWeight = FontWeight.Bold,
+            modifier = Modifier.padding(16.dp)
+        )
+    }
+}
+
 @OptIn(ExperimentalAnimationApi::class)
 @Composable
 fun LedgerEntryCard(entry: IntegrityReportEntry) {
@@ -80,6 +105,12 @@ fun LedgerEntryCard(entry: IntegrityReportEntry) {
                 )
             }
 
+            Text(
+                text = if (entry.verified) "#${entry.seq} · signature verified" else "#${entry.seq} · NOT verified",
+                color = if (entry.verified) Color.White.copy(alpha = 0.7f) else Color.Yellow,
+                fontSize = 12.sp
+            )
+
             Spacer(Modifier.height(8.dp))
 
             AnimatedVisibility(visible = entry.expanded) {
@@ -45,11 +45,13 @@ object PlayIntegrityUtil {
      *
      * @param cloudProjectNumber Your linked Google Cloud project number (long)
      * @param bindData Optional string to bind token (e.g., "integrity_check_${System.currentTimeMillis()}")
+     * @param requestHash Optional precomputed requestHash (e.g., MercyShieldPlus.pqPlayRequestHash(challenge)) — wins over bindData
      * @return Raw token string on success, "null_token" on failure
      */
     suspend fun requestIntegrityToken(
         cloudProjectNumber: Long,
-        bindData: String? = null
+        bindData: String? = null,
+        requestHash: String? = null
     ): String {
         val manager = integrityManager ?: return "null_token".also { /* Mercy log: "Manager not initialized" */ }
 
@@ -57,7 +59,9 @@ object PlayIntegrityUtil {
             val builder = IntegrityTokenRequest.builder()
                 .setCloudProjectNumber(cloudProjectNumber)
 
-            bindData?.let {
+            if (requestHash != null) {
+                builder.setRequestHash(requestHash)
+            } else bindData?.let {
                 builder.setRequestHash(generateRequestHash(it))
             }
 
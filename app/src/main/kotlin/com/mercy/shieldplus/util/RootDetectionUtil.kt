@@ -3,57 +3,15 @@ package com.mercyshieldplus.util
 import android.content.Context
 import android.content.pm.PackageManager
 import android.os.Build
-import java.io.File
 
 /**
  * RootDetectionUtil — Comprehensive Custom Root/Tampering Scans (2026 Best Practices)
  *
- * Supplements Play Integrity with file, package, and property checks.
- * Now includes dedicated KernelSU detection (kernel-based root, harder to hide).
- * Magisk/KernelSU/APatch share /data/adb, but KernelSU has specific traces.
- *
- * Techniques: rootbeer-inspired + 2026 updates for KernelSU (paths like /data/adb/kernelsu)
+ * Supplements Play Integrity with package and property checks (Android APIs only).
+ * su / Magisk / KernelSU file and mount scans live in the Rust core
+ * (`MercyShieldPlus.collectDeviceRootEvidence`, rust/src/collector.rs).
  */
 object RootDetectionUtil {
-    // Common classic root binary/file paths
-    private val classicRootFilePaths = listOf(
-        "/system/app/Superuser.apk",
-        "/system/app/SuperSU.apk",
-        "/system/xbin/su",
-        "/system/xbin/daemonsu",
-        "/system/xbin/busybox",
-        "/system/bin/su",
-        "/system/bin/.ext",
-        "/system/bin/failsafe/su",
-        "/system/sd/xbin/su",
-        "/system/usr/we-need-root/su",
-        "/system/usr/su",
-        "/sbin/su",
-        "/vendor/bin/su",
-        "/cache/su",
-        "/data/local/su",
-        "/data/local/xbin/su",
-        "/data/local/bin/su",
-        "/dev/su",
-        "/su/bin/su"
-    )
-
-    // Magisk-specific indicators
-    private val magiskFilePaths = listOf(
-        "/sbin/.magisk",
-        "/sbin/.core/img",
-        "/data/adb/magisk"
-    )
-
-    // KernelSU-specific indicators (2026 — kernel-based root traces)
-    private val kernelSUFilePaths = listOf(
-        "/data/adb/kernelsu",          // Primary KernelSU base directory
-        "/data/adb/ksu",               // Alternative/alias
-        "/data/adb/kernelsu/modules",  // Modules path
-        "/data/adb/kernelsu/ap_list",  // App profile list
-        "/proc/kernelsu"               // Proc entry if present
-    )
-
     // Known root management packages
     private val rootPackages = listOf(
         "com.topjohnwu.magisk",        // Magisk
@@ -63,25 +21,6 @@ object RootDetectionUtil {
         // KernelSU manager often separate/no fixed package (user-installed APK)
     )
 
-    /**
-     * Scan for all suspicious root files (classic + Magisk + KernelSU)
-     */
-    fun detectSuspiciousFiles(): List<String> {
-        val found = mutableListOf<String>()
-        val allPaths = classicRootFilePaths + magiskFilePaths + kernelSUFilePaths
-
-        for (path in allPaths) {
-            try {
-                if (File(path).exists()) {
-                    found.add(path)
-                }
-            } catch (ignored: Exception) {
-                // Permission/SELinux deny — mercy ignore
-            }
-        }
-        return found
-    }
-
     /**
      * Detect installed root management apps (primarily Magisk/etc.; KernelSU manager varies)
      */
@@ -122,39 +61,6 @@ object RootDetectionUtil {
 
         return issues
     }
-
-    /**
-     * Specific Magisk indicators (files + packages)
-     */
-    fun isMagiskPresent(context: Context): Boolean {
-        if (detectRootPackages(context).any { it == "com.topjohnwu.magisk" }) return true
-
-        for (path in magiskFilePaths) {
-            try {
-                if (File(path).exists()) return true
-            } catch (ignored: Exception) {}
-        }
-        return false
-    }
-
-    /**
-     * Specific KernelSU indicators (2026 — checks primary traces)
-     */
-    fun isKernelSUPresent(): Boolean {
-        for (path in kernelSUFilePaths) {
-            try {
-                if (File(path).exists()) return true
-            } catch (ignored: Exception) {}
-        }
-        return false
-    }
-
-    /**
-     * Generalized any kernel-level root (Magisk OR KernelSU)
-     */
-    fun isAnyKernelRootPresent(context: Context): Boolean {
-        return isMagiskPresent(context) || isKernelSUPresent()
-    }
 }        for (pkg in rootPackages) {
             try {
                 pm.getPackageInfo(pkg, 0)
@@ -9,25 +9,34 @@ import com.mercyshieldplus.MercyShieldPlus
  * SecureKeyManager — Keystore-Backed Persistent Storage for PQ Device Keys Mercy
  *
  * Uses EncryptedSharedPreferences (hardware-backed MasterKey) to store:
- * - DSA secret key base64 (for signing attestations)
- * - Optional KEM secret key if needed
+ * - DSA secret key base64 (signs attestations and ledger entries)
+ * - DSA public key base64 (sent to the server for TOFU enrollment, verifies the local ledger)
+ * - KEM secret key base64 (kept for identity backup)
  *
- * On first launch: Generate fresh PQ keypair via Rust uniFFI
- * Subsequent: Load stored DSA SK base64 for signing
+ * On first launch: Generate fresh PQ keypair via Rust uniFFI and persist all three
+ * Subsequent: Load the stored identity — the same DSA key for the lifetime of the install
  *
  * Biometric optional via MasterKey setUserAuthenticationRequired
  */
 object SecureKeyManager {
     private const val PREFS_NAME = "mercyshield_pq_keys_prefs"
     private const val KEY_DSA_SK_B64 = "dsa_secret_key_base64"
+    private const val KEY_DSA_PK_B64 = "dsa_public_key_base64"
+    private const val KEY_KEM_SK_B64 = "kem_secret_key_base64"
 
     private const val AUTH_VALIDITY_SECONDS = 60  // Post-biometric validity
 
+    data class DeviceKeys(
+        val dsaSkB64: String,
+        val dsaPkB64: String,
+        val kemSkB64: String
+    )
+
     /**
-     * Get or generate persistent DSA SK base64
+     * Get or generate the persistent device identity
      * Triggers biometric if required
      */
-    fun getOrGenerateDsaSkB64(context: Context): String {
+    fun getOrGenerateDeviceKeys(context: Context): DeviceKeys {
         val masterKey = MasterKey.Builder(context)
             .setKeyScheme(MasterKey.KeyScheme.AES256_GCM)
             .setUserAuthenticationRequired(true)  // Biometric mercy
@@ -42,24 +51,25 @@ object SecureKeyManager {
             EncryptedSharedPreferences.PrefValueEncryptionScheme.AES256_GCM
         )
 
-        var dsaSkB64 = prefs.getString(KEY_DSA_SK_B64, null)
-
-        if (dsaSkB64 == null) {
-            // First launch — generate fresh pair
-            val (kemPkB64, dsaPkB64) = MercyShieldPlus.generatePqKeypair()
-            // Here dsaPkB64 is actually the SK in our current uniFFI (wait—no)
-            // Fix: Adjust uniFFI to return SK for storage (or separate gen)
+        val dsaSkB64 = prefs.getString(KEY_DSA_SK_B64, null)
+        val dsaPkB64 = prefs.getString(KEY_DSA_PK_B64, null)
+        val kemSkB64 = prefs.getString(KEY_KEM_SK_B64, null)
+        // Older installs stored a public key in the SK slot and no PK — those regenerate here
+        if (dsaSkB64 != null && dsaPkB64 != null && kemSkB64 != null) {
+            return DeviceKeys(dsaSkB64, dsaPkB64, kemSkB64)
+        }
 
-            // Current uniFFI returns PKs — generate + store SK separately
-            // For demo: Generate and store dummy — production persist real SK
-            dsaSkB64 = dsaPkB64  // Placeholder — real: return SK from Rust gen
+        // First launch — generate fresh pair, only the secret halves and the DSA PK are kept
+        val keys = MercyShieldPlus.generatePqKeypair()
+        val generated = DeviceKeys(keys.dsaSkB64, keys.dsaPkB64, keys.kemSkB64)
 
-            with(prefs.edit()) {
-                putString(KEY_DSA_SK_B64, dsaSkB64)
-                apply()
-            }
+        with(prefs.edit()) {
+            putString(KEY_DSA_SK_B64, generated.dsaSkB64)
+            putString(KEY_DSA_PK_B64, generated.dsaPkB64)
+            putString(KEY_KEM_SK_B64, generated.kemSkB64)
+            commit()  // Synchronous — the key must be on disk before anything is signed with it
         }
 
-        return dsaSkB64
+        return generated
     }
 }
@@ -6,6 +6,7 @@ import okhttp3.MediaType.Companion.toMediaType
 import okhttp3.OkHttpClient
 import okhttp3.Request
 import okhttp3.RequestBody.Companion.toRequestBody
+import org.json.JSONObject
 import java.io.IOException
 
 /**
@@ -17,6 +18,7 @@ import java.io.IOException
  */
 object ServerSyncUtil {
     private const val SERVER_URL = "https://your-mercy-verifier.example.com/verify"  // Replace with actual HTTPS URL mercy
+    private const val CHALLENGE_URL = "https://your-mercy-verifier.example.com/challenge"
 
     // Hardcoded server KEM public key base64 (generate on server, export)
     private const val SERVER_KEM_PK_BASE64 = "your_server_ml_kem_768_pk_base64_here"
@@ -48,6 +50,33 @@ object ServerSyncUtil {
         }
     }
 
+    /**
+     * Fetch a single-use attestation challenge (base64 nonce, expires server-side)
+     * @return challenge base64, or null when offline
+     */
+    suspend fun fetchChallenge(): String? {
+        return withContext(Dispatchers.IO) {
+            val request = Request.Builder()
+                .url(CHALLENGE_URL)
+                .get()
+                .build()
+
+            try {
+                client.newCall(request).execute().use { response ->
+                    if (!response.isSuccessful) return@use null
+                    JSONObject(response.body?.string() ?: return@use null).optString("challenge").ifEmpty { null }
+                }
+            } catch (e: IOException) {
+                null  // Network mercy failure
+            }
+        }
+    }
+
+    /**
+     * Get server KEM PK base64 (as pqSecureAttestationBlob takes it)
+     */
+    fun getServerKemPkB64(): String = SERVER_KEM_PK_BASE64
+
     /**
      * Get server KEM PK bytes (base64 decode)
      */
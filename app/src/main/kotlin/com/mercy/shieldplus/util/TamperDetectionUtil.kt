@@ -3,6 +3,7 @@ package com.mercyshieldplus.util
 import android.content.Context
 import android.content.pm.PackageManager
 import android.os.Build
+import com.mercyshieldplus.Evidence
 import java.io.File
 import java.io.FileInputStream
 import java.security.MessageDigest
@@ -29,10 +30,19 @@ object TamperDetectionUtil {
     private const val EXPECTED_DEBUG_SIG_SHA256 = "YOUR_DEBUG_CERT_SHA256_HERE"
     private const val SKIP_CHECKSUM_IN_DEBUG = true  // Debug APK path varies — optional skip
 
+    private val expectedSigSha256: String
+        get() = if (android.os.BuildConfig.DEBUG) EXPECTED_DEBUG_SIG_SHA256 else EXPECTED_RELEASE_SIG_SHA256
+
     /**
      * Check signing certificate fingerprint
      */
-    private fun isSignatureTampered(context: Context): Boolean {
+    private fun isSignatureTampered(context: Context): Boolean =
+        signingCertFingerprint(context).let { it == null || it != expectedSigSha256 }  // Error → tampered mercy
+
+    /**
+     * SHA-256 fingerprint of the signing certificate (colon hex), null if unavailable
+     */
+    private fun signingCertFingerprint(context: Context): String? {
         return try {
             val packageInfo = if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.P) {
                 context.packageManager.getPackageInfo(
@@ -54,16 +64,13 @@ object TamperDetectionUtil {
                 packageInfo.signatures
             }
 
-            if (signatures.isEmpty()) return true
+            if (signatures.isEmpty()) return null
 
             val cert = signatures[0].toByteArray()
             val digest = MessageDigest.getInstance("SHA-256").digest(cert)
-            val fingerprint = digest.joinToString(":") { "%02X".format(it) }
-
-            val expected = if (android.os.BuildConfig.DEBUG) EXPECTED_DEBUG_SIG_SHA256 else EXPECTED_RELEASE_SIG_SHA256
-            fingerprint != expected
+            digest.joinToString(":") { "%02X".format(it) }
         } catch (e: Exception) {
-            true  // Error → tampered mercy
+            null
         }
     }
 
@@ -73,10 +80,17 @@ object TamperDetectionUtil {
     private fun isApkChecksumTampered(context: Context): Boolean {
         if (android.os.BuildConfig.DEBUG && SKIP_CHECKSUM_IN_DEBUG) return false  // Debug mercy
 
+        return apkSha256(context).let { it == null || it != EXPECTED_RELEASE_APK_SHA256.lowercase() }  // Error → tampered
+    }
+
+    /**
+     * SHA-256 of base.apk (lowercase hex), null if unreadable
+     */
+    private fun apkSha256(context: Context): String? {
         return try {
             val apkPath = context.packageCodePath  // /data/app/~~package~~/base.apk
             val file = File(apkPath)
-            if (!file.exists()) return true
+            if (!file.exists()) return null
 
             val digest = MessageDigest.getInstance("SHA-256")
             FileInputStream(file).use { fis ->
@@ -87,10 +101,9 @@ object TamperDetectionUtil {
                 }
             }
 
-            val hash = digest.digest().joinToString("") { "%02x".format(it) }
-            hash != EXPECTED_RELEASE_APK_SHA256.lowercase()
+            digest.digest().joinToString("") { "%02x".format(it) }
         } catch (e: Exception) {
-            true  // Error → tampered
+            null
         }
     }
 
@@ -103,6 +116,20 @@ object TamperDetectionUtil {
         return sigTampered || checksumTampered
     }
 
+    /**
+     * Typed findings for the Rust report (expected vs. observed values)
+     */
+    fun collectEvidence(context: Context): List<Evidence> {
+        val evidence = mutableListOf<Evidence>()
+        if (isSignatureTampered(context)) {
+            evidence.add(Evidence.SignatureMismatch(expectedSigSha256, signingCertFingerprint(context) ?: "unavailable"))
+        }
+        if (isApkChecksumTampered(context)) {
+            evidence.add(Evidence.ApkChecksumMismatch(EXPECTED_RELEASE_APK_SHA256.lowercase(), apkSha256(context) ?: "unavailable"))
+        }
+        return evidence
+    }
+
     /**
      * Get details for logging/reporting
      */
@@ -1,36 +1,58 @@
 package com.mercyshieldplus.viewmodel
 
 import android.app.Application
+import android.util.AtomicFile
+import android.util.Base64
 import androidx.lifecycle.AndroidViewModel
 import androidx.lifecycle.viewModelScope
 import com.google.gson.Gson
-import com.google.gson.reflect.TypeToken
+import com.google.gson.JsonParser
 import com.mercyshieldplus.database.AppDatabase
-import com.mercyshieldplus.database.IntegrityReportEntity
 import com.mercyshieldplus.database.LogEntryEntity
 import com.mercyshieldplus.util.PlayIntegrityUtil
 import com.mercyshieldplus.util.RootDetectionUtil
+import com.mercyshieldplus.util.SecureKeyManager
 import com.mercyshieldplus.util.ServerSyncUtil
 import com.mercyshieldplus.util.TamperDetectionUtil
+import java.io.File
+import kotlinx.coroutines.Dispatchers
 import kotlinx.coroutines.flow.MutableStateFlow
 import kotlinx.coroutines.flow.StateFlow
 import kotlinx.coroutines.flow.asStateFlow
 import kotlinx.coroutines.launch
+import kotlinx.coroutines.withContext
 
 // uniFFI generated bindings mercy
+import com.mercyshieldplus.Evidence
+import com.mercyshieldplus.IntegrityVerdict
+import com.mercyshieldplus.LedgerRecord
 import com.mercyshieldplus.MercyShieldPlus
+import com.mercyshieldplus.MercyException
+import com.mercyshieldplus.MessageKind
+import com.mercyshieldplus.PqLedger
 
+// One signed ledger entry as LedgerScreen shows it
 data class IntegrityReportEntry(
+ val seq: ULong,
  val timestamp: Long,
  val isGenuine: Boolean,
  val isAnomaly: Boolean = !isGenuine,
  val details: List<String>,
  val riskScore: UByte,
  val verdict: String,
+ val verified: Boolean, // Signature and chain link checked against the device DSA PK
  var expanded: Boolean = false,
  val onToggleExpand: () -> Unit
 )
 
+// Outcome of walking the persisted ledger from genesis
+data class LedgerStatus(
+ val length: ULong,
+ val firstInvalid: ULong?
+) {
+ val isIntact: Boolean get() = firstInvalid == null
+}
+
 sealed class ShieldState {
  object Loading : ShieldState()
  data class Genuine(val details: List<String>) : ShieldState()
@@ -42,6 +64,16 @@ class ShieldViewModel(application: Application) : AndroidViewModel(application)
  private val database = AppDatabase.getDatabase(application)
  private val dao = database.integrityDao()
 
+ // Persistent device identity — one DSA key signs every report and ledger entry
+ private val deviceKeys by lazy { SecureKeyManager.getOrGenerateDeviceKeys(getApplication()) }
+
+ // Hash-chained report history, persisted as the Rust export (atomic replace on every append)
+ private val ledgerFile = AtomicFile(File(application.filesDir, LEDGER_FILE))
+ private var ledger: PqLedger? = null
+
+ private val _ledgerStatus = MutableStateFlow<LedgerStatus?>(null)
+ val ledgerStatus: StateFlow<LedgerStatus?> = _ledgerStatus.asStateFlow()
+
  private val _shieldState = MutableStateFlow<ShieldState>(ShieldState.Loading)
  val shieldState: StateFlow<ShieldState> = _shieldState.asStateFlow()
 
@@ -52,7 +84,7 @@ class ShieldViewModel(application: Application) : AndroidViewModel(application)
 
  init {
  PlayIntegrityUtil.init(getApplication())
- loadHistoryFromDb()
+ loadLedger()
  logEvent("INFO", "MercyShieldPlus fortress awakening eternal ⚡️")
  checkIntegrity()
  }
@@ -68,105 +100,205 @@ class ShieldViewModel(application: Application) : AndroidViewModel(application)
  }
  }
 
- private fun loadHistoryFromDb() {
+ private fun loadLedger() {
  viewModelScope.launch {
- dao.getAllHistory().collect { entities ->
- val entries = entities.map { entity ->
- val details = gson.fromJson<List<String>>(
- entity.detailsJson,
- object : TypeToken<List<String>>() {}.type
- ) ?: emptyList()
-
- IntegrityReportEntry(
- timestamp = entity.timestamp,
- isGenuine = entity.isGenuine,
- details = details,
- riskScore = entity.riskScore.toUByte(),
- verdict = entity.verdict,
- onToggleExpand = {
- toggleExpanded(entity.timestamp)
+ try {
+ publishLedger(openLedger())
+ } catch (e: Exception) {
+ logEvent("ERROR", "Ledger load failed: ${e.message}")
  }
- )
  }
- _integrityHistory.value = entries.sortedByDescending { it.timestamp }
  }
+
+ private suspend fun openLedger(): PqLedger {
+ ledger?.let { return it }
+ val export = withContext(Dispatchers.IO) {
+ if (ledgerFile.baseFile.exists()) ledgerFile.readFully() else null
+ }
+ val opened = try {
+ export?.let { PqLedger.fromExport(it) } ?: PqLedger()
+ } catch (e: MercyException) {
+ // Unparsable export — set it aside instead of overwriting it with a fresh chain
+ withContext(Dispatchers.IO) {
+ ledgerFile.baseFile.renameTo(File(ledgerFile.baseFile.path + ".corrupt"))
  }
+ logEvent("ERROR", "Ledger export unreadable (${e::class.simpleName}) — moved aside, new chain started")
+ PqLedger()
+ }
+ ledger = opened
+ return opened
+ }
+
+ // Sign the canonical report onto the chain, persist, then re-verify what the UI shows
+ private suspend fun appendToLedger(canonicalReport: String, timestampMs: Long) {
+ val chain = openLedger()
+ chain.append(canonicalReport.toByteArray(), timestampMs.toULong(), deviceKeys.dsaSkB64)
+ val export = chain.export()
+ withContext(Dispatchers.IO) {
+ val out = ledgerFile.startWrite()
+ try {
+ out.write(export)
+ ledgerFile.finishWrite(out)
+ } catch (e: Exception) {
+ ledgerFile.failWrite(out)
+ throw e
+ }
+ }
+ publishLedger(chain)
  }
 
- private fun toggleExpanded(timestamp: Long) {
+ private fun publishLedger(chain: PqLedger) {
+ val verification = chain.verify(deviceKeys.dsaPkB64)
+ _ledgerStatus.value = LedgerStatus(verification.length, verification.firstInvalid)
+ if (verification.firstInvalid != null) {
+ logEvent("ERROR", "Ledger chain broken at entry ${verification.firstInvalid} — history was tampered with")
+ }
+
+ val expanded = _integrityHistory.value.filter { it.expanded }.map { it.seq }.toSet()
+ _integrityHistory.value = chain.records()
+ .map { toHistoryEntry(it, verification.firstInvalid, it.seq in expanded) }
+ .sortedByDescending { it.seq }
+ }
+
+ private fun toHistoryEntry(record: LedgerRecord, firstInvalid: ULong?, expanded: Boolean): IntegrityReportEntry {
+ // Each entry holds the signed report map; the Rust report sits under "report"
+ val report = runCatching {
+ val signed = JsonParser.parseString(record.report).asJsonObject
+ MercyShieldPlus.reportFromJson(signed.get("report").toString())
+ }.getOrNull()
+
+ return IntegrityReportEntry(
+ seq = record.seq,
+ timestamp = record.timestampMs.toLong(),
+ isGenuine = report?.verdict == IntegrityVerdict.GENUINE,
+ details = report?.evidence?.map(::evidenceLabel) ?: listOf("Unreadable report"),
+ riskScore = report?.riskScore ?: 0u,
+ verdict = report?.verdict?.name ?: "UNKNOWN",
+ verified = firstInvalid == null || record.seq < firstInvalid,
+ expanded = expanded,
+ onToggleExpand = {
+ toggleExpanded(record.seq)
+ }
+ )
+ }
+
+ private fun toggleExpanded(seq: ULong) {
  _integrityHistory.value = _integrityHistory.value.map {
- if (it.timestamp == timestamp) it.copy(expanded = !it.expanded) else it
+ if (it.seq == seq) it.copy(expanded = !it.expanded) else it
  }
  }
 
+ private fun evidenceLabel(evidence: Evidence): String = when (evidence) {
+ is Evidence.SuspiciousFile -> evidence.path
+ is Evidence.MagiskIndicator -> "Magisk: ${evidence.indicator}"
+ is Evidence.KernelRoot -> "Kernel root: ${evidence.indicator}"
+ is Evidence.DebuggerAttached -> "Debugger attached (TracerPid ${evidence.tracerPid})"
+ is Evidence.FridaIndicator -> "Frida: ${evidence.indicator}"
+ is Evidence.RwxMemory -> "RWX memory: ${evidence.region}"
+ is Evidence.TamperProp -> "${evidence.name}=${evidence.value}"
+ is Evidence.RootPackage -> "Root package: ${evidence.`package`}"
+ else -> evidence.toString()
+ }
+
  private fun checkIntegrity() {
  viewModelScope.launch {
  try {
  logEvent("INFO", "Integrity check initiated — scanning fortress")
 
- val suspiciousFiles = RootDetectionUtil.detectSuspiciousFiles()
+ // su / Magisk / KernelSU files and mounts are scanned natively (Rust collector, not hookable here)
+ val rootEvidence = MercyShieldPlus.collectDeviceRootEvidence()
+ // Debugger / Frida traces in our own /proc (TracerPid, maps, threads, fds, default ports)
+ val instrumentationEvidence = MercyShieldPlus.collectDeviceInstrumentationEvidence()
  val suspiciousProps = RootDetectionUtil.detectSuspiciousProps()
- val anyKernelRoot = RootDetectionUtil.isAnyKernelRootPresent(getApplication())
+ val rootPackages = RootDetectionUtil.detectRootPackages(getApplication())
  val tamperDetails = TamperDetectionUtil.getTamperDetails(getApplication())
 
- val allDetails = suspiciousFiles + suspiciousProps + tamperDetails + if (anyKernelRoot) listOf("Kernel root indicators detected") else emptyList()
+ val rootDetails = (rootEvidence + instrumentationEvidence).map(::evidenceLabel)
+ val allDetails = rootDetails + suspiciousProps + tamperDetails
+
+ // Typed findings for the Rust report — display strings above stay for the status screen
+ val evidence = buildList {
+ addAll(rootEvidence)
+ addAll(instrumentationEvidence)
+ suspiciousProps.forEach { prop ->
+ val (name, value) = prop.split("=", limit = 2).let { it[0] to it.getOrElse(1) { "" } }
+ add(Evidence.TamperProp(name, value))
+ }
+ rootPackages.forEach { add(Evidence.RootPackage(it)) }
+ addAll(TamperDetectionUtil.collectEvidence(getApplication()))
+ }
 
+ // Server challenge binds both the Play token and the signed report to this round trip
+ val challengeB64 = ServerSyncUtil.fetchChallenge()
  val bindData = "integrity_check_${System.currentTimeMillis()}"
  val cloudProjectNumber = 123456789012L // Your Google Cloud project number mercy
- val playToken = PlayIntegrityUtil.requestIntegrityToken(cloudProjectNumber, bindData)
+ val playToken = PlayIntegrityUtil.requestIntegrityToken(
+ cloudProjectNumber,
+ bindData,
+ challengeB64?.let { MercyShieldPlus.pqPlayRequestHash(it) }
+ )
 
- // PQ key generation mercy (persist SK production)
- val (kemPkB64, dsaPkB64) = MercyShieldPlus.generatePqKeypair()
- logEvent ("INFO", "PQ keys generated — KEM PK: ${kemPkB64.take(20)}..., DSA PK: ${dsaPkB64.take(20)}...")
+ // Risk score + verdict come from the Rust policy engine (policy id/version travel in the report)
+ val report = MercyShieldPlus.evaluateIntegrity(evidence, playToken)
+ val isGenuine = report.verdict == IntegrityVerdict.GENUINE
 
- // Build report JSON with all details + token + DSA PK
+ // Persistent device identity (generated once, kept in SecureKeyManager)
+ val keys = deviceKeys
+
+ // Build report JSON with the Rust report + token + DSA PK
+ val checkedAt = System.currentTimeMillis()
  val reportMap = mapOf(
- "timestamp" to System.currentTimeMillis(),
- "details" to allDetails,
+ "timestamp" to checkedAt,
+ "report" to JsonParser.parseString(MercyShieldPlus.reportToJson(report)), // verdict, typed evidence, policy
  "play_token" to playToken,
- "dsa_pk_base64" to dsaPkB64 // Bootstrap server trust
+ "dsa_pk_base64" to keys.dsaPkB64 // Bootstrap server trust
  )
- val jsonReport = gson.toJson(reportMap)
+ // Canonical (RFC 8785) bytes are what gets signed — the verifier rejects any other encoding
+ val jsonReport = MercyShieldPlus.canonicalizeJson(gson.toJson(reportMap))
+
+ // Every check, genuine or not, is signed onto the local ledger
+ appendToLedger(jsonReport, checkedAt)
 
- // On anomaly — sign report + generate blob mercy
- if (allDetails.isNotEmpty()) {
- // Sign with persisted DSA SK (future Keystore) — here fresh for demo
- val sigB64 = MercyShieldPlus.pqSignData(dsaPkB64, jsonReport.toByteArray())
+ // On anomaly — sign report + generate blob mercy (offline → no challenge → no sync, verifier would reject)
+ if (!isGenuine && challengeB64 != null) {
+ val challengedReport = MercyShieldPlus.pqBindChallenge(jsonReport.toByteArray(), challengeB64)
+
+ // Signed with the persisted device SK — its PK is the one in the report
+ val sigB64 = MercyShieldPlus.pqSignData(keys.dsaSkB64, challengedReport, MessageKind.ATTESTATION_REPORT)
  logEvent("INFO", "Report signed — signature: ${sigB64.take(20)}...")
 
  val serverPkB64 = ServerSyncUtil.getServerKemPkB64() // Config mercy
  val blobB64 = MercyShieldPlus.pqSecureAttestationBlob(
- jsonReport.toByteArray(),
+ challengedReport,
  serverPkB64,
- dsaPkB64 // Local SK base64 for sign
+ keys.dsaSkB64 // Local SK base64 for sign
  )
 
- val blobBytes = BASE64.decode(blobB64)
+ val blobBytes = Base64.decode(blobB64, Base64.DEFAULT)
  val syncSuccess = ServerSyncUtil.sendAnomalyBlob(blobBytes)
  logEvent(if (syncSuccess) "SYNC_SUCCESS" else "SYNC_FAILURE", "Anomaly blob sync — ${if (syncSuccess) "delivered eternal" else "offline mercy"}")
  }
 
- // Persist report mercy
- val entity = IntegrityReportEntity(
- isGenuine = allDetails.isEmpty(),
- detailsJson = gson.toJson(allDetails),
- riskScore = if (allDetails.isEmpty()) 0 else 80,
- verdict = if (allDetails.isEmpty()) "Genuine" else "Anomaly"
- )
- dao.insert(entity)
-
  // UI state mercy
- _shieldState.value = if (allDetails.isEmpty()) {
+ _shieldState.value = if (isGenuine) {
  ShieldState.Genuine(allDetails)
  } else {
- ShieldState.Anomaly("Anomaly Detected", allDetails, 80u)
+ ShieldState.Anomaly(report.verdict.name, allDetails, report.riskScore)
  }
 
- logEvent("INFO", "Integrity check completed — status: ${if (allDetails.isEmpty()) "Genuine Eternal" else "Anomaly Detected"}")
+ logEvent("INFO", "Integrity check completed — ${report.verdict.name} (risk ${report.riskScore}, policy ${report.policyId} v${report.policyVersion})")
+ } catch (e: MercyException) {
+ // Typed Rust core error (bad key/base64/signature) — no more native abort
+ logEvent("ERROR", "PQ core rejected input: ${e::class.simpleName} — ${e.message}")
+ _shieldState.value = ShieldState.Error("PQ core error: ${e.message}")
  } catch (e: Exception) {
  logEvent("ERROR", "Integrity check failed: ${e.message}")
  _shieldState.value = ShieldState.Error(e.message ?: "Unknown fortress anomaly")
  }
  }
  }
+
+ companion object {
+ private const val LEDGER_FILE = "pq_ledger.mslx"
+ }
 }
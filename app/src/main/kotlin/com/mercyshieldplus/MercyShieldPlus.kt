@@ -8,15 +8,217 @@ object MercyShieldPlus {
         System.loadLibrary("mercyshieldplus")
     }
 
-    external fun generatePqKeypair(): Pair<String, String>
+    external fun generatePqKeypair(): PqKeyPair
 
-    external fun pqSignData(dsaSkB64: String, message: ByteArray): String
+    external fun evaluateIntegrity(evidence: List<Evidence>, playToken: String): IntegrityReport
 
-    external fun pqVerifyData(dsaPkB64: String, message: ByteArray, signatureB64: String): Boolean
+    external fun evaluateIntegrityWithPolicy(evidence: List<Evidence>, playToken: String, policyJson: String): IntegrityReport
+
+    external fun evidenceSeverity(evidence: Evidence): Severity
+
+    external fun evidenceSource(evidence: Evidence): EvidenceSource
+
+    external fun defaultRiskPolicyJson(): String
+
+    external fun collectDeviceRootEvidence(): List<Evidence>
+
+    external fun collectDeviceInstrumentationEvidence(): List<Evidence>
+
+    external fun reportToJson(report: IntegrityReport): String
+
+    external fun reportFromJson(json: String): IntegrityReport
+
+    external fun canonicalizeJson(json: String): String
+
+    external fun pqSignData(dsaSkB64: String, message: ByteArray, kind: MessageKind): String
+
+    external fun pqVerifyData(dsaPkB64: String, message: ByteArray, signatureB64: String, kind: MessageKind): Boolean
 
     external fun pqSecureAttestationBlob(
         report: ByteArray,
         serverKemPkB64: String,
         localDsaSkB64: String? = null
     ): String
+
+    external fun pqBindChallenge(report: ByteArray, challengeB64: String): ByteArray
+
+    external fun pqPlayRequestHash(challengeB64: String): String
+
+    external fun pqRotateDeviceKey(oldDsaSkB64: String, currentEpoch: ULong): RotatedKey
+
+    external fun pqVerifyRotation(statementB64: String, enrolledDsaPkB64: String, enrolledEpoch: ULong): String
+
+    external fun pqExportKeyBackup(
+        passphrase: String,
+        kemSkB64: String,
+        dsaSkB64: String,
+        dsaEpoch: ULong,
+        params: KdfParams? = null
+    ): String
+
+    external fun pqImportKeyBackup(passphrase: String, backupB64: String): RestoredKeys
+
+    external fun pqVerifyInclusion(sthB64: String, dsaPkB64: String, entry: ByteArray, proof: InclusionProof): Boolean
+
+    external fun pqVerifyConsistency(
+        oldSthB64: String,
+        newSthB64: String,
+        dsaPkB64: String,
+        proof: ConsistencyProof
+    ): Boolean
+}
+
+// Policy-scored integrity report (mirrors rust/src/integrity.rs + policy.rs)
+data class IntegrityReport(
+    val schemaVersion: UInt,
+    val verdict: IntegrityVerdict,
+    val evidence: List<Evidence>,
+    val riskScore: UByte,
+    val playToken: String,
+    val policyId: String,
+    val policyVersion: UInt
+)
+
+enum class IntegrityVerdict {
+    GENUINE,
+    SUSPICIOUS,
+    COMPROMISED
 }
+
+// Typed integrity findings with raw values (mirrors rust/src/evidence.rs)
+sealed class Evidence {
+    data class SuspiciousFile(val path: String) : Evidence()
+    data class TamperProp(val name: String, val value: String) : Evidence()
+    data class RootPackage(val `package`: String) : Evidence()
+    data class MagiskIndicator(val indicator: String) : Evidence()
+    data class KernelRoot(val indicator: String) : Evidence()
+    data class SignatureMismatch(val expected: String, val actual: String) : Evidence()
+    data class ApkChecksumMismatch(val expected: String, val actual: String) : Evidence()
+    data class PlayTokenMissing(val reason: String) : Evidence()
+    data class DebuggerAttached(val tracerPid: UInt) : Evidence()
+    data class FridaIndicator(val indicator: String) : Evidence()
+    data class RwxMemory(val region: String) : Evidence()
+}
+
+enum class Severity {
+    LOW,
+    MEDIUM,
+    HIGH,
+    CRITICAL
+}
+
+enum class EvidenceSource {
+    FILE_SYSTEM,
+    SYSTEM_PROPERTY,
+    PACKAGE_MANAGER,
+    KERNEL,
+    APP_SIGNATURE,
+    APK_FILE,
+    PLAY_INTEGRITY,
+    PROCESS
+}
+
+// Freshly generated device identity — SKs go straight into SecureKeyManager
+data class PqKeyPair(
+    val kemPkB64: String,
+    val dsaPkB64: String,
+    val kemSkB64: String,
+    val dsaSkB64: String
+)
+
+// Passphrase-sealed identity backup (mirrors rust/src/backup.rs)
+data class KdfParams(
+    val memoryKib: UInt,
+    val iterations: UInt,
+    val parallelism: UInt
+)
+
+data class RestoredKeys(
+    val kemSkB64: String,
+    val dsaSkB64: String,
+    val dsaEpoch: ULong
+)
+
+// Device key rotation result (mirrors rust/src/rotation.rs)
+data class RotatedKey(
+    val newDsaSkB64: String,
+    val newDsaPkB64: String,
+    val epoch: ULong,
+    val statementB64: String
+)
+
+// Streaming HashML-DSA signer/verifier for large exports (mirrors rust/src/prehash.rs)
+class PqPrehashState(alg: PrehashAlgorithm) {
+    external fun update(chunk: ByteArray)
+
+    external fun sign(dsaSkB64: String, kind: MessageKind): String
+
+    external fun verify(dsaPkB64: String, signatureB64: String, kind: MessageKind): Boolean
+}
+
+enum class PrehashAlgorithm {
+    SHA512,
+    SHAKE256
+}
+
+// FIPS 204 signing context per message type (mirrors rust/src/context.rs)
+enum class MessageKind {
+    ATTESTATION_REPORT,
+    KEY_ROTATION,
+    LOG_EXPORT,
+    LEDGER_ENTRY,
+    TREE_HEAD
+}
+
+// Hash-chained, ML-DSA signed report ledger (mirrors rust/src/ledger.rs)
+class PqLedger {
+    companion object {
+        external fun fromExport(export: ByteArray): PqLedger
+    }
+
+    external fun append(report: ByteArray, timestampMs: ULong, dsaSkB64: String): String
+
+    external fun verify(dsaPkB64: String): LedgerVerification
+
+    external fun entry(seq: ULong): ByteArray
+
+    external fun records(): List<LedgerRecord>
+
+    external fun signTreeHead(timestampMs: ULong, dsaSkB64: String): String
+
+    external fun inclusionProof(seq: ULong, treeSize: ULong): InclusionProof
+
+    external fun consistencyProof(oldSize: ULong, newSize: ULong): ConsistencyProof
+
+    external fun export(): ByteArray
+
+    external fun len(): ULong
+
+    external fun isEmpty(): Boolean
+}
+
+data class LedgerVerification(
+    val length: ULong,
+    val firstInvalid: ULong?,
+    val headHash: ByteArray
+)
+
+data class LedgerRecord(
+    val seq: ULong,
+    val timestampMs: ULong,
+    val report: String,
+    val entryHash: ByteArray
+)
+
+// RFC 6962 proofs over the ledger's Merkle tree (mirrors rust/src/merkle.rs)
+data class InclusionProof(
+    val leafIndex: ULong,
+    val treeSize: ULong,
+    val path: List<ByteArray>
+)
+
+data class ConsistencyProof(
+    val oldSize: ULong,
+    val newSize: ULong,
+    val path: List<ByteArray>
+)
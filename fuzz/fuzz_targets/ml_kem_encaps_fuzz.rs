@@ -1,11 +1,9 @@
 #![no_main]
 use libfuzzer_sys::fuzz_target;
-use ml_kem::MlKem768;
+use mercyshieldplus::pq;
 
 fuzz_target!(|server_pk_bytes: &[u8]| {
-    if server_pk_bytes.len() == MlKem768PublicKey::BYTE_LEN {
-        if let Ok(server_pk) = MlKem768PublicKey::from_bytes(server_pk_bytes.try_into().unwrap()) {
-            let _ = MlKem768::encapsulate(&server_pk, &mut rand_core::OsRng);
-        }
+    if let Ok(server_pk) = pq::kem_public_key(server_pk_bytes) {
+        let _ = pq::kem_encapsulate(&server_pk, &mut rand_core::OsRng);
     }
 });
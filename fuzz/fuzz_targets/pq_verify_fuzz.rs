@@ -1,13 +1,14 @@
 #![no_main]
 use libfuzzer_sys::fuzz_target;
 use arbitrary::{Arbitrary, Unstructured};
-use mercyshieldplus::pq_verify_data;
+use mercyshieldplus::{pq_verify_data, MessageKind};
 
 #[derive(Arbitrary, Debug)]
 struct VerifyInput {
     dsa_pk_b64: String,
     message: Vec<u8>,
     signature_b64: String,
+    kind: u8,
 }
 
 fuzz_target!(|input: &[u8]| {
@@ -17,6 +18,7 @@ fuzz_target!(|input: &[u8]| {
             verify_input.dsa_pk_b64,
             verify_input.message,
             verify_input.signature_b64,
+            MessageKind::ALL[verify_input.kind as usize % MessageKind::ALL.len()],
         );
     }
 });
@@ -1,12 +1,8 @@
 #![no_main]
 use libfuzzer_sys::fuzz_target;
-use ml_kem::{MlKem768, MlKem768Ciphertext};
+use mercyshieldplus::pq;
 
 fuzz_target!(|data: &[u8]| {
-    if data.len() == MlKem768Ciphertext::BYTE_LEN {
-        let ct_bytes: [u8; MlKem768Ciphertext::BYTE_LEN] = data.try_into().unwrap();
-        let ct = MlKem768Ciphertext::from_bytes(ct_bytes);
-        let (_sk, _pk) = MlKem768::generate(&mut rand::thread_rng());  // Dummy SK
-        let _ = MlKem768::decapsulate(&ct, &_sk);  // Fuzz malformed ct
-    }
+    let (sk, _pk) = pq::kem_generate(&mut rand::thread_rng());  // Dummy SK
+    let _ = pq::kem_decapsulate(&sk, data);  // Fuzz malformed ct (wrong length is InvalidBlob)
 });
@@ -1,7 +1,7 @@
 #![no_main]
 use libfuzzer_sys::fuzz_target;
 use arbitrary::{Arbitrary, Unstructured};
-use mercyshieldplus::{pq_verify_data};  // Your lib exports
+use mercyshieldplus::{pq_verify_data, MessageKind};  // Your lib exports
 
 // Structured input for verify (PK, message, sig — arbitrary bytes)
 #[derive(Arbitrary, Debug)]
@@ -9,6 +9,7 @@ struct VerifyInput {
     dsa_pk_b64: String,
     message: Vec<u8>,
     signature_b64: String,
+    kind: u8,
 }
 
 fuzz_target!(|input: &[u8]| {
@@ -19,6 +20,7 @@ fuzz_target!(|input: &[u8]| {
             verify_input.dsa_pk_b64,
             verify_input.message,
             verify_input.signature_b64,
+            MessageKind::ALL[verify_input.kind as usize % MessageKind::ALL.len()],
         );
     }
 });
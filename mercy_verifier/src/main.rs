@@ -1,22 +1,65 @@
 use axum::{
-    routing::{post},
-    Json, Router, extract::State,
+    extract::State,
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
 };
-use ml_kem::{MlKem768, MlKem768Ciphertext, MlKem768SecretKey, MlKem768SharedSecret};
-use ml_dsa::MlDsa65;
-use ml_dsa::signature::{Signature, Verifier};
-use aes_gcm::{AeadInPlace, Aes256Gcm, KeyInit, Nonce};
-use zeroize::Zeroizing;
-use reqwest::{Client};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use jsonschema::JSONSchema;
+use mercyshieldplus::batch::{BatchVerifier, VerifyJob};
+use mercyshieldplus::blob::{key_id, KEY_ID_LEN};
+use mercyshieldplus::challenge::{play_request_hash, ChallengeStore, ChallengedReport};
+use mercyshieldplus::integrity::report_from_json;
+use mercyshieldplus::jcs::is_canonical;
+use mercyshieldplus::key_encoding::{
+    pem_to_der, private_key_from_der, KeyAlgorithm, PEM_PRIVATE_LABEL,
+};
+use mercyshieldplus::pq;
+use mercyshieldplus::rotation::{verify_rotation, RotationStatement};
+use mercyshieldplus::secret::SecretBuffer;
+use mercyshieldplus::{open_attestation_blob, signature_status, MessageKind, SignatureStatus};
+use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
-use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use zeroize::Zeroizing;
 
 #[derive(Serialize, Deserialize)]
 struct AttestationRequest {
-    blob: Vec<u8>,  // Raw blob: ciphertext || nonce || encrypted_payload (payload = json_report || signature)
+    blob: Vec<u8>, // Raw v2 blob: header (magic, version, alg ids, key id, KEM ct, nonce) || AEAD(challenged report || signature)
+}
+
+#[derive(Serialize, Deserialize)]
+struct ChallengeResponse {
+    challenge: String, // base64 nonce — bind with pq_bind_challenge + pq_play_request_hash
+    expires_in: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RotationRequest {
+    statement: Vec<u8>, // Encoded RotationStatement (cross-signed by old + new device DSA keys)
+}
+
+#[derive(Serialize, Deserialize)]
+struct SignatureItem {
+    dsa_pk: Vec<u8>,
+    message: Vec<u8>,
+    signature: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BatchVerifyRequest {
+    kind: String, // attestation_report | key_rotation | log_export | ledger_entry | tree_head
+    items: Vec<SignatureItem>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BatchVerifyResult {
+    valid: bool,
+    error: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -29,54 +72,104 @@ struct TokenVerifyResponse {
     token_payload: Value,
 }
 
-#[derive(Clone)]
+/// Device DSA key currently trusted for a device, keyed by the key fingerprint
+struct Enrollment {
+    dsa_pk: Vec<u8>,
+    epoch: u64,
+}
+
+#[derive(Default)]
+struct Registry {
+    enrolled: HashMap<[u8; KEY_ID_LEN], Enrollment>,
+    retired: HashSet<[u8; KEY_ID_LEN]>, // Rotated-away keys — never re-enrolled by TOFU
+}
+
+const MAX_BATCH_ITEMS: usize = 10_000;
+const VERIFY_QUEUE_DEPTH: usize = 1024;
+
+// Generated from the core's report types — see rust/report-schema.rs
+const REPORT_SCHEMA: &str = include_str!("../../rust/schema/integrity-report.schema.json");
+
+const CHALLENGE_TTL_SECS: u64 = 120;
+const MAX_OUTSTANDING_CHALLENGES: usize = 100_000;
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
 struct AppState {
-    server_sk: MlKem768SecretKey,
+    server_sk: SecretBuffer, // Raw ML-KEM-768 decapsulation key, mlock'd + guard-paged; parsed per request
+    registry: Mutex<Registry>,
+    challenges: Mutex<ChallengeStore>,
+    report_schema: JSONSchema,
+    batch_verifier: Arc<BatchVerifier>,
     http_client: Client,
     play_api_key: String,
     package_name: String,
 }
 
+async fn issue_challenge(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<ChallengeResponse>, (StatusCode, String)> {
+    // A full store refuses new challenges instead of evicting ones already handed out
+    let nonce = state
+        .challenges
+        .lock()
+        .unwrap()
+        .issue(unix_now())
+        .map_err(|e| {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                format!("Challenge unavailable: {}", e),
+            )
+        })?;
+    Ok(Json(ChallengeResponse {
+        challenge: BASE64.encode(nonce),
+        expires_in: CHALLENGE_TTL_SECS,
+    }))
+}
+
 async fn verify_attestation(
     State(state): State<Arc<AppState>>,
     Json(payload): Json<AttestationRequest>,
 ) -> Json<String> {
-    let blob = payload.blob;
-
-    let ct_len = MlKem768Ciphertext::BYTE_LEN;
-    if blob.len() < ct_len + 12 {
-        return Json("Invalid blob: too short".to_string());
-    }
-
-    let ciphertext_bytes: [u8; MlKem768Ciphertext::BYTE_LEN] = blob[..ct_len].try_into().unwrap();
-    let ciphertext = MlKem768Ciphertext::from_bytes(ciphertext_bytes);
-    let nonce = Nonce::from_slice(&blob[ct_len..ct_len + 12]);
-    let mut encrypted_payload = blob[ct_len + 12..].to_vec();
-
-    // Decapsulate with server SK
-    let shared_secret: MlKem768SharedSecret = MlKem768::decapsulate(&ciphertext, &state.server_sk);
-    let aes_key = Zeroizing::new(*shared_secret.as_bytes());
-    let cipher = Aes256Gcm::new_from_slice(&aes_key).unwrap();
-
-    // Decrypt payload
-    if cipher.decrypt_in_place(nonce, b"", &mut encrypted_payload).is_err() {
-        return Json("Decryption failed — invalid blob or key".to_string());
-    }
+    // Parsed key zeroizes itself on drop — only the locked SecretBuffer outlives the request
+    let server_sk = match pq::kem_secret_key(&state.server_sk) {
+        Ok(sk) => sk,
+        Err(_) => return Json("Server key unavailable".to_string()),
+    };
 
-    // Parse payload: signed report JSON bytes + detached signature
-    let sig_len = 3296;  // ML-DSA-65 typical sig size — adjust dynamically if needed
-    if encrypted_payload.len() < sig_len {
-        return Json("Payload too short for signature".to_string());
+    // Decapsulate + decrypt + parse via the shared core (v2 header checks, key schedule, AAD)
+    let opened = match open_attestation_blob(&payload.blob, &server_sk, None) {
+        Ok(o) => o,
+        Err(e) => return Json(format!("Invalid blob: {}", e)),
+    };
+    if opened.signature.is_none() {
+        return Json("Unsigned report rejected".to_string());
     }
 
-    let signed_data = &encrypted_payload[..encrypted_payload.len() - sig_len];
-    let signature_bytes = &encrypted_payload[encrypted_payload.len() - sig_len..];
+    // Signed bytes are the challenged report — unchallenged (replayable) reports are rejected
+    let challenged = match ChallengedReport::parse(&opened.report) {
+        Ok(c) => c,
+        Err(_) => return Json("Report not bound to a server challenge".to_string()),
+    };
 
-    let report_str = String::from_utf8_lossy(signed_data);
-    let report_json: Value = match serde_json::from_str(&report_str) {
+    // Lossy decoding would canonicalize U+FFFD, not the bytes that were signed
+    let report_str = match std::str::from_utf8(&challenged.report) {
+        Ok(s) => s,
+        Err(_) => return Json("Report is not valid UTF-8".to_string()),
+    };
+    let report_json: Value = match serde_json::from_str(report_str) {
         Ok(j) => j,
         Err(_) => return Json("Invalid report JSON".to_string()),
     };
+    // One report, one encoding: signed bytes must already be RFC 8785 canonical
+    if !is_canonical(report_str) {
+        return Json("Report JSON is not canonical (RFC 8785)".to_string());
+    }
 
     // Extract client DSA public key from signed report (bootstrap trust)
     let dsa_pk_base64 = match report_json["dsa_pk_base64"].as_str() {
@@ -89,35 +182,67 @@ async fn verify_attestation(
         Err(_) => return Json("Invalid base64 DSA public key".to_string()),
     };
 
-    let verifying_key = match ml_dsa::VerifyingKey::from_bytes(&dsa_pk_bytes) {
-        Ok(vk) => vk,
-        Err(_) => return Json("Invalid DSA public key format".to_string()),
-    };
+    // Keys retired by a rotation can no longer vouch for reports
+    let fingerprint = key_id(&dsa_pk_bytes);
+    if state
+        .registry
+        .lock()
+        .unwrap()
+        .retired
+        .contains(&fingerprint)
+    {
+        return Json("Device key retired by rotation — report rejected".to_string());
+    }
 
     // Verify detached signature
-    let signature = match Signature::from_bytes(signature_bytes) {
-        Ok(sig) => sig,
-        Err(_) => return Json("Invalid signature format".to_string()),
+    match signature_status(
+        &opened.report,
+        opened.signature.as_deref(),
+        Some(&dsa_pk_bytes),
+    ) {
+        Ok(SignatureStatus::Valid) => {}
+        Ok(_) => return Json("ML-DSA signature verification failed — forged report".to_string()),
+        Err(e) => return Json(format!("Signature check failed: {}", e)),
+    }
+
+    // The signed report body must match the published schema before any of its fields are used
+    if let Err(mut errors) = state.report_schema.validate(&report_json["report"]) {
+        let reason = errors.next().map(|e| e.to_string()).unwrap_or_default();
+        return Json(format!("Report does not match schema: {}", reason));
+    }
+    let report = match report_from_json(report_json["report"].to_string()) {
+        Ok(r) => r,
+        Err(e) => return Json(format!("Invalid report: {}", e)),
     };
 
-    if verifying_key.verify(signed_data, &signature).is_err() {
-        return Json("ML-DSA signature verification failed — forged report".to_string());
+    // Signature covers the nonce — now burn it (single use, unexpired)
+    if let Err(e) = state
+        .challenges
+        .lock()
+        .unwrap()
+        .consume(&challenged.nonce, unix_now())
+    {
+        return Json(format!("Challenge rejected: {}", e));
     }
 
     // Signature valid — trust report
-    let play_token = match report_json["play_token"].as_str() {
-        Some(t) if !t.is_empty() && t != "null_token" => t,
+    let play_token = match report.play_token.as_str() {
+        t if !t.is_empty() && t != "null_token" => t,
         _ => return Json("Missing valid Play Integrity token".to_string()),
     };
 
     // Server-side Play Integrity verification
-    let verify_url = format!("https://playintegrity.googleapis.com/v1/{}:decodeIntegrityToken", state.package_name);
+    let verify_url = format!(
+        "https://playintegrity.googleapis.com/v1/{}:decodeIntegrityToken",
+        state.package_name
+    );
 
     let verify_body = TokenVerifyRequest {
         integrity_token: play_token.to_string(),
     };
 
-    let response = state.http_client
+    let response = state
+        .http_client
         .post(&verify_url)
         .bearer_auth(&state.play_api_key)
         .json(&verify_body)
@@ -133,9 +258,31 @@ async fn verify_attestation(
 
             let payload = verify_resp.token_payload;
 
+            // Play token must have been requested for this very challenge
+            let expected_hash = play_request_hash(&challenged.nonce);
+            if payload["requestDetails"]["requestHash"].as_str() != Some(expected_hash.as_str()) {
+                return Json("Play Integrity token not bound to challenge".to_string());
+            }
+
+            // Unseen keys are enrolled at epoch 0 (trust on first use) — only once the report has
+            // burned a live challenge and its Play token answers that same challenge
+            state
+                .registry
+                .lock()
+                .unwrap()
+                .enrolled
+                .entry(fingerprint)
+                .or_insert_with(|| Enrollment {
+                    dsa_pk: dsa_pk_bytes.clone(),
+                    epoch: 0,
+                });
+
             let device_ok = payload["deviceIntegrity"]["deviceRecognitionVerdict"]
                 .as_array()
-                .map(|arr| arr.iter().any(|v| v.as_str() == Some("MEETS_DEVICE_INTEGRITY")))
+                .map(|arr| {
+                    arr.iter()
+                        .any(|v| v.as_str() == Some("MEETS_DEVICE_INTEGRITY"))
+                })
                 .unwrap_or(false);
 
             let app_ok = payload["appIntegrity"]["appRecognitionVerdict"]
@@ -159,126 +306,131 @@ async fn verify_attestation(
     ))
 }
 
-#[tokio::main]
-async fn main() {
-    // Load server ML-KEM secret key (env var hex/base64 mercy)
-    let server_sk_hex = std::env::var("SERVER_PQ_SK_HEX").expect("SERVER_PQ_SK_HEX env required");
-    let server_sk_bytes = hex::decode(server_sk_hex).expect("Invalid hex SK");
-    let server_sk = MlKem768SecretKey::from_bytes(&server_sk_bytes.try_into().unwrap()).unwrap();
-
-    // Play Integrity API key (OAuth bearer)
-    let play_api_key = std::env::var("PLAY_INTEGRITY_API_KEY").expect("PLAY API key required");
-
-    let state = Arc::new(AppState {
-        server_sk,
-        http_client: Client::new(),
-        play_api_key,
-        package_name: "com.mercyshieldplus".to_string(),
-    });
-
-    let app = Router::new()
-        .route("/verify", post(verify_attestation))
-        .with_state(state);
-
-    let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
-    println!("Mercy Verifier eternal listening on {}", addr);
-    axum::Server::bind(&addr).serve(app.into_make_service()).await.unwrap();
-}        None => return Json("Missing client DSA public key in report".to_string()),
-    };
-
-    let dsa_pk_bytes = match BASE64.decode(dsa_pk_base64) {
-        Ok(b) => b,
-        Err(_) => return Json("Invalid base64 DSA public key".to_string()),
-    };
-
-    let verifying_key = match ml_dsa::VerifyingKey::from_bytes(&dsa_pk_bytes) {
-        Ok(vk) => vk,
-        Err(_) => return Json("Invalid DSA public key format".to_string()),
+async fn rotate_device_key(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<RotationRequest>,
+) -> Json<String> {
+    let statement = match RotationStatement::parse(&payload.statement) {
+        Ok(s) => s,
+        Err(e) => return Json(format!("Invalid rotation statement: {}", e)),
     };
 
-    // Verify signature on signed_data (report JSON bytes)
-    let signature = match Signature::from_bytes(signature_bytes) {
-        Ok(sig) => sig,
-        Err(_) => return Json("Invalid signature format".to_string()),
+    let old_fingerprint = key_id(&statement.old_pk);
+    let mut registry = state.registry.lock().unwrap();
+    let Some(enrollment) = registry.enrolled.get(&old_fingerprint) else {
+        return Json("Rotation rejected: outgoing key is not enrolled".to_string());
     };
 
-    if verifying_key.verify(signed_data, &signature).is_err() {
-        return Json("ML-DSA signature verification failed — forged report".to_string());
+    // Old key must be the enrolled one at exactly epoch + 1, and both keys must have signed
+    if let Err(e) = verify_rotation(&statement, &enrollment.dsa_pk, enrollment.epoch) {
+        return Json(format!("Rotation rejected: {}", e));
     }
 
-    // Signature valid → trust report contents (including PK bootstrap)
-    let play_token = match report_json["play_token"].as_str() {
-        Some(t) if !t.is_empty() && t != "null_token" => t,
-        _ => return Json("Missing valid Play Integrity token after sig verify".to_string()),
-    };
-
-    // Server-side Play token verification
-    let verify_url = format!("https://playintegrity.googleapis.com/v1/{}:decodeIntegrityToken", state.package_name);
+    let new_fingerprint = key_id(&statement.new_pk);
+    if registry.retired.contains(&new_fingerprint)
+        || registry.enrolled.contains_key(&new_fingerprint)
+    {
+        return Json("Rotation rejected: incoming key already known".to_string());
+    }
+    registry.enrolled.remove(&old_fingerprint);
+    registry.retired.insert(old_fingerprint);
+    registry.enrolled.insert(
+        new_fingerprint,
+        Enrollment {
+            dsa_pk: statement.new_pk,
+            epoch: statement.epoch,
+        },
+    );
+
+    Json(format!("Device key rotated ✓ — epoch {}", statement.epoch))
+}
 
-    let verify_body = TokenVerifyRequest {
-        integrity_token: play_token.to_string(),
+async fn verify_signatures(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<BatchVerifyRequest>,
+) -> Result<Json<Vec<BatchVerifyResult>>, Json<String>> {
+    let Some(kind) = MessageKind::from_name(&payload.kind) else {
+        return Err(Json(format!("Unknown message kind: {}", payload.kind)));
     };
+    if payload.items.len() > MAX_BATCH_ITEMS {
+        return Err(Json(format!("Batch too large (max {})", MAX_BATCH_ITEMS)));
+    }
 
-    let response = state.http_client
-        .post(&verify_url)
-        .bearer_auth(&state.play_api_key)
-        .json(&verify_body)
-        .send()
-        .await;
-
-    let play_verdict = match response {
-        Ok(resp) if resp.status().is_success() => {
-            let verify_resp: TokenVerifyResponse = match resp.json().await {
-                Ok(v) => v,
-                Err(_) => return Json("Failed to parse Play response".to_string()),
-            };
-
-            let payload = verify_resp.token_payload;
-
-            let device_ok = payload["deviceIntegrity"]["deviceRecognitionVerdict"]
-                .as_array()
-                .map(|arr| arr.iter().any(|v| v.as_str() == Some("MEETS_DEVICE_INTEGRITY")))
-                .unwrap_or(false);
-
-            let app_ok = payload["appIntegrity"]["appRecognitionVerdict"]
-                .as_str()
-                .map(|s| s == "PLAY_RECOGNIZED")
-                .unwrap_or(false);
-
-            if device_ok && app_ok {
-                "Genuine Eternal"
-            } else {
-                "Anomaly Detected"
-            }
-        }
-        _ => "Play API verification failed",
-    };
+    let jobs = payload.items.into_iter().map(move |item| VerifyJob {
+        dsa_pk: item.dsa_pk,
+        message: item.message,
+        signature: item.signature,
+        kind,
+    });
 
-    Json(format!(
-        "Signature Valid ✓\nPlay Verdict: {}\nReport: {}",
-        play_verdict, report_str
+    // Off the async runtime — the rayon pool does the fan-out
+    let verifier = state.batch_verifier.clone();
+    let results = tokio::task::spawn_blocking(move || verifier.verify_batch(jobs))
+        .await
+        .map_err(|_| Json("Batch verification aborted".to_string()))?;
+
+    Ok(Json(
+        results
+            .into_iter()
+            .map(|r| match r {
+                Ok(valid) => BatchVerifyResult { valid, error: None },
+                Err(e) => BatchVerifyResult {
+                    valid: false,
+                    error: Some(e.to_string()),
+                },
+            })
+            .collect(),
     ))
 }
 
 #[tokio::main]
 async fn main() {
-    let server_sk_bytes = std::env::var("SERVER_PQ_SK").expect("SERVER_PQ_SK required").into_bytes();
-    let server_sk = MlKem768SecretKey::from_bytes(&server_sk_bytes.try_into().unwrap()).unwrap();
+    // Load server ML-KEM secret key (PKCS#8 PEM, id-alg-ml-kem-768 — mistyped keys rejected)
+    // Every intermediate copy (PEM, DER, raw) is wiped; the key itself lives in a SecretBuffer
+    let server_sk_pem =
+        Zeroizing::new(std::env::var("SERVER_PQ_SK_PEM").expect("SERVER_PQ_SK_PEM env required"));
+    let server_sk = pem_to_der(PEM_PRIVATE_LABEL, &server_sk_pem)
+        .map(Zeroizing::new)
+        .and_then(|der| private_key_from_der(KeyAlgorithm::MlKem768, &der))
+        .map(Zeroizing::new)
+        .and_then(|raw| SecretBuffer::from_slice(&raw))
+        .expect("SERVER_PQ_SK_PEM must be an ML-KEM-768 PKCS#8 private key");
+    pq::kem_secret_key(&server_sk).expect("SERVER_PQ_SK_PEM is not a valid ML-KEM-768 key");
+    if !server_sk.is_locked() {
+        eprintln!("warning: server key memory could not be mlock'd — raise RLIMIT_MEMLOCK");
+    }
 
+    // Play Integrity API key (OAuth bearer)
     let play_api_key = std::env::var("PLAY_INTEGRITY_API_KEY").expect("PLAY API key required");
 
     let state = Arc::new(AppState {
         server_sk,
+        registry: Mutex::new(Registry::default()),
+        challenges: Mutex::new(ChallengeStore::new(
+            CHALLENGE_TTL_SECS,
+            MAX_OUTSTANDING_CHALLENGES,
+        )),
+        report_schema: JSONSchema::compile(
+            &serde_json::from_str(REPORT_SCHEMA).expect("report schema is JSON"),
+        )
+        .expect("report schema compiles"),
+        batch_verifier: Arc::new(
+            BatchVerifier::new(0, VERIFY_QUEUE_DEPTH).expect("verification thread pool"),
+        ),
         http_client: Client::new(),
         play_api_key,
         package_name: "com.mercyshieldplus".to_string(),
     });
 
     let app = Router::new()
+        .route("/challenge", get(issue_challenge))
         .route("/verify", post(verify_attestation))
+        .route("/rotate", post(rotate_device_key))
+        .route("/verify_signatures", post(verify_signatures))
         .with_state(state);
 
     let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
-    println!("Mercy Verifier eternal on {}", addr);
-    axum::Server::bind(&addr).serve(app.into_make_service()).await.unwrap();
+    println!("Mercy Verifier eternal listening on {}", addr);
+    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    axum::serve(listener, app).await.unwrap();
 }
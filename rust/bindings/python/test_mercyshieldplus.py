@@ -0,0 +1,197 @@
+"""Python-driven checks of libmercyshieldplus: the uniffi bindings and the C ABI (ctypes).
+
+    cd rust
+    cargo build --profile bindings --features capi
+    cargo run --features bindgen-cli --bin uniffi-bindgen -- generate \
+        --library target/bindings/libmercyshieldplus.so --language python --out-dir bindings/python
+    cp target/bindings/libmercyshieldplus.so bindings/python/
+    python3 -m unittest discover -s bindings/python -v
+"""
+import base64
+import ctypes
+import json
+import os
+import unittest
+
+import mercyshieldplus as msp
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+LIB = ctypes.CDLL(os.path.join(HERE, "libmercyshieldplus.so"))
+
+# include/mercyshieldplus.h
+MERCY_STATUS_OK = 0
+MERCY_STATUS_NULL_POINTER = 1
+MERCY_STATUS_INVALID_ARGUMENT = 3
+MERCY_STATUS_INVALID_KEY = 11
+MERCY_MESSAGE_KIND_ATTESTATION_REPORT = 0
+
+BLOB_HEADER_PREFIX = 4 + 1 + 3 + 16  # magic, version, algs, key_id — fixed for a given recipient
+KEM_CT_LEN = 1088
+
+# What the C ABI's flat (files, props, magisk) inputs map to
+SU_EVIDENCE = [
+    msp.Evidence.SUSPICIOUS_FILE(path="/system/xbin/su"),
+    msp.Evidence.MAGISK_INDICATOR(indicator="magisk"),
+]
+
+
+class MercyBuffer(ctypes.Structure):
+    _fields_ = [("data", ctypes.POINTER(ctypes.c_uint8)), ("len", ctypes.c_size_t)]
+
+
+u8_p = ctypes.POINTER(ctypes.c_uint8)
+LIB.mercy_secure_attestation_blob.argtypes = [
+    u8_p, ctypes.c_size_t, u8_p, ctypes.c_size_t, u8_p, ctypes.c_size_t, ctypes.POINTER(MercyBuffer),
+]
+LIB.mercy_secure_attestation_blob.restype = ctypes.c_int32
+LIB.mercy_verify_data.argtypes = [
+    u8_p, ctypes.c_size_t, u8_p, ctypes.c_size_t, u8_p, ctypes.c_size_t, ctypes.c_uint32,
+    ctypes.POINTER(ctypes.c_bool),
+]
+LIB.mercy_verify_data.restype = ctypes.c_int32
+LIB.mercy_evaluate_integrity.argtypes = [
+    ctypes.POINTER(ctypes.c_char_p), ctypes.c_size_t, ctypes.POINTER(ctypes.c_char_p), ctypes.c_size_t,
+    ctypes.c_bool, ctypes.c_char_p, ctypes.POINTER(ctypes.c_void_p),
+]
+LIB.mercy_evaluate_integrity.restype = ctypes.c_int32
+LIB.mercy_buffer_free.argtypes = [MercyBuffer]
+LIB.mercy_buffer_free.restype = None
+LIB.mercy_string_free.argtypes = [ctypes.c_void_p]
+LIB.mercy_string_free.restype = None
+
+
+def c_bytes(data):
+    return (ctypes.c_uint8 * len(data)).from_buffer_copy(data), len(data)
+
+
+def c_strings(items):
+    return (ctypes.c_char_p * len(items))(*[s.encode() for s in items]), len(items)
+
+
+class UniffiBindingsTest(unittest.TestCase):
+    def test_evaluate_integrity_report(self):
+        report = msp.evaluate_integrity(SU_EVIDENCE, "null_token")
+        self.assertEqual(report.verdict, msp.IntegrityVerdict.COMPROMISED)
+        self.assertEqual(report.risk_score, 95)
+        self.assertEqual(report.evidence[-1], msp.Evidence.PLAY_TOKEN_MISSING(reason="null_token"))
+        self.assertEqual(msp.evidence_severity(report.evidence[0]), msp.Severity.HIGH)
+
+        parsed = json.loads(msp.report_to_json(report))
+        self.assertEqual((parsed["schema_version"], parsed["verdict"]), (1, "Compromised"))
+        self.assertEqual(parsed["play_token"], "null_token")
+        self.assertIn({"type": "suspicious_file", "path": "/system/xbin/su"}, parsed["evidence"])
+        self.assertEqual((parsed["policy_id"], parsed["policy_version"]), ("mercyshieldplus-default", 3))
+
+    def test_report_json_round_trip(self):
+        hostile = '","verdict":"Genuine","x":"\u0000\n</script>'
+        report = msp.evaluate_integrity([msp.Evidence.SUSPICIOUS_FILE(path=hostile)], hostile)
+        encoded = msp.report_to_json(report)
+        self.assertEqual(json.loads(encoded)["play_token"], hostile)
+        self.assertEqual(msp.report_from_json(encoded), report)
+
+        with self.assertRaises(msp.MercyError.InvalidReport):
+            msp.report_from_json(encoded.replace('"risk_score"', '"trusted":true,"risk_score"'))
+        with self.assertRaises(msp.MercyError.UnsupportedVersion):
+            msp.report_from_json(encoded.replace('"schema_version":1', '"schema_version":9'))
+
+    def test_canonical_json(self):
+        gson_style = '{"timestamp": 1.0E12, "report": {"b": "\u00e9", "a": [1, 2.50]}}'
+        self.assertEqual(msp.canonicalize_json(gson_style), '{"report":{"a":[1,2.5],"b":"\u00e9"},"timestamp":1000000000000}')
+        with self.assertRaises(msp.MercyError.InvalidJson):
+            msp.canonicalize_json('{"verdict":"Compromised","verdict":"Genuine"}')
+
+    def test_custom_risk_policy(self):
+        policy = json.loads(msp.default_risk_policy_json())
+        policy.update(id="strict", version=2, hard_fail=["magisk"])
+        magisk = [msp.Evidence.MAGISK_INDICATOR(indicator="magisk")]
+        report = msp.evaluate_integrity_with_policy(magisk, "tok", json.dumps(policy))
+        self.assertEqual(report.verdict, msp.IntegrityVerdict.COMPROMISED)
+        self.assertEqual((report.risk_score, report.policy_id, report.policy_version), (20, "strict", 2))
+        with self.assertRaises(msp.MercyError.InvalidPolicy):
+            msp.evaluate_integrity_with_policy(magisk, "tok", "{}")
+
+    def test_blob_sealed_to_server_key(self):
+        keys = msp.generate_pq_keypair()
+        blob = base64.b64decode(msp.pq_secure_attestation_blob(b'{"risk":0}', keys.kem_pk_b64, None))
+        self.assertEqual(blob[:5], b"MSPB\x02")
+        self.assertGreater(len(blob), KEM_CT_LEN + 12 + 16)
+
+    def test_ledger_export_and_proof_bounds(self):
+        ledger = msp.PqLedger.from_export(msp.PqLedger().export())
+        self.assertTrue(ledger.is_empty())
+        self.assertEqual(ledger.consistency_proof(0, 0).path, [])
+        with self.assertRaises(msp.MercyError.InvalidTreeSize):
+            ledger.inclusion_proof(0, 0)
+        with self.assertRaises(msp.MercyError.InvalidBlob):
+            msp.PqLedger.from_export(b"MSLX")
+
+    def test_ledger_append_survives_export(self):
+        keys = msp.generate_pq_keypair()
+        ledger = msp.PqLedger()
+        head = ledger.append(b'{"verdict":"Genuine","risk":0}', 1700000000000, keys.dsa_sk_b64)
+        restored = msp.PqLedger.from_export(ledger.export())
+        self.assertIsNone(restored.verify(keys.dsa_pk_b64).first_invalid)
+        [record] = restored.records()
+        self.assertEqual(record.report, '{"risk":0,"verdict":"Genuine"}')
+        self.assertEqual(base64.b64encode(record.entry_hash).decode(), head)
+
+    def test_errors_raise_mercy_error(self):
+        kind = msp.MessageKind.LEDGER_ENTRY
+        with self.assertRaises(msp.MercyError.InvalidBase64):
+            msp.pq_verify_data("%%", b"x", "AAAA", kind)
+        with self.assertRaises(msp.MercyError.InvalidKey):
+            msp.pq_verify_data(base64.b64encode(b"\x01" * 3).decode(), b"x", "AAAA", kind)
+
+
+class CAbiTest(unittest.TestCase):
+    def test_evaluate_integrity_matches_uniffi(self):
+        files, n_files = c_strings(["/system/xbin/su"])
+        out = ctypes.c_void_p()
+        status = LIB.mercy_evaluate_integrity(files, n_files, None, 0, True, b"null_token", ctypes.byref(out))
+        self.assertEqual(status, MERCY_STATUS_OK)
+        try:
+            c_json = ctypes.string_at(out.value).decode()
+        finally:
+            LIB.mercy_string_free(out)
+
+        expected = msp.report_to_json(msp.evaluate_integrity(SU_EVIDENCE, "null_token"))
+        self.assertEqual(c_json, expected)
+
+    def test_secure_attestation_blob_ownership(self):
+        kem_pk = base64.b64decode(msp.generate_pq_keypair().kem_pk_b64)
+        report, report_len = c_bytes(b'{"risk":0}')
+        pk, pk_len = c_bytes(kem_pk)
+
+        out = MercyBuffer()
+        status = LIB.mercy_secure_attestation_blob(report, report_len, pk, pk_len, None, 0, ctypes.byref(out))
+        self.assertEqual(status, MERCY_STATUS_OK)
+        try:
+            blob = ctypes.string_at(out.data, out.len)
+        finally:
+            LIB.mercy_buffer_free(out)
+
+        # Same recipient → same header prefix as the uniffi path
+        via_uniffi = base64.b64decode(msp.pq_secure_attestation_blob(b"{}", base64.b64encode(kem_pk).decode(), None))
+        self.assertEqual(blob[:BLOB_HEADER_PREFIX], via_uniffi[:BLOB_HEADER_PREFIX])
+
+    def test_verify_data_status_codes(self):
+        pk, pk_len = c_bytes(b"\x01" * 3)
+        msg, msg_len = c_bytes(b"report")
+        sig, sig_len = c_bytes(b"\x00" * 5)
+        valid = ctypes.c_bool(True)
+
+        kind = MERCY_MESSAGE_KIND_ATTESTATION_REPORT
+        status = LIB.mercy_verify_data(pk, pk_len, msg, msg_len, sig, sig_len, kind, ctypes.byref(valid))
+        self.assertEqual(status, MERCY_STATUS_INVALID_KEY)
+        self.assertTrue(valid.value, "out-param must be untouched on error")
+
+        status = LIB.mercy_verify_data(pk, pk_len, msg, msg_len, sig, sig_len, 99, ctypes.byref(valid))
+        self.assertEqual(status, MERCY_STATUS_INVALID_ARGUMENT)
+        status = LIB.mercy_verify_data(None, 8, msg, msg_len, sig, sig_len, kind, ctypes.byref(valid))
+        self.assertEqual(status, MERCY_STATUS_NULL_POINTER)
+        status = LIB.mercy_verify_data(pk, pk_len, msg, msg_len, sig, sig_len, kind, None)
+        self.assertEqual(status, MERCY_STATUS_NULL_POINTER)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -1,3 +0,0 @@
-fn main() {
-    uniffi::generate_scaffolding("./src/lib.rs").expect("uniFFI scaffolding generation failed — fortress mercy");
-}
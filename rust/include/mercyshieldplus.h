@@ -0,0 +1,138 @@
+/* MercyShieldPlus core C ABI — generated by cbindgen from src/capi.rs, do not edit */
+
+#ifndef MERCYSHIELDPLUS_H
+#define MERCYSHIELDPLUS_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result code of every `mercy_*` call
+enum MercyStatus
+#ifdef __cplusplus
+  : int32_t
+#endif // __cplusplus
+ {
+  MERCY_STATUS_OK = 0,
+  // A required pointer argument was NULL
+  MERCY_STATUS_NULL_POINTER = 1,
+  // A `char *` argument was not valid UTF-8
+  MERCY_STATUS_INVALID_UTF8 = 2,
+  // An enum-valued argument was out of range
+  MERCY_STATUS_INVALID_ARGUMENT = 3,
+  // The call panicked — treat as an internal bug (`panic = "abort"` builds abort instead)
+  MERCY_STATUS_PANIC = 4,
+  MERCY_STATUS_INVALID_BASE64 = 10,
+  MERCY_STATUS_INVALID_KEY = 11,
+  MERCY_STATUS_INVALID_SIGNATURE = 12,
+  MERCY_STATUS_CRYPTO_FAILURE = 13,
+  MERCY_STATUS_INVALID_BLOB = 14,
+  MERCY_STATUS_UNSUPPORTED_VERSION = 15,
+  MERCY_STATUS_UNSUPPORTED_ALGORITHM = 16,
+  MERCY_STATUS_WRONG_RECIPIENT = 17,
+  MERCY_STATUS_DECRYPTION_FAILED = 18,
+  MERCY_STATUS_STREAM_TRUNCATED = 19,
+  MERCY_STATUS_KEY_TYPE_MISMATCH = 20,
+  MERCY_STATUS_INVALID_STATE = 21,
+  MERCY_STATUS_STALE_EPOCH = 22,
+  MERCY_STATUS_ROTATION_REJECTED = 23,
+  MERCY_STATUS_INVALID_BACKUP_PARAMS = 24,
+  MERCY_STATUS_CHALLENGE_UNKNOWN = 25,
+  MERCY_STATUS_CHALLENGE_EXPIRED = 26,
+  MERCY_STATUS_SECURE_MEMORY = 27,
+  MERCY_STATUS_INVALID_TREE_SIZE = 28,
+  MERCY_STATUS_INVALID_POLICY = 29,
+  MERCY_STATUS_INVALID_REPORT = 30,
+  MERCY_STATUS_INVALID_JSON = 31,
+  MERCY_STATUS_CHALLENGE_STORE_FULL = 32,
+};
+#ifndef __cplusplus
+typedef int32_t MercyStatus;
+#endif // __cplusplus
+
+// `MessageKind` as passed over the C ABI (a plain `uint32_t`, validated on entry)
+enum MercyMessageKind
+#ifdef __cplusplus
+  : uint32_t
+#endif // __cplusplus
+ {
+  MERCY_MESSAGE_KIND_ATTESTATION_REPORT = 0,
+  MERCY_MESSAGE_KIND_KEY_ROTATION = 1,
+  MERCY_MESSAGE_KIND_LOG_EXPORT = 2,
+  MERCY_MESSAGE_KIND_LEDGER_ENTRY = 3,
+  MERCY_MESSAGE_KIND_TREE_HEAD = 4,
+};
+#ifndef __cplusplus
+typedef uint32_t MercyMessageKind;
+#endif // __cplusplus
+
+// Rust-allocated byte buffer — release with `mercy_buffer_free`
+typedef struct MercyBuffer {
+  uint8_t *data;
+  size_t len;
+} MercyBuffer;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Release a buffer returned by this library. Passing an empty buffer is a no-op.
+//
+// # Safety
+// `buffer` must come from a `mercy_*` out-param and must not be used (or freed) again.
+void mercy_buffer_free(MercyBuffer buffer);
+
+// Score the collected evidence and return the report as a JSON string (see `report_to_json`).
+// Props are `name=value` strings; each input becomes one typed `Evidence` finding.
+//
+// # Safety
+// `suspicious_files` / `suspicious_props` point to `*_count` NUL-terminated strings (NULL allowed
+// when the count is 0); `play_token` is NUL-terminated; `out_report_json` must be writable.
+MercyStatus mercy_evaluate_integrity(const char *const *suspicious_files,
+                                     size_t suspicious_files_count,
+                                     const char *const *suspicious_props,
+                                     size_t suspicious_props_count,
+                                     bool magisk_indicators,
+                                     const char *play_token,
+                                     char **out_report_json);
+
+// Seal a v2 attestation blob to the server KEM public key (raw bytes in, raw blob out).
+// `dsa_sk == NULL` produces an unsigned blob; otherwise the report is signed with that ML-DSA-65 SK.
+//
+// # Safety
+// Pointer/length pairs must describe readable memory; `out_blob` must be writable.
+MercyStatus mercy_secure_attestation_blob(const uint8_t *report,
+                                          size_t report_len,
+                                          const uint8_t *server_kem_pk,
+                                          size_t server_kem_pk_len,
+                                          const uint8_t *dsa_sk,
+                                          size_t dsa_sk_len,
+                                          MercyBuffer *out_blob);
+
+// Release a string returned by this library. Passing NULL is a no-op.
+//
+// # Safety
+// `s` must come from a `mercy_*` out-param and must not be used (or freed) again.
+void mercy_string_free(char *s);
+
+// Verify an ML-DSA-65 signature made under `kind` (a `MercyMessageKind` value).
+// A well-formed but wrong signature is `MERCY_STATUS_OK` with `*out_valid == false`.
+//
+// # Safety
+// Pointer/length pairs must describe readable memory; `out_valid` must be writable.
+MercyStatus mercy_verify_data(const uint8_t *dsa_pk,
+                              size_t dsa_pk_len,
+                              const uint8_t *message,
+                              size_t message_len,
+                              const uint8_t *signature,
+                              size_t signature_len,
+                              uint32_t kind,
+                              bool *out_valid);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* MERCYSHIELDPLUS_H */
@@ -0,0 +1,6 @@
+// rust/report-schema.rs — prints the integrity report JSON Schema (keep the committed copy current)
+//
+//   cargo run --features schema --bin report-schema > schema/integrity-report.schema.json
+fn main() {
+    print!("{}", mercyshieldplus::integrity::report_json_schema());
+}
@@ -0,0 +1,449 @@
+// rust/src/backup.rs — Passphrase-Sealed Identity Backup Eternal (Argon2id → AES-256-GCM)
+//
+// Backup layout (all integers big-endian):
+//   magic        4   b"MSPK"
+//   version      1   BACKUP_VERSION
+//   kdf_alg      1   KdfAlgorithm (Argon2id v1.3)
+//   aead_alg     1   AeadAlgorithm
+//   memory_kib   4   Argon2 m_cost
+//   iterations   4   Argon2 t_cost
+//   parallelism  4   Argon2 p_cost
+//   salt        16   random per backup
+//   nonce       12   AEAD nonce
+//   ------------- everything above is AEAD associated data -------------
+//   sealed     rest  AEAD(kem_sk_len u16 || kem_sk || dsa_sk_len u16 || dsa_sk || dsa_epoch u64)
+//
+// Stored KDF parameters are range-checked before Argon2 runs, so a crafted backup can neither
+// downgrade the work factor nor make the device allocate more than 256 MiB.
+use aes_gcm::{AeadInPlace, Aes256Gcm, KeyInit, Nonce};
+use alloc::string::String;
+use alloc::vec::Vec;
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use rand_core::{CryptoRngCore, OsRng};
+use zeroize::Zeroizing;
+
+use crate::blob::{AeadAlgorithm, Reader, NONCE_LEN};
+use crate::error::{MercyError, MercyResult};
+use crate::kdf::KEY_LEN;
+use crate::key_encoding::KeyAlgorithm;
+use crate::secret::SecretBuffer;
+
+pub const BACKUP_MAGIC: [u8; 4] = *b"MSPK";
+pub const BACKUP_VERSION: u8 = 1;
+pub const SALT_LEN: usize = 16;
+
+const MIN_MEMORY_KIB: u32 = 19 * 1024; // OWASP Argon2id floor
+const MAX_MEMORY_KIB: u32 = 256 * 1024; // stays within a mid-range phone's app heap
+const MIN_ITERATIONS: u32 = 2;
+const MAX_ITERATIONS: u32 = 16;
+const MAX_PARALLELISM: u32 = 16;
+
+/// Passphrase KDF named in the backup header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum KdfAlgorithm {
+    Argon2id = 1,
+}
+
+impl TryFrom<u8> for KdfAlgorithm {
+    type Error = MercyError;
+    fn try_from(id: u8) -> MercyResult<Self> {
+        match id {
+            1 => Ok(KdfAlgorithm::Argon2id),
+            _ => Err(MercyError::UnsupportedAlgorithm),
+        }
+    }
+}
+
+/// Argon2id cost parameters (stored in the backup header)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct KdfParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl KdfParams {
+    /// 64 MiB, 3 passes, 1 lane — a few hundred ms on a mid-range phone
+    pub const RECOMMENDED: KdfParams = KdfParams {
+        memory_kib: 64 * 1024,
+        iterations: 3,
+        parallelism: 1,
+    };
+
+    pub fn validate(&self) -> MercyResult<()> {
+        let memory_ok = (MIN_MEMORY_KIB..=MAX_MEMORY_KIB).contains(&self.memory_kib);
+        let iterations_ok = (MIN_ITERATIONS..=MAX_ITERATIONS).contains(&self.iterations);
+        let parallelism_ok = (1..=MAX_PARALLELISM).contains(&self.parallelism);
+        if memory_ok && iterations_ok && parallelism_ok {
+            Ok(())
+        } else {
+            Err(MercyError::InvalidBackupParams)
+        }
+    }
+}
+
+/// Secret keys carried by a backup
+#[derive(Debug)]
+pub struct BackupKeys {
+    pub kem_sk: SecretBuffer,
+    pub dsa_sk: SecretBuffer,
+    /// Rotation epoch of `dsa_sk` (see `rotation.rs`)
+    pub dsa_epoch: u64,
+}
+
+impl BackupKeys {
+    fn encode(&self) -> Zeroizing<Vec<u8>> {
+        let mut out = Zeroizing::new(Vec::with_capacity(
+            12 + self.kem_sk.len() + self.dsa_sk.len(),
+        ));
+        out.extend_from_slice(&(self.kem_sk.len() as u16).to_be_bytes());
+        out.extend_from_slice(&self.kem_sk);
+        out.extend_from_slice(&(self.dsa_sk.len() as u16).to_be_bytes());
+        out.extend_from_slice(&self.dsa_sk);
+        out.extend_from_slice(&self.dsa_epoch.to_be_bytes());
+        out
+    }
+
+    fn parse(plaintext: &[u8]) -> MercyResult<Self> {
+        let mut r = Reader::new(plaintext);
+        let kem_len = r.u16()? as usize;
+        let kem_sk = SecretBuffer::from_slice(r.take(kem_len)?)?;
+        let dsa_len = r.u16()? as usize;
+        let dsa_sk = SecretBuffer::from_slice(r.take(dsa_len)?)?;
+        let dsa_epoch = r.u64()?;
+        if !r.is_empty() {
+            return Err(MercyError::InvalidBlob);
+        }
+        let keys = BackupKeys {
+            kem_sk,
+            dsa_sk,
+            dsa_epoch,
+        };
+        keys.check_lengths()?;
+        Ok(keys)
+    }
+
+    fn check_lengths(&self) -> MercyResult<()> {
+        if self.kem_sk.len() != KeyAlgorithm::MlKem768.private_key_len()
+            || self.dsa_sk.len() != KeyAlgorithm::MlDsa65.private_key_len()
+        {
+            return Err(MercyError::InvalidKey);
+        }
+        Ok(())
+    }
+}
+
+fn derive_backup_key(
+    passphrase: &[u8],
+    salt: &[u8],
+    params: &KdfParams,
+) -> MercyResult<Zeroizing<[u8; KEY_LEN]>> {
+    if passphrase.is_empty() {
+        return Err(MercyError::InvalidBackupParams);
+    }
+    params.validate()?;
+
+    let argon_params = Params::new(
+        params.memory_kib,
+        params.iterations,
+        params.parallelism,
+        Some(KEY_LEN),
+    )
+    .map_err(|_| MercyError::InvalidBackupParams)?;
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, argon_params)
+        .hash_password_into(passphrase, salt, &mut *key)
+        .map_err(|_| MercyError::CryptoFailure)?;
+    Ok(key)
+}
+
+fn encode_header(params: &KdfParams, salt: &[u8; SALT_LEN], nonce: &[u8; NONCE_LEN]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(7 + 12 + SALT_LEN + NONCE_LEN);
+    out.extend_from_slice(&BACKUP_MAGIC);
+    out.push(BACKUP_VERSION);
+    out.push(KdfAlgorithm::Argon2id as u8);
+    out.push(AeadAlgorithm::Aes256Gcm as u8);
+    out.extend_from_slice(&params.memory_kib.to_be_bytes());
+    out.extend_from_slice(&params.iterations.to_be_bytes());
+    out.extend_from_slice(&params.parallelism.to_be_bytes());
+    out.extend_from_slice(salt);
+    out.extend_from_slice(nonce);
+    out
+}
+
+/// Seal secret keys under a passphrase — returns the raw backup bytes
+pub fn seal_key_backup(
+    passphrase: &[u8],
+    keys: &BackupKeys,
+    params: &KdfParams,
+) -> MercyResult<Vec<u8>> {
+    seal_key_backup_with_rng(passphrase, keys, params, &mut OsRng)
+}
+
+/// `seal_key_backup` with an injected RNG (salt, then nonce)
+pub fn seal_key_backup_with_rng(
+    passphrase: &[u8],
+    keys: &BackupKeys,
+    params: &KdfParams,
+    rng: &mut impl CryptoRngCore,
+) -> MercyResult<Vec<u8>> {
+    keys.check_lengths()?;
+
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill_bytes(&mut salt);
+    rng.fill_bytes(&mut nonce);
+
+    let key = derive_backup_key(passphrase, &salt, params)?;
+    let cipher = Aes256Gcm::new((&*key).into());
+
+    let mut backup = encode_header(params, &salt, &nonce);
+    let mut sealed = keys.encode();
+    cipher
+        .encrypt_in_place(Nonce::from_slice(&nonce), &backup, &mut *sealed)
+        .map_err(|_| MercyError::CryptoFailure)?;
+    backup.extend_from_slice(&sealed);
+    Ok(backup)
+}
+
+/// Open a backup — header and KDF parameters are validated before the passphrase is stretched
+pub fn open_key_backup(passphrase: &[u8], backup: &[u8]) -> MercyResult<BackupKeys> {
+    let mut r = Reader::new(backup);
+    if r.take(4)? != BACKUP_MAGIC {
+        return Err(MercyError::InvalidBlob);
+    }
+    if r.u8()? != BACKUP_VERSION {
+        return Err(MercyError::UnsupportedVersion);
+    }
+    KdfAlgorithm::try_from(r.u8()?)?;
+    AeadAlgorithm::try_from(r.u8()?)?;
+    let params = KdfParams {
+        memory_kib: r.u32()?,
+        iterations: r.u32()?,
+        parallelism: r.u32()?,
+    };
+    let salt = r.take(SALT_LEN)?;
+    let nonce = r.take(NONCE_LEN)?;
+    let header_len = r.position();
+    params.validate()?;
+
+    let key = derive_backup_key(passphrase, salt, &params)?;
+    let cipher = Aes256Gcm::new((&*key).into());
+
+    let mut plaintext = Zeroizing::new(backup[header_len..].to_vec());
+    cipher
+        .decrypt_in_place(
+            Nonce::from_slice(nonce),
+            &backup[..header_len],
+            &mut *plaintext,
+        )
+        .map_err(|_| MercyError::DecryptionFailed)?;
+
+    BackupKeys::parse(&plaintext)
+}
+
+/// Restored device identity (base64 secret keys + DSA rotation epoch)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct RestoredKeys {
+    pub kem_sk_b64: String,
+    pub dsa_sk_b64: String,
+    pub dsa_epoch: u64,
+}
+
+/// "Backup identity" — seal KEM + DSA secret keys under a passphrase (None = recommended params)
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn pq_export_key_backup(
+    passphrase: String,
+    kem_sk_b64: String,
+    dsa_sk_b64: String,
+    dsa_epoch: u64,
+    params: Option<KdfParams>,
+) -> MercyResult<String> {
+    let passphrase = Zeroizing::new(passphrase);
+    let keys = BackupKeys {
+        kem_sk: SecretBuffer::from_base64(kem_sk_b64)?,
+        dsa_sk: SecretBuffer::from_base64(dsa_sk_b64)?,
+        dsa_epoch,
+    };
+    let backup = seal_key_backup(
+        passphrase.as_bytes(),
+        &keys,
+        &params.unwrap_or(KdfParams::RECOMMENDED),
+    )?;
+    Ok(BASE64.encode(backup))
+}
+
+/// "Restore identity" — wrong passphrase or tampered backup is `DecryptionFailed`
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn pq_import_key_backup(passphrase: String, backup_b64: String) -> MercyResult<RestoredKeys> {
+    let passphrase = Zeroizing::new(passphrase);
+    let backup = BASE64
+        .decode(backup_b64)
+        .map_err(|_| MercyError::InvalidBase64)?;
+    let keys = open_key_backup(passphrase.as_bytes(), &backup)?;
+
+    Ok(RestoredKeys {
+        kem_sk_b64: BASE64.encode(&*keys.kem_sk),
+        dsa_sk_b64: BASE64.encode(&*keys.dsa_sk),
+        dsa_epoch: keys.dsa_epoch,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    // Cheapest parameters `validate` accepts — keeps debug-build tests fast
+    const TEST_PARAMS: KdfParams = KdfParams {
+        memory_kib: MIN_MEMORY_KIB,
+        iterations: MIN_ITERATIONS,
+        parallelism: 1,
+    };
+
+    fn test_keys() -> BackupKeys {
+        BackupKeys {
+            kem_sk: SecretBuffer::from_slice(&vec![0x11; KeyAlgorithm::MlKem768.private_key_len()])
+                .unwrap(),
+            dsa_sk: SecretBuffer::from_slice(&vec![0x22; KeyAlgorithm::MlDsa65.private_key_len()])
+                .unwrap(),
+            dsa_epoch: 3,
+        }
+    }
+
+    #[test]
+    fn test_backup_roundtrip() {
+        let backup =
+            seal_key_backup(b"correct horse battery staple", &test_keys(), &TEST_PARAMS).unwrap();
+        assert_eq!(&backup[..4], &BACKUP_MAGIC);
+        let opened = open_key_backup(b"correct horse battery staple", &backup).unwrap();
+        let expected = test_keys();
+        assert_eq!(*opened.kem_sk, *expected.kem_sk);
+        assert_eq!(*opened.dsa_sk, *expected.dsa_sk);
+        assert_eq!(opened.dsa_epoch, expected.dsa_epoch);
+    }
+
+    #[test]
+    fn test_wrong_passphrase_and_tamper_rejected() {
+        let backup = seal_key_backup(b"passphrase", &test_keys(), &TEST_PARAMS).unwrap();
+        assert_eq!(
+            open_key_backup(b"passphrasf", &backup).err(),
+            Some(MercyError::DecryptionFailed)
+        );
+
+        // Header is AAD — bumping iterations within range still breaks authentication
+        let mut tampered = backup.clone();
+        tampered[14] += 1;
+        assert_eq!(
+            open_key_backup(b"passphrase", &tampered).err(),
+            Some(MercyError::DecryptionFailed)
+        );
+
+        let mut tampered = backup;
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert_eq!(
+            open_key_backup(b"passphrase", &tampered).err(),
+            Some(MercyError::DecryptionFailed)
+        );
+    }
+
+    #[test]
+    fn test_stored_params_validated_before_kdf() {
+        let salt = [0u8; SALT_LEN];
+        let nonce = [0u8; NONCE_LEN];
+        let weak = KdfParams {
+            memory_kib: 8,
+            iterations: 1,
+            parallelism: 1,
+        };
+        let huge = KdfParams {
+            memory_kib: u32::MAX,
+            iterations: 3,
+            parallelism: 1,
+        };
+        let gigabyte = KdfParams {
+            memory_kib: 1024 * 1024,
+            iterations: 3,
+            parallelism: 1,
+        };
+        let lanes = KdfParams {
+            memory_kib: MIN_MEMORY_KIB,
+            iterations: 3,
+            parallelism: 0,
+        };
+        for params in [weak, huge, gigabyte, lanes] {
+            let mut backup = encode_header(&params, &salt, &nonce);
+            backup.extend_from_slice(&[0u8; 32]);
+            assert_eq!(
+                open_key_backup(b"pw", &backup).err(),
+                Some(MercyError::InvalidBackupParams),
+                "{:?}",
+                params
+            );
+            assert_eq!(
+                seal_key_backup(b"pw", &test_keys(), &params),
+                Err(MercyError::InvalidBackupParams)
+            );
+        }
+    }
+
+    #[test]
+    fn test_header_and_key_checks() {
+        let backup = seal_key_backup(b"pw", &test_keys(), &TEST_PARAMS).unwrap();
+
+        let mut version = backup.clone();
+        version[4] = 2;
+        assert_eq!(
+            open_key_backup(b"pw", &version).err(),
+            Some(MercyError::UnsupportedVersion)
+        );
+        let mut kdf = backup.clone();
+        kdf[5] = 9;
+        assert_eq!(
+            open_key_backup(b"pw", &kdf).err(),
+            Some(MercyError::UnsupportedAlgorithm)
+        );
+        assert_eq!(
+            open_key_backup(b"pw", &backup[..20]).err(),
+            Some(MercyError::InvalidBlob)
+        );
+
+        assert_eq!(
+            seal_key_backup(b"", &test_keys(), &TEST_PARAMS),
+            Err(MercyError::InvalidBackupParams)
+        );
+        let mut short = test_keys();
+        short.dsa_sk = SecretBuffer::from_slice(&[0x22; 10]).unwrap();
+        assert_eq!(
+            seal_key_backup(b"pw", &short, &TEST_PARAMS),
+            Err(MercyError::InvalidKey)
+        );
+    }
+
+    #[test]
+    fn test_ffi_backup_roundtrip() {
+        let keys = test_keys();
+        let backup_b64 = pq_export_key_backup(
+            "hunter2".into(),
+            BASE64.encode(&*keys.kem_sk),
+            BASE64.encode(&*keys.dsa_sk),
+            keys.dsa_epoch,
+            Some(TEST_PARAMS),
+        )
+        .unwrap();
+
+        let restored = pq_import_key_backup("hunter2".into(), backup_b64.clone()).unwrap();
+        assert_eq!(restored.kem_sk_b64, BASE64.encode(&*keys.kem_sk));
+        assert_eq!(restored.dsa_sk_b64, BASE64.encode(&*keys.dsa_sk));
+        assert_eq!(restored.dsa_epoch, 3);
+        assert_eq!(
+            pq_import_key_backup("hunter3".into(), backup_b64),
+            Err(MercyError::DecryptionFailed)
+        );
+    }
+}
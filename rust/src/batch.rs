@@ -0,0 +1,182 @@
+// rust/src/batch.rs — Batch ML-DSA Verification Eternal (rayon fan-out behind a bounded queue)
+//
+// The caller's thread cuts jobs into chunks of up to `CHUNK` and feeds them into a bounded channel;
+// the verifier's own rayon pool drains it with `par_bridge` and verifies each chunk serially, so
+// the channel and work-stealing overhead is paid per chunk rather than per signature. Memory stays
+// at roughly `queue_depth` in-flight jobs however long the input iterator is, and results come
+// back in input order, one `MercyResult<bool>` per job (same semantics as
+// `pq_verify_data`: malformed input is an error, a wrong signature is `Ok(false)`).
+use alloc::vec::Vec;
+use rayon::iter::{ParallelBridge, ParallelIterator};
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::sync::mpsc::sync_channel;
+use std::thread;
+
+use crate::context::MessageKind;
+use crate::error::{MercyError, MercyResult};
+
+/// Jobs per channel message — large enough to amortise the hand-off, small enough to keep every
+/// worker busy near the end of a batch
+const CHUNK: usize = 64;
+
+/// One `(pk, msg, sig)` triple plus the context it must verify under
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyJob {
+    pub dsa_pk: Vec<u8>,
+    pub message: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub kind: MessageKind,
+}
+
+/// Verify a single job — raw-bytes twin of `pq_verify_data`
+pub fn verify_one(job: &VerifyJob) -> MercyResult<bool> {
+    crate::verify_data(&job.dsa_pk, &job.message, &job.signature, job.kind)
+}
+
+/// Dedicated verification pool — build once, share behind an `Arc`
+pub struct BatchVerifier {
+    pool: ThreadPool,
+    queue_depth: usize,
+}
+
+impl BatchVerifier {
+    /// `threads == 0` uses one thread per core; `queue_depth` bounds jobs buffered ahead of the workers
+    pub fn new(threads: usize, queue_depth: usize) -> MercyResult<Self> {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|i| std::format!("mercy-verify-{}", i))
+            .build()
+            .map_err(|_| MercyError::CryptoFailure)?;
+        Ok(BatchVerifier {
+            pool,
+            queue_depth: queue_depth.max(1),
+        })
+    }
+
+    pub fn threads(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+
+    /// Verify every job — result `i` belongs to job `i`
+    pub fn verify_batch<I>(&self, jobs: I) -> Vec<MercyResult<bool>>
+    where
+        I: IntoIterator<Item = VerifyJob>,
+    {
+        self.run_bounded(jobs, verify_one)
+    }
+
+    fn run_bounded<I, T, R, F>(&self, jobs: I, f: F) -> Vec<R>
+    where
+        I: IntoIterator<Item = T>,
+        T: Send,
+        R: Send,
+        F: Fn(&T) -> R + Sync,
+    {
+        let chunk_len = CHUNK.min(self.queue_depth);
+        let (tx, rx) = sync_channel::<Vec<(usize, T)>>((self.queue_depth / chunk_len).max(1));
+
+        let mut indexed: Vec<(usize, R)> = thread::scope(|s| {
+            // Consumers live on the pool; the producer is this thread, so a full queue blocks the
+            // caller instead of parking a pool worker (no deadlock with a 1-thread pool)
+            let workers = s.spawn(|| {
+                self.pool.install(|| {
+                    rx.into_iter()
+                        .par_bridge()
+                        .flat_map_iter(|chunk| chunk.into_iter().map(|(i, job)| (i, f(&job))))
+                        .collect()
+                })
+            });
+            let mut jobs = jobs.into_iter().enumerate();
+            loop {
+                let chunk: Vec<(usize, T)> = jobs.by_ref().take(chunk_len).collect();
+                if chunk.is_empty() || tx.send(chunk).is_err() {
+                    break;
+                }
+            }
+            drop(tx);
+            workers
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        });
+
+        indexed.sort_unstable_by_key(|(i, _)| *i);
+        indexed.into_iter().map(|(_, r)| r).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pq;
+    use alloc::vec;
+    use rand_core::OsRng;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn signed_job(message: &[u8], kind: MessageKind) -> VerifyJob {
+        let sk = pq::dsa_generate(&mut OsRng);
+        VerifyJob {
+            dsa_pk: pq::dsa_public_key_bytes(&sk),
+            message: message.to_vec(),
+            signature: pq::dsa_sign(&sk, message, kind.context(), &mut OsRng).unwrap(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_run_bounded_preserves_order() {
+        let verifier = BatchVerifier::new(4, 3).unwrap();
+        let out = verifier.run_bounded(0..1000u32, |x| x * 2);
+        assert_eq!(out, (0..1000u32).map(|x| x * 2).collect::<Vec<_>>());
+        // Several full chunks plus a ragged tail
+        let chunked = BatchVerifier::new(3, 4 * CHUNK).unwrap();
+        let n = 10 * CHUNK as u32 + 7;
+        assert_eq!(
+            chunked.run_bounded(0..n, |x| *x),
+            (0..n).collect::<Vec<_>>()
+        );
+        assert!(verifier
+            .run_bounded(core::iter::empty::<u32>(), |x| *x)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_single_thread_pool_does_not_deadlock() {
+        let verifier = BatchVerifier::new(1, 1).unwrap();
+        let in_flight = AtomicUsize::new(0);
+        let out = verifier.run_bounded(0..200u32, |x| {
+            assert!(
+                in_flight.fetch_add(1, Ordering::SeqCst) < 1,
+                "one worker, one job at a time"
+            );
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            *x
+        });
+        assert_eq!(out.len(), 200);
+    }
+
+    #[test]
+    fn test_per_item_results() {
+        let good = signed_job(b"report-a", MessageKind::AttestationReport);
+        let mut forged = signed_job(b"report-b", MessageKind::AttestationReport);
+        forged.message = b"report-B".to_vec();
+        let mut cross_context = signed_job(b"entry", MessageKind::LedgerEntry);
+        cross_context.kind = MessageKind::LogExport;
+        let mut bad_key = good.clone();
+        bad_key.dsa_pk.truncate(5);
+        let mut bad_sig = good.clone();
+        bad_sig.signature = vec![0; 3];
+
+        let verifier = BatchVerifier::new(2, 2).unwrap();
+        let results = verifier.verify_batch(vec![good, forged, cross_context, bad_key, bad_sig]);
+        assert_eq!(
+            results,
+            vec![
+                Ok(true),
+                Ok(false),
+                Ok(false),
+                Err(MercyError::InvalidKey),
+                Err(MercyError::InvalidSignature)
+            ]
+        );
+    }
+}
@@ -0,0 +1,474 @@
+// rust/src/blob.rs — Attestation Blob Format v2 Eternal (versioned header + length-prefixed fields)
+//
+// Layout (integers big-endian):
+//   magic       4   b"MSPB"
+//   version     1   0x02
+//   kem_alg     1   KemAlgorithm
+//   aead_alg    1   AeadAlgorithm
+//   sig_alg     1   SignatureAlgorithm (0 = unsigned report)
+//   key_id     16   SHA3-256(recipient KEM PK)[..16]
+//   kem_ct    2+n   length-prefixed KEM ciphertext
+//   nonce      12   AEAD nonce
+//   ------------- everything above is AEAD associated data -------------
+//   sealed    rest  AEAD(report_len u32 || report || sig_len u32 || sig)
+use aes_gcm::{AeadInPlace, Aes256Gcm, KeyInit, Nonce};
+use alloc::vec::Vec;
+use sha3::{Digest, Sha3_256};
+use zeroize::Zeroizing;
+
+use crate::error::{MercyError, MercyResult};
+use crate::kdf::{self, KeyPurpose, KeySchedule, KEY_LEN};
+
+pub const BLOB_MAGIC: [u8; 4] = *b"MSPB";
+pub const BLOB_VERSION: u8 = 2;
+pub const KEY_ID_LEN: usize = 16;
+pub const NONCE_LEN: usize = 12;
+
+/// KEM used to wrap the blob key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum KemAlgorithm {
+    MlKem768 = 1,
+}
+
+/// AEAD sealing the payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum AeadAlgorithm {
+    Aes256Gcm = 1,
+}
+
+/// Signature over the report (None = unsigned)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SignatureAlgorithm {
+    None = 0,
+    MlDsa65 = 1,
+}
+
+impl TryFrom<u8> for KemAlgorithm {
+    type Error = MercyError;
+    fn try_from(id: u8) -> MercyResult<Self> {
+        match id {
+            1 => Ok(KemAlgorithm::MlKem768),
+            _ => Err(MercyError::UnsupportedAlgorithm),
+        }
+    }
+}
+
+impl TryFrom<u8> for AeadAlgorithm {
+    type Error = MercyError;
+    fn try_from(id: u8) -> MercyResult<Self> {
+        match id {
+            1 => Ok(AeadAlgorithm::Aes256Gcm),
+            _ => Err(MercyError::UnsupportedAlgorithm),
+        }
+    }
+}
+
+impl TryFrom<u8> for SignatureAlgorithm {
+    type Error = MercyError;
+    fn try_from(id: u8) -> MercyResult<Self> {
+        match id {
+            0 => Ok(SignatureAlgorithm::None),
+            1 => Ok(SignatureAlgorithm::MlDsa65),
+            _ => Err(MercyError::UnsupportedAlgorithm),
+        }
+    }
+}
+
+/// Key id for a recipient KEM public key — truncated SHA3-256 fingerprint
+pub fn key_id(kem_pk_bytes: &[u8]) -> [u8; KEY_ID_LEN] {
+    let digest = Sha3_256::digest(kem_pk_bytes);
+    let mut id = [0u8; KEY_ID_LEN];
+    id.copy_from_slice(&digest[..KEY_ID_LEN]);
+    id
+}
+
+/// Blob AEAD key via the shared HKDF schedule (never the raw KEM shared secret)
+pub fn blob_key(
+    shared_secret: &[u8],
+    kem_ciphertext: &[u8],
+    recipient_pk_bytes: &[u8],
+) -> MercyResult<Zeroizing<[u8; KEY_LEN]>> {
+    let pk_hash = kdf::recipient_pk_hash(recipient_pk_bytes);
+    KeySchedule::new(shared_secret, kem_ciphertext, &pk_hash).derive_key(KeyPurpose::BlobAead)
+}
+
+/// Seal a blob once the KEM step is done — no RNG, no KEM, so whole blobs have known answers
+pub fn seal(
+    header: &BlobHeader,
+    shared_secret: &[u8],
+    recipient_pk_bytes: &[u8],
+    payload: &BlobPayload,
+) -> MercyResult<Vec<u8>> {
+    let aes_key = blob_key(shared_secret, &header.kem_ciphertext, recipient_pk_bytes)?;
+    let cipher = Aes256Gcm::new((&*aes_key).into());
+
+    let mut blob = header.encode()?;
+    let mut sealed = payload.encode()?;
+    cipher
+        .encrypt_in_place(Nonce::from_slice(&header.nonce), &blob, &mut sealed)
+        .map_err(|_| MercyError::CryptoFailure)?;
+    blob.extend_from_slice(&sealed);
+    Ok(blob)
+}
+
+/// Inverse of `seal` for an already-parsed header (`header_len` = AAD span from `BlobHeader::parse`)
+pub fn open_sealed(
+    blob: &[u8],
+    header: &BlobHeader,
+    header_len: usize,
+    shared_secret: &[u8],
+    recipient_pk_bytes: &[u8],
+) -> MercyResult<BlobPayload> {
+    let aes_key = blob_key(shared_secret, &header.kem_ciphertext, recipient_pk_bytes)?;
+    let cipher = Aes256Gcm::new((&*aes_key).into());
+
+    let mut plaintext = Zeroizing::new(blob[header_len..].to_vec());
+    cipher
+        .decrypt_in_place(
+            Nonce::from_slice(&header.nonce),
+            &blob[..header_len],
+            &mut *plaintext,
+        )
+        .map_err(|_| MercyError::DecryptionFailed)?;
+
+    BlobPayload::parse(&plaintext, header.sig_alg)
+}
+
+/// Cleartext blob header — bound as AEAD associated data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobHeader {
+    pub kem_alg: KemAlgorithm,
+    pub aead_alg: AeadAlgorithm,
+    pub sig_alg: SignatureAlgorithm,
+    pub key_id: [u8; KEY_ID_LEN],
+    pub kem_ciphertext: Vec<u8>,
+    pub nonce: [u8; NONCE_LEN],
+}
+
+impl BlobHeader {
+    pub fn encode(&self) -> MercyResult<Vec<u8>> {
+        let ct_len =
+            u16::try_from(self.kem_ciphertext.len()).map_err(|_| MercyError::InvalidBlob)?;
+
+        let mut out =
+            Vec::with_capacity(8 + KEY_ID_LEN + 2 + self.kem_ciphertext.len() + NONCE_LEN);
+        out.extend_from_slice(&BLOB_MAGIC);
+        out.push(BLOB_VERSION);
+        out.push(self.kem_alg as u8);
+        out.push(self.aead_alg as u8);
+        out.push(self.sig_alg as u8);
+        out.extend_from_slice(&self.key_id);
+        out.extend_from_slice(&ct_len.to_be_bytes());
+        out.extend_from_slice(&self.kem_ciphertext);
+        out.extend_from_slice(&self.nonce);
+        Ok(out)
+    }
+
+    /// Parse header from the front of `blob` — returns header + header length (AAD span)
+    pub fn parse(blob: &[u8]) -> MercyResult<(Self, usize)> {
+        let mut r = Reader::new(blob);
+
+        if r.take(4)? != BLOB_MAGIC {
+            return Err(MercyError::InvalidBlob);
+        }
+        if r.u8()? != BLOB_VERSION {
+            return Err(MercyError::UnsupportedVersion);
+        }
+
+        let kem_alg = KemAlgorithm::try_from(r.u8()?)?;
+        let aead_alg = AeadAlgorithm::try_from(r.u8()?)?;
+        let sig_alg = SignatureAlgorithm::try_from(r.u8()?)?;
+
+        let mut key_id = [0u8; KEY_ID_LEN];
+        key_id.copy_from_slice(r.take(KEY_ID_LEN)?);
+
+        let ct_len = r.u16()? as usize;
+        let kem_ciphertext = r.take(ct_len)?.to_vec();
+
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce.copy_from_slice(r.take(NONCE_LEN)?);
+
+        let header = BlobHeader {
+            kem_alg,
+            aead_alg,
+            sig_alg,
+            key_id,
+            kem_ciphertext,
+            nonce,
+        };
+        Ok((header, r.pos))
+    }
+}
+
+/// Decrypted blob contents — report bytes + optional detached signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobPayload {
+    pub report: Vec<u8>,
+    pub signature: Option<Vec<u8>>,
+}
+
+impl BlobPayload {
+    pub fn encode(&self) -> MercyResult<Vec<u8>> {
+        let sig = self.signature.as_deref().unwrap_or(&[]);
+        let report_len = u32::try_from(self.report.len()).map_err(|_| MercyError::InvalidBlob)?;
+        let sig_len = u32::try_from(sig.len()).map_err(|_| MercyError::InvalidBlob)?;
+
+        let mut out = Vec::with_capacity(8 + self.report.len() + sig.len());
+        out.extend_from_slice(&report_len.to_be_bytes());
+        out.extend_from_slice(&self.report);
+        out.extend_from_slice(&sig_len.to_be_bytes());
+        out.extend_from_slice(sig);
+        Ok(out)
+    }
+
+    /// Parse decrypted payload — `sig_alg` from the header decides whether a signature must be present
+    pub fn parse(plaintext: &[u8], sig_alg: SignatureAlgorithm) -> MercyResult<Self> {
+        let mut r = Reader::new(plaintext);
+
+        let report_len = r.u32()? as usize;
+        let report = r.take(report_len)?.to_vec();
+        let sig_len = r.u32()? as usize;
+        let sig = r.take(sig_len)?;
+
+        if !r.is_empty() {
+            return Err(MercyError::InvalidBlob);
+        }
+
+        let signature = match (sig_alg, sig_len) {
+            (SignatureAlgorithm::None, 0) => None,
+            (SignatureAlgorithm::MlDsa65, n) if n > 0 => Some(sig.to_vec()),
+            _ => return Err(MercyError::InvalidBlob),
+        };
+
+        Ok(BlobPayload { report, signature })
+    }
+}
+
+/// Bounds-checked big-endian cursor — truncation is `InvalidBlob`, never a panic
+pub(crate) struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(buf: &'a [u8]) -> Self {
+        Reader { buf, pos: 0 }
+    }
+
+    pub(crate) fn take(&mut self, n: usize) -> MercyResult<&'a [u8]> {
+        let end = self.pos.checked_add(n).ok_or(MercyError::InvalidBlob)?;
+        let slice = self.buf.get(self.pos..end).ok_or(MercyError::InvalidBlob)?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    pub(crate) fn u8(&mut self) -> MercyResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> MercyResult<u16> {
+        let b = self.take(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    pub(crate) fn u32(&mut self) -> MercyResult<u32> {
+        let b = self.take(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    pub(crate) fn u64(&mut self) -> MercyResult<u64> {
+        let mut b = [0u8; 8];
+        b.copy_from_slice(self.take(8)?);
+        Ok(u64::from_be_bytes(b))
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.pos == self.buf.len()
+    }
+
+    pub(crate) fn position(&self) -> usize {
+        self.pos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pq;
+    use crate::rng::SeededRng;
+    use crate::{open_attestation_blob, secure_attestation_blob_with_rng, SignatureStatus};
+    use alloc::vec;
+
+    const KAT_FILE: &str = include_str!("../tests/vectors/blob_v2.kat");
+    const KAT_REPORT: &[u8] =
+        b"{\"timestamp\":1700000000000,\"details\":[\"Magisk binary present\"]}";
+
+    /// Whole-blob KAT through the real ML-KEM-768 / ML-DSA-65 path: server KEM key, device DSA key
+    /// and the blob's own randomness (signature `rnd`, encapsulation, nonce) each from a `SeededRng`
+    fn kat_keys() -> (pq::MlKem768SecretKey, Vec<u8>, pq::DsaSigningKey) {
+        let (kem_sk, kem_pk) = pq::kem_generate(&mut SeededRng::from_seed(b"blob-v2-kat/server"));
+        let dsa_sk = pq::dsa_generate(&mut SeededRng::from_seed(b"blob-v2-kat/device"));
+        (kem_sk, pq::kem_public_key_bytes(&kem_pk), dsa_sk)
+    }
+
+    fn kat_blob(seed: &[u8], signed: bool) -> Vec<u8> {
+        let (_, kem_pk, dsa_sk) = kat_keys();
+        let dsa_sk = pq::dsa_secret_key_bytes(&dsa_sk);
+        let sk = signed.then_some(&dsa_sk[..]);
+        secure_attestation_blob_with_rng(
+            KAT_REPORT.to_vec(),
+            &kem_pk,
+            sk,
+            &mut SeededRng::from_seed(seed),
+        )
+        .unwrap()
+    }
+
+    fn kat_vector(name: &str) -> Vec<u8> {
+        let hex = KAT_FILE
+            .lines()
+            .filter(|l| !l.starts_with('#'))
+            .find_map(|l| l.strip_prefix(name)?.trim_start().strip_prefix('='))
+            .unwrap_or_else(|| panic!("missing KAT vector {}", name))
+            .trim();
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn sample_header() -> BlobHeader {
+        BlobHeader {
+            kem_alg: KemAlgorithm::MlKem768,
+            aead_alg: AeadAlgorithm::Aes256Gcm,
+            sig_alg: SignatureAlgorithm::MlDsa65,
+            key_id: key_id(b"server kem pk"),
+            kem_ciphertext: vec![0xAB; 1088],
+            nonce: [7u8; NONCE_LEN],
+        }
+    }
+
+    #[test]
+    fn test_whole_blob_kat() {
+        let unsigned = kat_vector("unsigned");
+        let signed = kat_vector("signed");
+        assert_eq!(kat_blob(b"blob-v2-kat/unsigned", false), unsigned);
+        assert_eq!(kat_blob(b"blob-v2-kat/signed", true), signed);
+
+        // The committed bytes open with the server key and carry a valid device signature
+        let (kem_sk, kem_pk, dsa_sk) = kat_keys();
+        assert_eq!(kat_vector("server_kem_pk"), kem_pk);
+        let dsa_pk = pq::dsa_public_key_bytes(&dsa_sk);
+        assert_eq!(kat_vector("device_dsa_pk"), dsa_pk);
+
+        let opened = open_attestation_blob(&unsigned, &kem_sk, None).unwrap();
+        assert_eq!(
+            (opened.report.as_slice(), opened.signature_status),
+            (KAT_REPORT, SignatureStatus::Unsigned)
+        );
+        let opened = open_attestation_blob(&signed, &kem_sk, Some(&dsa_pk)).unwrap();
+        assert_eq!(
+            (opened.report.as_slice(), opened.signature_status),
+            (KAT_REPORT, SignatureStatus::Valid)
+        );
+    }
+
+    #[test]
+    fn test_header_roundtrip() {
+        let header = sample_header();
+        let mut bytes = header.encode().unwrap();
+        let header_len = bytes.len();
+        bytes.extend_from_slice(b"sealed payload");
+
+        let (parsed, len) = BlobHeader::parse(&bytes).unwrap();
+        assert_eq!(parsed, header);
+        assert_eq!(len, header_len, "AAD span must end at the nonce");
+    }
+
+    #[test]
+    fn test_header_rejects_unknown_version() {
+        let mut bytes = sample_header().encode().unwrap();
+        bytes[4] = 3;
+        assert_eq!(
+            BlobHeader::parse(&bytes),
+            Err(MercyError::UnsupportedVersion)
+        );
+    }
+
+    #[test]
+    fn test_header_rejects_bad_magic_and_algorithms() {
+        let good = sample_header().encode().unwrap();
+
+        let mut bad_magic = good.clone();
+        bad_magic[0] = b'X';
+        assert_eq!(BlobHeader::parse(&bad_magic), Err(MercyError::InvalidBlob));
+
+        let mut bad_kem = good.clone();
+        bad_kem[5] = 0x7F;
+        assert_eq!(
+            BlobHeader::parse(&bad_kem),
+            Err(MercyError::UnsupportedAlgorithm)
+        );
+
+        let mut bad_sig = good;
+        bad_sig[7] = 9;
+        assert_eq!(
+            BlobHeader::parse(&bad_sig),
+            Err(MercyError::UnsupportedAlgorithm)
+        );
+    }
+
+    #[test]
+    fn test_header_truncation_never_panics() {
+        let bytes = sample_header().encode().unwrap();
+        for cut in 0..bytes.len() {
+            assert_eq!(
+                BlobHeader::parse(&bytes[..cut]),
+                Err(MercyError::InvalidBlob),
+                "cut at {}",
+                cut
+            );
+        }
+    }
+
+    #[test]
+    fn test_payload_roundtrip_and_sig_alg_binding() {
+        let signed = BlobPayload {
+            report: b"{\"risk\":0}".to_vec(),
+            signature: Some(vec![1u8; 3309]),
+        };
+        let bytes = signed.encode().unwrap();
+        assert_eq!(
+            BlobPayload::parse(&bytes, SignatureAlgorithm::MlDsa65).unwrap(),
+            signed
+        );
+        assert_eq!(
+            BlobPayload::parse(&bytes, SignatureAlgorithm::None),
+            Err(MercyError::InvalidBlob)
+        );
+
+        let unsigned = BlobPayload {
+            report: b"{}".to_vec(),
+            signature: None,
+        };
+        let bytes = unsigned.encode().unwrap();
+        assert_eq!(
+            BlobPayload::parse(&bytes, SignatureAlgorithm::None).unwrap(),
+            unsigned
+        );
+        assert_eq!(
+            BlobPayload::parse(&bytes, SignatureAlgorithm::MlDsa65),
+            Err(MercyError::InvalidBlob)
+        );
+
+        let mut trailing = bytes;
+        trailing.push(0);
+        assert_eq!(
+            BlobPayload::parse(&trailing, SignatureAlgorithm::None),
+            Err(MercyError::InvalidBlob)
+        );
+    }
+}
@@ -0,0 +1,409 @@
+// rust/src/capi.rs — C ABI Eternal (backend scripts + QA harness; header: include/mercyshieldplus.h)
+//
+// Ownership rules:
+//   * Input pointers are borrowed for the duration of the call only. `(NULL, 0)` is an empty slice;
+//     `NULL` with a non-zero length is `MERCY_STATUS_NULL_POINTER`.
+//   * Every `MercyBuffer` / `char *` written to an out-param is owned by the caller and must be
+//     released exactly once with `mercy_buffer_free` / `mercy_string_free` (both accept empty/NULL).
+//   * Out-params are only written when the call returns `MERCY_STATUS_OK`.
+//   * Panics never unwind into C. Built with `--profile bindings` (`panic = "unwind"`) they surface
+//     as `MERCY_STATUS_PANIC`; the size-tuned release profile is `panic = "abort"` and aborts.
+//
+// Regenerate the header after changing anything here:
+//   cbindgen --config cbindgen.toml --crate mercyshieldplus --output include/mercyshieldplus.h
+use alloc::boxed::Box;
+use alloc::ffi::CString;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::ffi::{c_char, CStr};
+use core::{ptr, slice};
+use rand_core::OsRng;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::context::MessageKind;
+use crate::error::MercyError;
+use crate::integrity::{evaluate_integrity, legacy_evidence, report_to_json};
+
+/// Result code of every `mercy_*` call
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MercyStatus {
+    Ok = 0,
+    /// A required pointer argument was NULL
+    NullPointer = 1,
+    /// A `char *` argument was not valid UTF-8
+    InvalidUtf8 = 2,
+    /// An enum-valued argument was out of range
+    InvalidArgument = 3,
+    /// The call panicked — treat as an internal bug (`panic = "abort"` builds abort instead)
+    Panic = 4,
+    InvalidBase64 = 10,
+    InvalidKey = 11,
+    InvalidSignature = 12,
+    CryptoFailure = 13,
+    InvalidBlob = 14,
+    UnsupportedVersion = 15,
+    UnsupportedAlgorithm = 16,
+    WrongRecipient = 17,
+    DecryptionFailed = 18,
+    StreamTruncated = 19,
+    KeyTypeMismatch = 20,
+    InvalidState = 21,
+    StaleEpoch = 22,
+    RotationRejected = 23,
+    InvalidBackupParams = 24,
+    ChallengeUnknown = 25,
+    ChallengeExpired = 26,
+    SecureMemory = 27,
+    InvalidTreeSize = 28,
+    InvalidPolicy = 29,
+    InvalidReport = 30,
+    InvalidJson = 31,
+    ChallengeStoreFull = 32,
+}
+
+impl From<MercyError> for MercyStatus {
+    fn from(err: MercyError) -> Self {
+        match err {
+            MercyError::InvalidBase64 => MercyStatus::InvalidBase64,
+            MercyError::InvalidKey => MercyStatus::InvalidKey,
+            MercyError::InvalidSignature => MercyStatus::InvalidSignature,
+            MercyError::CryptoFailure => MercyStatus::CryptoFailure,
+            MercyError::InvalidBlob => MercyStatus::InvalidBlob,
+            MercyError::UnsupportedVersion => MercyStatus::UnsupportedVersion,
+            MercyError::UnsupportedAlgorithm => MercyStatus::UnsupportedAlgorithm,
+            MercyError::WrongRecipient => MercyStatus::WrongRecipient,
+            MercyError::DecryptionFailed => MercyStatus::DecryptionFailed,
+            MercyError::StreamTruncated => MercyStatus::StreamTruncated,
+            MercyError::KeyTypeMismatch => MercyStatus::KeyTypeMismatch,
+            MercyError::InvalidState => MercyStatus::InvalidState,
+            MercyError::StaleEpoch => MercyStatus::StaleEpoch,
+            MercyError::RotationRejected => MercyStatus::RotationRejected,
+            MercyError::InvalidBackupParams => MercyStatus::InvalidBackupParams,
+            MercyError::ChallengeUnknown => MercyStatus::ChallengeUnknown,
+            MercyError::ChallengeExpired => MercyStatus::ChallengeExpired,
+            MercyError::SecureMemory => MercyStatus::SecureMemory,
+            MercyError::InvalidTreeSize => MercyStatus::InvalidTreeSize,
+            MercyError::InvalidPolicy => MercyStatus::InvalidPolicy,
+            MercyError::InvalidReport => MercyStatus::InvalidReport,
+            MercyError::InvalidJson => MercyStatus::InvalidJson,
+            MercyError::ChallengeStoreFull => MercyStatus::ChallengeStoreFull,
+        }
+    }
+}
+
+/// `MessageKind` as passed over the C ABI (a plain `uint32_t`, validated on entry)
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MercyMessageKind {
+    AttestationReport = 0,
+    KeyRotation = 1,
+    LogExport = 2,
+    LedgerEntry = 3,
+    TreeHead = 4,
+}
+
+/// Rust-allocated byte buffer — release with `mercy_buffer_free`
+#[repr(C)]
+#[derive(Debug)]
+pub struct MercyBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+impl MercyBuffer {
+    const EMPTY: MercyBuffer = MercyBuffer {
+        data: ptr::null_mut(),
+        len: 0,
+    };
+
+    fn from_vec(bytes: Vec<u8>) -> Self {
+        let len = bytes.len();
+        if len == 0 {
+            return MercyBuffer::EMPTY;
+        }
+        let data = Box::into_raw(bytes.into_boxed_slice()) as *mut u8;
+        MercyBuffer { data, len }
+    }
+}
+
+fn guard(f: impl FnOnce() -> Result<(), MercyStatus>) -> MercyStatus {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => MercyStatus::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => MercyStatus::Panic,
+    }
+}
+
+unsafe fn bytes_arg<'a>(data: *const u8, len: usize) -> Result<&'a [u8], MercyStatus> {
+    match (data.is_null(), len) {
+        (_, 0) => Ok(&[]),
+        (true, _) => Err(MercyStatus::NullPointer),
+        (false, _) => Ok(slice::from_raw_parts(data, len)),
+    }
+}
+
+unsafe fn str_arg<'a>(s: *const c_char) -> Result<&'a str, MercyStatus> {
+    if s.is_null() {
+        return Err(MercyStatus::NullPointer);
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|_| MercyStatus::InvalidUtf8)
+}
+
+unsafe fn str_array_arg(
+    items: *const *const c_char,
+    count: usize,
+) -> Result<Vec<String>, MercyStatus> {
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+    if items.is_null() {
+        return Err(MercyStatus::NullPointer);
+    }
+    slice::from_raw_parts(items, count)
+        .iter()
+        .map(|&s| str_arg(s).map(ToString::to_string))
+        .collect()
+}
+
+unsafe fn out_arg<'a, T>(out: *mut T) -> Result<&'a mut T, MercyStatus> {
+    out.as_mut().ok_or(MercyStatus::NullPointer)
+}
+
+fn message_kind(kind: u32) -> Result<MessageKind, MercyStatus> {
+    MessageKind::ALL
+        .get(kind as usize)
+        .copied()
+        .ok_or(MercyStatus::InvalidArgument)
+}
+
+/// Seal a v2 attestation blob to the server KEM public key (raw bytes in, raw blob out).
+/// `dsa_sk == NULL` produces an unsigned blob; otherwise the report is signed with that ML-DSA-65 SK.
+///
+/// # Safety
+/// Pointer/length pairs must describe readable memory; `out_blob` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn mercy_secure_attestation_blob(
+    report: *const u8,
+    report_len: usize,
+    server_kem_pk: *const u8,
+    server_kem_pk_len: usize,
+    dsa_sk: *const u8,
+    dsa_sk_len: usize,
+    out_blob: *mut MercyBuffer,
+) -> MercyStatus {
+    guard(|| {
+        let out_blob = out_arg(out_blob)?;
+        let report = bytes_arg(report, report_len)?.to_vec();
+        let server_kem_pk = bytes_arg(server_kem_pk, server_kem_pk_len)?;
+        let dsa_sk = if dsa_sk.is_null() {
+            None
+        } else {
+            Some(bytes_arg(dsa_sk, dsa_sk_len)?)
+        };
+
+        let blob =
+            crate::secure_attestation_blob_with_rng(report, server_kem_pk, dsa_sk, &mut OsRng)?;
+        *out_blob = MercyBuffer::from_vec(blob);
+        Ok(())
+    })
+}
+
+/// Verify an ML-DSA-65 signature made under `kind` (a `MercyMessageKind` value).
+/// A well-formed but wrong signature is `MERCY_STATUS_OK` with `*out_valid == false`.
+///
+/// # Safety
+/// Pointer/length pairs must describe readable memory; `out_valid` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn mercy_verify_data(
+    dsa_pk: *const u8,
+    dsa_pk_len: usize,
+    message: *const u8,
+    message_len: usize,
+    signature: *const u8,
+    signature_len: usize,
+    kind: u32,
+    out_valid: *mut bool,
+) -> MercyStatus {
+    guard(|| {
+        let out_valid = out_arg(out_valid)?;
+        let kind = message_kind(kind)?;
+        let dsa_pk = bytes_arg(dsa_pk, dsa_pk_len)?;
+        let message = bytes_arg(message, message_len)?;
+        let signature = bytes_arg(signature, signature_len)?;
+
+        *out_valid = crate::verify_data(dsa_pk, message, signature, kind)?;
+        Ok(())
+    })
+}
+
+/// Score the collected evidence and return the report as a JSON string (see `report_to_json`).
+/// Props are `name=value` strings; each input becomes one typed `Evidence` finding.
+///
+/// # Safety
+/// `suspicious_files` / `suspicious_props` point to `*_count` NUL-terminated strings (NULL allowed
+/// when the count is 0); `play_token` is NUL-terminated; `out_report_json` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn mercy_evaluate_integrity(
+    suspicious_files: *const *const c_char,
+    suspicious_files_count: usize,
+    suspicious_props: *const *const c_char,
+    suspicious_props_count: usize,
+    magisk_indicators: bool,
+    play_token: *const c_char,
+    out_report_json: *mut *mut c_char,
+) -> MercyStatus {
+    guard(|| {
+        let out_report_json = out_arg(out_report_json)?;
+        let files = str_array_arg(suspicious_files, suspicious_files_count)?;
+        let props = str_array_arg(suspicious_props, suspicious_props_count)?;
+        let play_token = str_arg(play_token)?.to_string();
+
+        let report =
+            evaluate_integrity(legacy_evidence(files, props, magisk_indicators), play_token);
+        // report_to_json escapes control characters, so an interior NUL is impossible
+        let json =
+            CString::new(report_to_json(report)).map_err(|_| MercyStatus::InvalidArgument)?;
+        *out_report_json = json.into_raw();
+        Ok(())
+    })
+}
+
+/// Release a buffer returned by this library. Passing an empty buffer is a no-op.
+///
+/// # Safety
+/// `buffer` must come from a `mercy_*` out-param and must not be used (or freed) again.
+#[no_mangle]
+pub unsafe extern "C" fn mercy_buffer_free(buffer: MercyBuffer) {
+    if !buffer.data.is_null() && buffer.len != 0 {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+            buffer.data,
+            buffer.len,
+        )));
+    }
+}
+
+/// Release a string returned by this library. Passing NULL is a no-op.
+///
+/// # Safety
+/// `s` must come from a `mercy_*` out-param and must not be used (or freed) again.
+#[no_mangle]
+pub unsafe extern "C" fn mercy_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_kind_values_match_message_kind_order() {
+        for (i, kind) in MessageKind::ALL.iter().enumerate() {
+            assert_eq!(message_kind(i as u32), Ok(*kind));
+        }
+        assert_eq!(MercyMessageKind::TreeHead as u32, 4);
+        assert_eq!(
+            message_kind(MessageKind::ALL.len() as u32),
+            Err(MercyStatus::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_null_and_empty_arguments() {
+        unsafe {
+            assert_eq!(bytes_arg(ptr::null(), 0), Ok(&[][..]));
+            assert_eq!(bytes_arg(ptr::null(), 4), Err(MercyStatus::NullPointer));
+            assert_eq!(str_array_arg(ptr::null(), 0), Ok(vec![]));
+            assert_eq!(str_array_arg(ptr::null(), 1), Err(MercyStatus::NullPointer));
+
+            let mut valid = true;
+            let status = mercy_verify_data(
+                ptr::null(),
+                8,
+                ptr::null(),
+                0,
+                ptr::null(),
+                0,
+                0,
+                &mut valid,
+            );
+            assert_eq!(status, MercyStatus::NullPointer);
+            let status = mercy_verify_data(
+                ptr::null(),
+                0,
+                ptr::null(),
+                0,
+                ptr::null(),
+                0,
+                0,
+                ptr::null_mut(),
+            );
+            assert_eq!(status, MercyStatus::NullPointer);
+        }
+    }
+
+    #[test]
+    fn test_evaluate_integrity_json_ownership() {
+        let files = [c"/system/xbin/su".as_ptr()];
+        let bad_utf8 = [c"\xff".as_ptr()];
+        let mut json: *mut c_char = ptr::null_mut();
+        unsafe {
+            let status = mercy_evaluate_integrity(
+                files.as_ptr(),
+                files.len(),
+                ptr::null(),
+                0,
+                true,
+                c"null_token".as_ptr(),
+                &mut json,
+            );
+            assert_eq!(status, MercyStatus::Ok);
+            let report = CStr::from_ptr(json).to_str().unwrap();
+            assert!(
+                report.starts_with(
+                    r#"{"evidence":[{"path":"/system/xbin/su","type":"suspicious_file"}"#
+                ),
+                "{}",
+                report
+            );
+            assert!(
+                report.ends_with(r#""risk_score":95,"schema_version":1,"verdict":"Compromised"}"#),
+                "{}",
+                report
+            );
+            mercy_string_free(json);
+
+            let mut untouched: *mut c_char = ptr::null_mut();
+            let status = mercy_evaluate_integrity(
+                bad_utf8.as_ptr(),
+                1,
+                ptr::null(),
+                0,
+                false,
+                c"tok".as_ptr(),
+                &mut untouched,
+            );
+            assert_eq!(status, MercyStatus::InvalidUtf8);
+            assert!(untouched.is_null());
+            mercy_string_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_buffer_roundtrip() {
+        let buffer = MercyBuffer::from_vec(vec![1, 2, 3]);
+        assert_eq!(
+            unsafe { slice::from_raw_parts(buffer.data, buffer.len) },
+            &[1, 2, 3]
+        );
+        unsafe {
+            mercy_buffer_free(buffer);
+            mercy_buffer_free(MercyBuffer::from_vec(Vec::new()));
+        }
+    }
+}
@@ -0,0 +1,260 @@
+// rust/src/challenge.rs — Challenge–Response Attestation Eternal (server nonce bound into the signed report)
+//
+// Challenged report layout (this whole byte string is what gets signed and sealed in the blob):
+//   magic     4   b"MSPC"
+//   version   1   CHALLENGE_VERSION
+//   nonce    32   verifier-issued challenge
+//   report  rest  report JSON
+//
+// The same nonce also feeds the Play Integrity requestHash, so the Play token and the ML-DSA
+// signature are both pinned to one verifier round trip. Nonces are single use and expire; a full
+// store refuses new challenges rather than evicting outstanding ones, so a flood of challenge
+// requests cannot invalidate nonces already handed to honest devices.
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use base64::{
+    engine::general_purpose::STANDARD as BASE64, engine::general_purpose::URL_SAFE_NO_PAD,
+    Engine as _,
+};
+use rand_core::{CryptoRngCore, OsRng};
+use sha2::{Digest, Sha256};
+
+use crate::blob::Reader;
+use crate::error::{MercyError, MercyResult};
+
+pub const CHALLENGE_MAGIC: [u8; 4] = *b"MSPC";
+pub const CHALLENGE_VERSION: u8 = 1;
+pub const NONCE_LEN: usize = 32;
+
+const PLAY_HASH_LABEL: &[u8] = b"MercyShieldPlus/play-request-hash/v1";
+
+/// Report plus the verifier challenge it answers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChallengedReport {
+    pub nonce: [u8; NONCE_LEN],
+    pub report: Vec<u8>,
+}
+
+impl ChallengedReport {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(5 + NONCE_LEN + self.report.len());
+        out.extend_from_slice(&CHALLENGE_MAGIC);
+        out.push(CHALLENGE_VERSION);
+        out.extend_from_slice(&self.nonce);
+        out.extend_from_slice(&self.report);
+        out
+    }
+
+    pub fn parse(bytes: &[u8]) -> MercyResult<Self> {
+        let mut r = Reader::new(bytes);
+        if r.take(4)? != CHALLENGE_MAGIC {
+            return Err(MercyError::InvalidBlob);
+        }
+        if r.u8()? != CHALLENGE_VERSION {
+            return Err(MercyError::UnsupportedVersion);
+        }
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce.copy_from_slice(r.take(NONCE_LEN)?);
+        Ok(ChallengedReport {
+            nonce,
+            report: bytes[r.position()..].to_vec(),
+        })
+    }
+}
+
+/// Play Integrity requestHash for a challenge — SHA-256(label || nonce), base64url
+pub fn play_request_hash(nonce: &[u8; NONCE_LEN]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(PLAY_HASH_LABEL);
+    hasher.update(nonce);
+    URL_SAFE_NO_PAD.encode(hasher.finalize())
+}
+
+/// Verifier-side outstanding challenges — `now` is unix seconds from the caller's clock
+pub struct ChallengeStore {
+    ttl_secs: u64,
+    max_outstanding: usize,
+    outstanding: BTreeMap<[u8; NONCE_LEN], u64>,
+}
+
+impl ChallengeStore {
+    pub fn new(ttl_secs: u64, max_outstanding: usize) -> Self {
+        ChallengeStore {
+            ttl_secs,
+            max_outstanding,
+            outstanding: BTreeMap::new(),
+        }
+    }
+
+    /// Issue a fresh nonce valid until `now + ttl` — `ChallengeStoreFull` while `max_outstanding`
+    /// unexpired challenges are pending
+    pub fn issue(&mut self, now: u64) -> MercyResult<[u8; NONCE_LEN]> {
+        self.issue_with_rng(now, &mut OsRng)
+    }
+
+    pub fn issue_with_rng(
+        &mut self,
+        now: u64,
+        rng: &mut impl CryptoRngCore,
+    ) -> MercyResult<[u8; NONCE_LEN]> {
+        self.purge_expired(now);
+        if self.outstanding.len() >= self.max_outstanding {
+            return Err(MercyError::ChallengeStoreFull);
+        }
+
+        let mut nonce = [0u8; NONCE_LEN];
+        rng.fill_bytes(&mut nonce);
+        self.outstanding
+            .insert(nonce, now.saturating_add(self.ttl_secs));
+        Ok(nonce)
+    }
+
+    /// Accept a nonce exactly once, before it expires
+    pub fn consume(&mut self, nonce: &[u8; NONCE_LEN], now: u64) -> MercyResult<()> {
+        let expires_at = self
+            .outstanding
+            .remove(nonce)
+            .ok_or(MercyError::ChallengeUnknown)?;
+        if now > expires_at {
+            return Err(MercyError::ChallengeExpired);
+        }
+        Ok(())
+    }
+
+    pub fn purge_expired(&mut self, now: u64) {
+        self.outstanding.retain(|_, expires_at| *expires_at >= now);
+    }
+
+    pub fn len(&self) -> usize {
+        self.outstanding.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.outstanding.is_empty()
+    }
+}
+
+fn decode_challenge(challenge_b64: &str) -> MercyResult<[u8; NONCE_LEN]> {
+    let bytes = BASE64
+        .decode(challenge_b64)
+        .map_err(|_| MercyError::InvalidBase64)?;
+    bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| MercyError::ChallengeUnknown)
+}
+
+/// Wrap report JSON with the verifier challenge — pass the result to `pq_secure_attestation_blob`
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn pq_bind_challenge(report: Vec<u8>, challenge_b64: String) -> MercyResult<Vec<u8>> {
+    let nonce = decode_challenge(&challenge_b64)?;
+    Ok(ChallengedReport { nonce, report }.encode())
+}
+
+/// requestHash to set on the Play Integrity token request for this challenge
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn pq_play_request_hash(challenge_b64: String) -> MercyResult<String> {
+    Ok(play_request_hash(&decode_challenge(&challenge_b64)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_challenged_report_roundtrip() {
+        let bound = ChallengedReport {
+            nonce: [7; NONCE_LEN],
+            report: b"{\"details\":[]}".to_vec(),
+        };
+        let bytes = bound.encode();
+        assert_eq!(ChallengedReport::parse(&bytes), Ok(bound));
+
+        assert_eq!(
+            ChallengedReport::parse(b"{\"details\":[]}"),
+            Err(MercyError::InvalidBlob)
+        );
+        assert_eq!(
+            ChallengedReport::parse(&bytes[..20]),
+            Err(MercyError::InvalidBlob)
+        );
+        let mut version = bytes;
+        version[4] = 0;
+        assert_eq!(
+            ChallengedReport::parse(&version),
+            Err(MercyError::UnsupportedVersion)
+        );
+    }
+
+    #[test]
+    fn test_challenge_single_use() {
+        let mut store = ChallengeStore::new(120, 16);
+        let nonce = store.issue(1_000).unwrap();
+        assert_eq!(store.consume(&nonce, 1_010), Ok(()));
+        assert_eq!(
+            store.consume(&nonce, 1_011),
+            Err(MercyError::ChallengeUnknown)
+        );
+        assert_eq!(
+            store.consume(&[0; NONCE_LEN], 1_011),
+            Err(MercyError::ChallengeUnknown)
+        );
+    }
+
+    #[test]
+    fn test_challenge_expiry() {
+        let mut store = ChallengeStore::new(120, 16);
+        let nonce = store.issue(1_000).unwrap();
+        assert_eq!(
+            store.consume(&nonce, 1_121),
+            Err(MercyError::ChallengeExpired)
+        );
+        // Expired challenge is gone, not reusable
+        assert_eq!(
+            store.consume(&nonce, 1_000),
+            Err(MercyError::ChallengeUnknown)
+        );
+
+        let stale = store.issue(2_000).unwrap();
+        store.issue(2_200).unwrap();
+        assert_eq!(store.len(), 1, "issuing purges expired challenges");
+        assert_eq!(
+            store.consume(&stale, 2_000),
+            Err(MercyError::ChallengeUnknown)
+        );
+    }
+
+    #[test]
+    fn test_store_bounded() {
+        let mut store = ChallengeStore::new(120, 2);
+        let first = store.issue(1_000).unwrap();
+        let second = store.issue(1_001).unwrap();
+        // A flood of requests is refused — it never evicts challenges already handed out
+        assert_eq!(store.issue(1_002), Err(MercyError::ChallengeStoreFull));
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.consume(&first, 1_003), Ok(()));
+
+        let third = store.issue(1_003).unwrap();
+        assert_eq!(store.consume(&second, 1_004), Ok(()));
+        assert_eq!(store.consume(&third, 1_004), Ok(()));
+
+        // Expired challenges free their slots
+        store.issue(2_000).unwrap();
+        store.issue(2_001).unwrap();
+        assert!(store.issue(2_121).is_ok());
+    }
+
+    #[test]
+    fn test_play_request_hash_bound_to_nonce() {
+        let a = play_request_hash(&[1; NONCE_LEN]);
+        assert_eq!(a, play_request_hash(&[1; NONCE_LEN]));
+        assert_ne!(a, play_request_hash(&[2; NONCE_LEN]));
+        assert_eq!(a.len(), 43);
+        assert_eq!(pq_play_request_hash(BASE64.encode([1; NONCE_LEN])), Ok(a));
+        assert_eq!(
+            pq_play_request_hash(BASE64.encode([1; 5])),
+            Err(MercyError::ChallengeUnknown)
+        );
+    }
+}
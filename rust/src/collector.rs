@@ -0,0 +1,307 @@
+// rust/src/collector.rs — Native Root Evidence Collectors Eternal (su, Magisk, KernelSU, /proc)
+//
+// The scans only ever see the device through `Fs`, so the same code runs against the real rootfs
+// (`RootFs::system()`) and against fixture trees in tests (`tests/fixtures/rootfs/<case>`). Every
+// hit becomes a typed `Evidence` finding that can go straight into `evaluate_integrity`.
+//
+// Paths are checked without following symlinks: a dangling `su` link is still a root artifact.
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::evidence::Evidence;
+
+/// Read-only view of a device filesystem, addressed by absolute paths
+pub trait Fs {
+    /// Whether anything (file, directory, symlink) exists at `path`
+    fn exists(&self, path: &str) -> bool;
+    /// Whole file as UTF-8 — `None` when missing, unreadable or not text
+    fn read_to_string(&self, path: &str) -> Option<String>;
+    /// Entry names of a directory, sorted — empty when missing or unreadable
+    fn read_dir(&self, path: &str) -> Vec<String>;
+    /// Target of a symlink (`/proc/self/fd/*`) — `None` when not a readable link
+    fn read_link(&self, path: &str) -> Option<String>;
+}
+
+/// Classic su binaries and superuser APKs
+pub const SU_PATHS: &[&str] = &[
+    "/system/app/Superuser.apk",
+    "/system/app/SuperSU.apk",
+    "/system/xbin/su",
+    "/system/xbin/daemonsu",
+    "/system/xbin/busybox",
+    "/system/bin/su",
+    "/system/bin/.ext",
+    "/system/bin/failsafe/su",
+    "/system/sd/xbin/su",
+    "/system/usr/we-need-root/su",
+    "/system/usr/su",
+    "/sbin/su",
+    "/vendor/bin/su",
+    "/cache/su",
+    "/data/local/su",
+    "/data/local/xbin/su",
+    "/data/local/bin/su",
+    "/dev/su",
+    "/su/bin/su",
+];
+
+/// Magisk installation and runtime directories
+pub const MAGISK_PATHS: &[&str] = &[
+    "/sbin/.magisk",
+    "/sbin/.core/img",
+    "/debug_ramdisk/.magisk",
+    "/data/adb/magisk",
+    "/data/adb/magisk.db",
+];
+
+/// KernelSU / APatch userspace daemons and state (kernel-level root)
+pub const KERNEL_ROOT_PATHS: &[&str] = &[
+    "/data/adb/kernelsu",
+    "/data/adb/ksu",
+    "/data/adb/ksud",
+    "/data/adb/ap",
+    "/data/adb/apd",
+    "/proc/kernelsu",
+];
+
+/// Mount table of the scanning process — root frameworks mount over /system from here
+pub const MOUNTS_PATH: &str = "/proc/self/mounts";
+
+/// Every root indicator visible through `fs`
+pub fn collect_root_evidence(fs: &impl Fs) -> Vec<Evidence> {
+    let present =
+        |paths: &'static [&'static str]| paths.iter().copied().filter(|path| fs.exists(path));
+
+    let mut evidence: Vec<Evidence> = present(SU_PATHS)
+        .map(|path| Evidence::SuspiciousFile {
+            path: path.to_string(),
+        })
+        .collect();
+    evidence.extend(present(MAGISK_PATHS).map(|path| Evidence::MagiskIndicator {
+        indicator: path.to_string(),
+    }));
+    evidence.extend(present(KERNEL_ROOT_PATHS).map(|path| Evidence::KernelRoot {
+        indicator: path.to_string(),
+    }));
+    if let Some(mounts) = fs.read_to_string(MOUNTS_PATH) {
+        evidence.extend(mount_evidence(&mounts));
+    }
+    evidence
+}
+
+/// Mounts whose source names a root framework (`magisk` tmpfs, `KSU` / `APatch` module overlays)
+pub fn mount_evidence(mounts: &str) -> Vec<Evidence> {
+    let mut evidence = Vec::new();
+    for line in mounts.lines() {
+        let mut fields = line.split_ascii_whitespace();
+        let (Some(source), Some(target)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        let indicator = alloc::format!("mount:{}:{}", source, target);
+        if source.eq_ignore_ascii_case("magisk") || target.contains("/.magisk") {
+            evidence.push(Evidence::MagiskIndicator { indicator });
+        } else if source == "KSU" || source == "APatch" {
+            evidence.push(Evidence::KernelRoot { indicator });
+        }
+    }
+    evidence
+}
+
+#[cfg(feature = "std")]
+pub use self::host::RootFs;
+
+#[cfg(feature = "std")]
+mod host {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    /// `Fs` over a directory tree — `/` on a device, a fixture directory in tests
+    pub struct RootFs {
+        root: PathBuf,
+    }
+
+    impl RootFs {
+        pub fn new(root: impl Into<PathBuf>) -> Self {
+            RootFs { root: root.into() }
+        }
+
+        /// The live filesystem
+        pub fn system() -> Self {
+            RootFs::new("/")
+        }
+
+        /// Fixture tree `tests/fixtures/rootfs/<case>` (integration tests: `tests/common`)
+        #[cfg(test)]
+        pub(crate) fn fixture(case: &str) -> Self {
+            RootFs::new(
+                PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                    .join("tests/fixtures/rootfs")
+                    .join(case),
+            )
+        }
+
+        fn resolve(&self, path: &str) -> PathBuf {
+            self.root.join(path.trim_start_matches('/'))
+        }
+    }
+
+    impl Fs for RootFs {
+        fn exists(&self, path: &str) -> bool {
+            // Permission / SELinux denials read as absent, like the Kotlin scan
+            fs::symlink_metadata(self.resolve(path)).is_ok()
+        }
+
+        fn read_to_string(&self, path: &str) -> Option<String> {
+            fs::read_to_string(self.resolve(path)).ok()
+        }
+
+        fn read_dir(&self, path: &str) -> Vec<String> {
+            let mut names: Vec<String> = fs::read_dir(self.resolve(path))
+                .into_iter()
+                .flatten()
+                .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+                .collect();
+            names.sort();
+            names
+        }
+
+        fn read_link(&self, path: &str) -> Option<String> {
+            fs::read_link(self.resolve(path))
+                .ok()?
+                .into_os_string()
+                .into_string()
+                .ok()
+        }
+    }
+}
+
+/// Root indicators on this device (su, Magisk, KernelSU / APatch files and mounts)
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn collect_device_root_evidence() -> Vec<Evidence> {
+    collect_root_evidence(&RootFs::system())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::collections::BTreeMap;
+
+    /// In-memory filesystem: path → contents
+    struct MapFs(BTreeMap<&'static str, &'static str>);
+
+    impl Fs for MapFs {
+        fn exists(&self, path: &str) -> bool {
+            self.0.contains_key(path)
+        }
+
+        fn read_to_string(&self, path: &str) -> Option<String> {
+            self.0.get(path).map(|s| s.to_string())
+        }
+
+        fn read_dir(&self, _path: &str) -> Vec<String> {
+            Vec::new()
+        }
+
+        fn read_link(&self, _path: &str) -> Option<String> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_paths_map_to_evidence_kinds() {
+        let fs = MapFs(BTreeMap::from([
+            ("/system/bin/su", ""),
+            ("/data/adb/magisk.db", ""),
+            ("/data/adb/apd", ""),
+        ]));
+        assert_eq!(
+            collect_root_evidence(&fs),
+            [
+                Evidence::SuspiciousFile {
+                    path: "/system/bin/su".into()
+                },
+                Evidence::MagiskIndicator {
+                    indicator: "/data/adb/magisk.db".into()
+                },
+                Evidence::KernelRoot {
+                    indicator: "/data/adb/apd".into()
+                },
+            ]
+        );
+        assert!(collect_root_evidence(&MapFs(BTreeMap::new())).is_empty());
+    }
+
+    #[test]
+    fn test_mount_sources() {
+        let mounts = "magisk /system/bin tmpfs ro 0 0\n\
+                      tmpfs /sbin/.magisk/mirror tmpfs rw 0 0\n\
+                      APatch /vendor overlay ro 0 0\n\
+                      /dev/block/dm-0 / ext4 ro 0 0\n\
+                      garbage\n";
+        assert_eq!(
+            mount_evidence(mounts),
+            [
+                Evidence::MagiskIndicator {
+                    indicator: "mount:magisk:/system/bin".into()
+                },
+                Evidence::MagiskIndicator {
+                    indicator: "mount:tmpfs:/sbin/.magisk/mirror".into()
+                },
+                Evidence::KernelRoot {
+                    indicator: "mount:APatch:/vendor".into()
+                },
+            ]
+        );
+    }
+
+    #[cfg(all(feature = "std", target_os = "linux"))]
+    #[test]
+    fn test_fixture_rootfs() {
+        assert!(collect_root_evidence(&RootFs::fixture("clean")).is_empty());
+        assert!(collect_root_evidence(&RootFs::fixture("missing")).is_empty());
+
+        // /sbin/su is a dangling symlink to /sbin/magisk — still counted
+        assert_eq!(
+            collect_root_evidence(&RootFs::fixture("magisk")),
+            [
+                Evidence::SuspiciousFile {
+                    path: "/system/xbin/su".into()
+                },
+                Evidence::SuspiciousFile {
+                    path: "/sbin/su".into()
+                },
+                Evidence::MagiskIndicator {
+                    indicator: "/sbin/.magisk".into()
+                },
+                Evidence::MagiskIndicator {
+                    indicator: "/data/adb/magisk".into()
+                },
+                Evidence::MagiskIndicator {
+                    indicator: "mount:magisk:/debug_ramdisk".into()
+                },
+                Evidence::MagiskIndicator {
+                    indicator: "mount:magisk:/system/bin".into()
+                },
+            ]
+        );
+        assert_eq!(
+            collect_root_evidence(&RootFs::fixture("kernelsu")),
+            [
+                Evidence::KernelRoot {
+                    indicator: "/data/adb/ksu".into()
+                },
+                Evidence::KernelRoot {
+                    indicator: "/data/adb/ksud".into()
+                },
+                Evidence::KernelRoot {
+                    indicator: "mount:KSU:/system".into()
+                },
+                Evidence::KernelRoot {
+                    indicator: "mount:KSU:/vendor".into()
+                },
+            ]
+        );
+    }
+}
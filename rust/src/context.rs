@@ -0,0 +1,82 @@
+// rust/src/context.rs — Domain-Separated Signing Contexts Eternal (FIPS 204 `ctx` per message type)
+//
+// Every ML-DSA signature is bound to exactly one MessageKind, so an integrity report signature
+// can never be replayed as a key rotation, log export, ledger entry or tree head signature (or vice versa).
+
+/// What a signature is over — mapped 1:1 to the FIPS 204 context string
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+pub enum MessageKind {
+    AttestationReport,
+    KeyRotation,
+    LogExport,
+    LedgerEntry,
+    TreeHead,
+}
+
+impl MessageKind {
+    pub const ALL: [MessageKind; 5] = [
+        MessageKind::AttestationReport,
+        MessageKind::KeyRotation,
+        MessageKind::LogExport,
+        MessageKind::LedgerEntry,
+        MessageKind::TreeHead,
+    ];
+
+    /// FIPS 204 context string (≤ 255 bytes, never empty)
+    pub fn context(self) -> &'static [u8] {
+        match self {
+            MessageKind::AttestationReport => b"MercyShieldPlus/attestation-report/v1",
+            MessageKind::KeyRotation => b"MercyShieldPlus/key-rotation/v1",
+            MessageKind::LogExport => b"MercyShieldPlus/log-export/v1",
+            MessageKind::LedgerEntry => b"MercyShieldPlus/ledger-entry/v1",
+            MessageKind::TreeHead => b"MercyShieldPlus/tree-head/v1",
+        }
+    }
+
+    /// Wire name used by the verifier API and the JS bindings
+    pub fn name(self) -> &'static str {
+        match self {
+            MessageKind::AttestationReport => "attestation_report",
+            MessageKind::KeyRotation => "key_rotation",
+            MessageKind::LogExport => "log_export",
+            MessageKind::LedgerEntry => "ledger_entry",
+            MessageKind::TreeHead => "tree_head",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        MessageKind::ALL
+            .into_iter()
+            .find(|kind| kind.name() == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contexts_distinct_and_within_fips204_limit() {
+        for (i, a) in MessageKind::ALL.iter().enumerate() {
+            assert!(!a.context().is_empty() && a.context().len() <= 255);
+            for b in &MessageKind::ALL[i + 1..] {
+                assert_ne!(
+                    a.context(),
+                    b.context(),
+                    "{:?} and {:?} share a context",
+                    a,
+                    b
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_names_roundtrip() {
+        for kind in MessageKind::ALL {
+            assert_eq!(MessageKind::from_name(kind.name()), Some(kind));
+        }
+        assert_eq!(MessageKind::from_name("AttestationReport"), None);
+    }
+}
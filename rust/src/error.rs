@@ -0,0 +1,89 @@
+// rust/src/error.rs — Typed Mercy Errors Eternal (uniffi → Kotlin MercyException)
+use core::fmt;
+
+/// Every fallible uniffi export returns this — no panics across the FFI boundary
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Error), uniffi(flat_error))]
+pub enum MercyError {
+    /// Input string was not valid base64
+    InvalidBase64,
+    /// Public/secret key bytes failed to parse (wrong length or encoding)
+    InvalidKey,
+    /// Signature bytes failed to parse
+    InvalidSignature,
+    /// Signing, encapsulation or AEAD operation failed
+    CryptoFailure,
+    /// Attestation blob is truncated, has bad magic or malformed fields
+    InvalidBlob,
+    /// Attestation blob version is not understood by this build
+    UnsupportedVersion,
+    /// Blob header names a KEM/AEAD/signature algorithm this build does not support
+    UnsupportedAlgorithm,
+    /// Blob key id does not match the KEM secret key used to open it
+    WrongRecipient,
+    /// AEAD tag check failed — blob tampered or sealed under a different key
+    DecryptionFailed,
+    /// Segmented stream ended before its final segment
+    StreamTruncated,
+    /// Encoded key names a different algorithm (or PEM label) than the caller expected
+    KeyTypeMismatch,
+    /// Stateful object used after it was finalized (or its lock was poisoned)
+    InvalidState,
+    /// Rotation epoch is not newer than the enrolled key's epoch (replayed or rolled-back rotation)
+    StaleEpoch,
+    /// Rotation statement not cross-signed by the enrolled key and its successor
+    RotationRejected,
+    /// Key backup passphrase empty or stored KDF parameters out of range
+    InvalidBackupParams,
+    /// Attestation challenge never issued, already used, or malformed
+    ChallengeUnknown,
+    /// Attestation challenge answered after its expiry
+    ChallengeExpired,
+    /// Guard-paged secret memory could not be mapped or protected
+    SecureMemory,
+    /// Merkle leaf index or tree size outside the current log
+    InvalidTreeSize,
+    /// Risk policy document malformed or internally inconsistent
+    InvalidPolicy,
+    /// Integrity report JSON does not match the report schema
+    InvalidReport,
+    /// JSON is not I-JSON (duplicate names, lone surrogates, out-of-range numbers) or not JSON at all
+    InvalidJson,
+    /// Verifier already holds its maximum of unexpired challenges — retry later
+    ChallengeStoreFull,
+}
+
+impl fmt::Display for MercyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            MercyError::InvalidBase64 => "invalid base64 input",
+            MercyError::InvalidKey => "invalid key encoding",
+            MercyError::InvalidSignature => "invalid signature encoding",
+            MercyError::CryptoFailure => "cryptographic operation failed",
+            MercyError::InvalidBlob => "malformed attestation blob",
+            MercyError::UnsupportedVersion => "unsupported attestation blob version",
+            MercyError::UnsupportedAlgorithm => "unsupported algorithm id in blob header",
+            MercyError::WrongRecipient => "blob sealed to a different recipient key",
+            MercyError::DecryptionFailed => "blob authentication failed",
+            MercyError::StreamTruncated => "encrypted stream truncated",
+            MercyError::KeyTypeMismatch => "key encoding is for a different key type",
+            MercyError::InvalidState => "object already finalized",
+            MercyError::StaleEpoch => "rotation epoch is stale",
+            MercyError::RotationRejected => "key rotation statement rejected",
+            MercyError::InvalidBackupParams => "key backup passphrase or kdf parameters rejected",
+            MercyError::ChallengeUnknown => "unknown or already used challenge",
+            MercyError::ChallengeExpired => "challenge expired",
+            MercyError::SecureMemory => "secure memory allocation failed",
+            MercyError::InvalidTreeSize => "leaf index or tree size out of range",
+            MercyError::InvalidPolicy => "invalid risk policy",
+            MercyError::InvalidReport => "malformed integrity report",
+            MercyError::InvalidJson => "input cannot be canonicalized as I-JSON",
+            MercyError::ChallengeStoreFull => "too many outstanding challenges",
+        };
+        f.write_str(msg)
+    }
+}
+
+impl core::error::Error for MercyError {}
+
+pub type MercyResult<T> = Result<T, MercyError>;
@@ -0,0 +1,256 @@
+// rust/src/evidence.rs — Typed Integrity Evidence Eternal (what was found, where, and how bad)
+//
+// Every finding is an `Evidence` variant carrying the raw observed values, so the verifier and the
+// UI can reason about it instead of parsing display strings. Severity and source are fixed per
+// variant; the policy (`policy.rs`) scores findings by their `EvidenceKind`.
+//
+// On the wire a finding is `{"type": <EvidenceKind name>, <raw values>}`. Severity and source are
+// not serialized: they follow from the type, and a reader must never take them from the client.
+#[cfg(feature = "schema")]
+use crate::schema_prelude::*;
+use alloc::string::String;
+use serde::{Deserialize, Serialize};
+
+use crate::policy::EvidenceKind;
+
+/// How strongly a finding indicates a compromised device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
+/// Where a finding was observed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+pub enum EvidenceSource {
+    FileSystem,
+    SystemProperty,
+    PackageManager,
+    Kernel,
+    AppSignature,
+    ApkFile,
+    PlayIntegrity,
+    /// The app's own process state (`/proc/self`, `/proc/net`)
+    Process,
+}
+
+impl EvidenceSource {
+    pub fn name(self) -> &'static str {
+        match self {
+            EvidenceSource::FileSystem => "file_system",
+            EvidenceSource::SystemProperty => "system_property",
+            EvidenceSource::PackageManager => "package_manager",
+            EvidenceSource::Kernel => "kernel",
+            EvidenceSource::AppSignature => "app_signature",
+            EvidenceSource::ApkFile => "apk_file",
+            EvidenceSource::PlayIntegrity => "play_integrity",
+            EvidenceSource::Process => "process",
+        }
+    }
+}
+
+/// One integrity finding with its raw values
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Evidence {
+    /// su binary, busybox or other root artifact present on disk
+    SuspiciousFile { path: String },
+    /// System property with an insecure value (`ro.debuggable=1`, test-keys, ...)
+    TamperProp { name: String, value: String },
+    /// Known root manager / hiding app installed
+    RootPackage { package: String },
+    /// Magisk / Zygisk artifact (daemon socket, module dir, ...)
+    #[serde(rename = "magisk")]
+    MagiskIndicator { indicator: String },
+    /// Kernel-level root (KernelSU, APatch)
+    KernelRoot { indicator: String },
+    /// Signing certificate fingerprint differs from the pinned release cert
+    SignatureMismatch { expected: String, actual: String },
+    /// base.apk digest differs from the release build
+    ApkChecksumMismatch { expected: String, actual: String },
+    /// No usable Play Integrity token — `reason` is what the client got instead
+    PlayTokenMissing { reason: String },
+    /// Another process is ptrace-attached (`TracerPid` in /proc/self/status)
+    #[serde(rename = "debugger")]
+    DebuggerAttached { tracer_pid: u32 },
+    /// Frida trace: agent mapping, gum-js thread, linjector pipe or listening default port
+    #[serde(rename = "frida")]
+    FridaIndicator { indicator: String },
+    /// Anonymous read-write-execute mapping (injected code) — `region` is the address range
+    RwxMemory { region: String },
+}
+
+impl Evidence {
+    /// Policy rule this finding is scored under
+    pub fn kind(&self) -> EvidenceKind {
+        match self {
+            Evidence::SuspiciousFile { .. } => EvidenceKind::SuspiciousFile,
+            Evidence::TamperProp { .. } => EvidenceKind::TamperProp,
+            Evidence::RootPackage { .. } => EvidenceKind::RootPackage,
+            Evidence::MagiskIndicator { .. } => EvidenceKind::Magisk,
+            Evidence::KernelRoot { .. } => EvidenceKind::KernelRoot,
+            Evidence::SignatureMismatch { .. } => EvidenceKind::SignatureMismatch,
+            Evidence::ApkChecksumMismatch { .. } => EvidenceKind::ApkChecksumMismatch,
+            Evidence::PlayTokenMissing { .. } => EvidenceKind::PlayTokenMissing,
+            Evidence::DebuggerAttached { .. } => EvidenceKind::Debugger,
+            Evidence::FridaIndicator { .. } => EvidenceKind::Frida,
+            Evidence::RwxMemory { .. } => EvidenceKind::RwxMemory,
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            Evidence::TamperProp { .. }
+            | Evidence::RootPackage { .. }
+            | Evidence::PlayTokenMissing { .. } => Severity::Medium,
+            Evidence::SuspiciousFile { .. }
+            | Evidence::MagiskIndicator { .. }
+            | Evidence::RwxMemory { .. } => Severity::High,
+            Evidence::KernelRoot { .. }
+            | Evidence::SignatureMismatch { .. }
+            | Evidence::ApkChecksumMismatch { .. }
+            | Evidence::DebuggerAttached { .. }
+            | Evidence::FridaIndicator { .. } => Severity::Critical,
+        }
+    }
+
+    pub fn source(&self) -> EvidenceSource {
+        match self {
+            Evidence::SuspiciousFile { .. } | Evidence::MagiskIndicator { .. } => {
+                EvidenceSource::FileSystem
+            }
+            Evidence::TamperProp { .. } => EvidenceSource::SystemProperty,
+            Evidence::RootPackage { .. } => EvidenceSource::PackageManager,
+            Evidence::KernelRoot { .. } => EvidenceSource::Kernel,
+            Evidence::SignatureMismatch { .. } => EvidenceSource::AppSignature,
+            Evidence::ApkChecksumMismatch { .. } => EvidenceSource::ApkFile,
+            Evidence::PlayTokenMissing { .. } => EvidenceSource::PlayIntegrity,
+            Evidence::DebuggerAttached { .. }
+            | Evidence::FridaIndicator { .. }
+            | Evidence::RwxMemory { .. } => EvidenceSource::Process,
+        }
+    }
+
+    /// `name=value` as reported by the Kotlin prop scan (a bare name gets an empty value)
+    pub fn tamper_prop(raw: &str) -> Self {
+        let (name, value) = raw.split_once('=').unwrap_or((raw, ""));
+        Evidence::TamperProp {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
+
+/// Severity of a finding (uniffi enums carry no methods)
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn evidence_severity(evidence: Evidence) -> Severity {
+    evidence.severity()
+}
+
+/// Where a finding was observed (uniffi enums carry no methods)
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn evidence_source(evidence: Evidence) -> EvidenceSource {
+    evidence.source()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tamper_prop_split() {
+        assert_eq!(
+            Evidence::tamper_prop("ro.debuggable=1"),
+            Evidence::TamperProp {
+                name: "ro.debuggable".into(),
+                value: "1".into()
+            }
+        );
+        assert_eq!(
+            Evidence::tamper_prop("ro.build.tags"),
+            Evidence::TamperProp {
+                name: "ro.build.tags".into(),
+                value: String::new()
+            }
+        );
+    }
+
+    #[test]
+    fn test_tamper_evidence_is_critical() {
+        let sig = Evidence::SignatureMismatch {
+            expected: "AA:BB".into(),
+            actual: "CC:DD".into(),
+        };
+        assert_eq!(
+            (sig.kind(), sig.severity(), sig.source()),
+            (
+                EvidenceKind::SignatureMismatch,
+                Severity::Critical,
+                EvidenceSource::AppSignature
+            )
+        );
+        assert!(Severity::Critical > Severity::High && Severity::Medium > Severity::Low);
+    }
+
+    #[test]
+    fn test_type_tag_is_kind_name() {
+        let all = [
+            Evidence::SuspiciousFile {
+                path: "/sbin/su".into(),
+            },
+            Evidence::tamper_prop("ro.secure=0"),
+            Evidence::RootPackage {
+                package: "com.topjohnwu.magisk".into(),
+            },
+            Evidence::MagiskIndicator {
+                indicator: "/data/adb/magisk".into(),
+            },
+            Evidence::KernelRoot {
+                indicator: "/data/adb/ksud".into(),
+            },
+            Evidence::SignatureMismatch {
+                expected: "AA".into(),
+                actual: "BB".into(),
+            },
+            Evidence::ApkChecksumMismatch {
+                expected: "00".into(),
+                actual: "11".into(),
+            },
+            Evidence::PlayTokenMissing {
+                reason: "null_token".into(),
+            },
+            Evidence::DebuggerAttached { tracer_pid: 4242 },
+            Evidence::FridaIndicator {
+                indicator: "thread:gum-js-loop".into(),
+            },
+            Evidence::RwxMemory {
+                region: "7f12340000-7f12350000".into(),
+            },
+        ];
+        for finding in all {
+            let json = serde_json::to_value(&finding).unwrap();
+            assert_eq!(json["type"], finding.kind().name());
+            assert_eq!(serde_json::from_value::<Evidence>(json).unwrap(), finding);
+        }
+        // Client-supplied severity is not part of the format
+        let json = r#"{"type":"magisk","indicator":"x","severity":"low"}"#;
+        assert!(serde_json::from_str::<Evidence>(json).is_err());
+    }
+}
@@ -0,0 +1,271 @@
+// rust/src/instrumentation.rs — Debugger / ptrace / Frida Detection Eternal (the app's own /proc)
+//
+// Runtime instrumentation leaves traces in the instrumented process itself:
+//
+//   /proc/self/status        TracerPid ≠ 0 while a debugger (or frida-inject) is ptrace-attached
+//   /proc/self/maps          frida-agent / gum-js mappings, anonymous rwx pages holding injected code
+//   /proc/self/task/*/comm   Frida's worker threads (gum-js-loop, pool-frida)
+//   /proc/self/fd/*          linjector pipes left by the injector
+//   /proc/net/tcp{,6}        frida-server listening on its default ports
+//
+// All reads go through `collector::Fs`, so tests replay captured proc files from
+// `tests/fixtures/rootfs/<case>/proc`. Parsers are lenient: a line they do not understand is
+// skipped, never an error.
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use crate::collector::Fs;
+use crate::evidence::Evidence;
+
+pub const STATUS_PATH: &str = "/proc/self/status";
+pub const MAPS_PATH: &str = "/proc/self/maps";
+pub const TASK_DIR: &str = "/proc/self/task";
+pub const FD_DIR: &str = "/proc/self/fd";
+pub const TCP_PATHS: &[&str] = &["/proc/net/tcp", "/proc/net/tcp6"];
+
+/// frida-server's default listening ports
+pub const FRIDA_PORTS: &[u16] = &[27042, 27043];
+
+/// Substrings of Frida library / memfd mapping names
+const FRIDA_MAP_MARKERS: &[&str] = &["frida-agent", "frida-gadget", "frida-loader", "gum-js"];
+
+/// Thread names only Frida's agent spawns in the target. The agent also starts `gmain` / `gdbus`,
+/// but every GLib-linked app has those, so they are not evidence on their own
+const FRIDA_THREADS: &[&str] = &["gum-js-loop", "pool-frida"];
+
+/// Substrings of fd targets left by the injector
+const FRIDA_FD_MARKERS: &[&str] = &["linjector", "frida"];
+
+/// ART's JIT cache — mapped rwx by Android 9 and earlier, not injected code
+const JIT_CACHE_MAPPING: &str = "[anon:dalvik-jit-code-cache";
+
+/// `st` column value for a listening socket in /proc/net/tcp
+const TCP_LISTEN: &str = "0A";
+
+/// Every instrumentation trace visible through `fs`
+pub fn collect_instrumentation_evidence(fs: &impl Fs) -> Vec<Evidence> {
+    let mut evidence = Vec::new();
+    if let Some(tracer_pid) = fs
+        .read_to_string(STATUS_PATH)
+        .and_then(|status| tracer_pid(&status))
+    {
+        evidence.push(Evidence::DebuggerAttached { tracer_pid });
+    }
+    if let Some(maps) = fs.read_to_string(MAPS_PATH) {
+        evidence.extend(maps_evidence(&maps));
+    }
+    for tid in fs.read_dir(TASK_DIR) {
+        let Some(comm) = fs.read_to_string(&format!("{}/{}/comm", TASK_DIR, tid)) else {
+            continue;
+        };
+        let name = comm.trim_end();
+        if FRIDA_THREADS.contains(&name) {
+            push_unique(
+                &mut evidence,
+                Evidence::FridaIndicator {
+                    indicator: format!("thread:{}", name),
+                },
+            );
+        }
+    }
+    for fd in fs.read_dir(FD_DIR) {
+        let Some(target) = fs.read_link(&format!("{}/{}", FD_DIR, fd)) else {
+            continue;
+        };
+        if FRIDA_FD_MARKERS
+            .iter()
+            .any(|marker| target.contains(marker))
+        {
+            push_unique(
+                &mut evidence,
+                Evidence::FridaIndicator {
+                    indicator: format!("fd:{}", target),
+                },
+            );
+        }
+    }
+    for path in TCP_PATHS {
+        for port in fs
+            .read_to_string(path)
+            .map(|tcp| listening_ports(&tcp))
+            .unwrap_or_default()
+        {
+            if FRIDA_PORTS.contains(&port) {
+                push_unique(
+                    &mut evidence,
+                    Evidence::FridaIndicator {
+                        indicator: format!("port:{}", port),
+                    },
+                );
+            }
+        }
+    }
+    evidence
+}
+
+/// Non-zero `TracerPid` from /proc/<pid>/status
+pub fn tracer_pid(status: &str) -> Option<u32> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("TracerPid:"))
+        .and_then(|pid| pid.trim().parse().ok())
+        .filter(|&pid| pid != 0)
+}
+
+/// Frida mappings (one finding per mapped name) and anonymous rwx regions from /proc/<pid>/maps
+pub fn maps_evidence(maps: &str) -> Vec<Evidence> {
+    let mut evidence = Vec::new();
+    for line in maps.lines() {
+        // address perms offset dev inode [pathname — may contain spaces]
+        let mut fields = line.splitn(6, ' ');
+        let (Some(range), Some(perms)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        let name = fields.nth(3).unwrap_or("").trim();
+
+        if FRIDA_MAP_MARKERS.iter().any(|marker| name.contains(marker)) {
+            push_unique(
+                &mut evidence,
+                Evidence::FridaIndicator {
+                    indicator: format!("maps:{}", name),
+                },
+            );
+        } else if perms.starts_with("rwx")
+            && (name.is_empty() || name.starts_with("[anon:"))
+            && !name.starts_with(JIT_CACHE_MAPPING)
+        {
+            evidence.push(Evidence::RwxMemory {
+                region: range.to_string(),
+            });
+        }
+    }
+    evidence
+}
+
+/// Local ports in LISTEN state from /proc/net/tcp or tcp6
+pub fn listening_ports(tcp: &str) -> Vec<u16> {
+    let mut ports = Vec::new();
+    for line in tcp.lines().skip(1) {
+        // sl local_address rem_address st ...
+        let mut fields = line.split_ascii_whitespace();
+        let (Some(local), Some(state)) = (fields.nth(1), fields.nth(1)) else {
+            continue;
+        };
+        let port = local
+            .rsplit_once(':')
+            .and_then(|(_, port)| u16::from_str_radix(port, 16).ok());
+        if let (Some(port), TCP_LISTEN) = (port, state) {
+            if !ports.contains(&port) {
+                ports.push(port);
+            }
+        }
+    }
+    ports
+}
+
+fn push_unique(evidence: &mut Vec<Evidence>, finding: Evidence) {
+    if !evidence.contains(&finding) {
+        evidence.push(finding);
+    }
+}
+
+/// Debugger and Frida traces in this process
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn collect_device_instrumentation_evidence() -> Vec<Evidence> {
+    collect_instrumentation_evidence(&crate::collector::RootFs::system())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracer_pid() {
+        let status = "Name:\tcom.mercyshieldplus\nState:\tS (sleeping)\nTgid:\t8123\nPid:\t8123\nPPid:\t612\nTracerPid:\t8150\nUid:\t10234\t10234\t10234\t10234\n";
+        assert_eq!(tracer_pid(status), Some(8150));
+        assert_eq!(tracer_pid(&status.replace("8150", "0")), None);
+        assert_eq!(tracer_pid("Name:\tx\n"), None);
+        assert_eq!(tracer_pid("TracerPid:\tnope\n"), None);
+    }
+
+    #[test]
+    fn test_maps_frida_and_rwx() {
+        let maps = "\
+70b2a5c000-70b2a7e000 r--p 00000000 fd:06 1843  /apex/com.android.runtime/lib64/bionic/libc.so
+7a11e00000-7a11f2c000 r--p 00000000 fe:2c 9012  /data/local/tmp/re.frida.server/frida-agent-64.so
+7a11f2c000-7a1236a000 r-xp 0012c000 fe:2c 9012  /data/local/tmp/re.frida.server/frida-agent-64.so
+7b00000000-7b00010000 rwxp 00000000 00:00 0
+7b00020000-7b00030000 rwxp 00000000 00:00 0  [anon:dalvik-jit-code-cache]
+7b00040000-7b00050000 rwxp 00000000 fd:06 77  /data/app/~~x/lib/arm64/libpatched.so
+7b00060000-7b00070000 rwxp 00000000 00:00 0  [anon:libc_malloc]
+7c00000000-7c00001000 r--s 00000000 00:01 31  /memfd:gum-js-loop (deleted)
+bogus
+";
+        assert_eq!(
+            maps_evidence(maps),
+            [
+                Evidence::FridaIndicator {
+                    indicator: "maps:/data/local/tmp/re.frida.server/frida-agent-64.so".into()
+                },
+                Evidence::RwxMemory {
+                    region: "7b00000000-7b00010000".into()
+                },
+                Evidence::RwxMemory {
+                    region: "7b00060000-7b00070000".into()
+                },
+                Evidence::FridaIndicator {
+                    indicator: "maps:/memfd:gum-js-loop (deleted)".into()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_listening_ports() {
+        let tcp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000:69A2 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 41523 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000 10234        0 41600 1 0000000000000000 100 0 0 10 0
+   2: 0F02000A:A3C4 8EFB1A8E:01BB 01 00000000:00000000 00:00000000 00000000 10234        0 41777 1 0000000000000000 20 4 30 10 -1
+";
+        // 27042 and 8080 listen; the established 443 connection does not count
+        assert_eq!(listening_ports(tcp), [27042, 8080]);
+        assert!(listening_ports("header only\n").is_empty());
+    }
+
+    #[cfg(all(feature = "std", target_os = "linux"))]
+    #[test]
+    fn test_fixture_proc() {
+        use crate::collector::RootFs;
+
+        assert!(collect_instrumentation_evidence(&RootFs::fixture("clean")).is_empty());
+        assert_eq!(
+            collect_instrumentation_evidence(&RootFs::fixture("traced")),
+            [Evidence::DebuggerAttached { tracer_pid: 9411 }]
+        );
+        assert_eq!(
+            collect_instrumentation_evidence(&RootFs::fixture("frida")),
+            [
+                Evidence::FridaIndicator {
+                    indicator: "maps:/data/local/tmp/re.frida.server/frida-agent-64.so".into()
+                },
+                Evidence::RwxMemory {
+                    region: "7b2c400000-7b2c410000".into()
+                },
+                Evidence::FridaIndicator {
+                    indicator: "thread:gum-js-loop".into()
+                },
+                Evidence::FridaIndicator {
+                    indicator: "thread:pool-frida".into()
+                },
+                Evidence::FridaIndicator {
+                    indicator: "fd:/data/local/tmp/re.frida.server/linjector-2".into()
+                },
+                Evidence::FridaIndicator {
+                    indicator: "port:27042".into()
+                },
+            ]
+        );
+    }
+}
@@ -1,84 +1,311 @@
 // rust/src/integrity.rs — Device Integrity Fortress Eternal (Custom + Play Integrity Token)
+//
+// Scoring is delegated to a `RiskPolicy` (see `policy.rs`); the policy id and version travel in
+// every report so a score can always be traced back to the weights that produced it.
+//
+// The JSON form is serde-derived and carries `schema_version`; `schema/integrity-report.schema.json`
+// is generated from these types (`cargo run --features schema --bin report-schema`) and is what the
+// verifier validates incoming reports against.
+#[cfg(feature = "schema")]
+use crate::schema_prelude::*;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
 use alloc::vec::Vec;
-use alloc::string::String;
-use uniffi::export;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+use crate::error::{MercyError, MercyResult};
+use crate::evidence::Evidence;
+use crate::policy::RiskPolicy;
+
+/// `schema_version` written by this build — bump on any incompatible change to the report JSON
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum IntegrityVerdict {
     Genuine,
     Suspicious,
     Compromised,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
 pub struct IntegrityReport {
+    pub schema_version: u32, // REPORT_SCHEMA_VERSION
     pub verdict: IntegrityVerdict,
-    pub details: Vec<String>,
-    pub risk_score: u8,  // 0-100
-    pub play_token: String,  // Raw token for server verify
+    pub evidence: Vec<Evidence>,
+    #[cfg_attr(feature = "schema", schemars(range(max = 100)))]
+    pub risk_score: u8, // 0-100
+    pub play_token: String, // Raw token for server verify
+    pub policy_id: String,
+    pub policy_version: u32,
 }
 
-/// Evaluate integrity — Kotlin passes evidences + raw Play token
-#[export]
+/// Evaluate integrity — Kotlin passes typed evidence + raw Play token (scored with the shipped policy)
+#[cfg_attr(feature = "uniffi", uniffi::export)]
 pub fn evaluate_integrity(
+    evidence: Vec<Evidence>,
+    play_token: String, // Raw token string (or "null_token" on fail)
+) -> IntegrityReport {
+    evaluate_with_policy(&RiskPolicy::default_policy(), evidence, play_token)
+}
+
+/// `evaluate_integrity` under a caller-supplied policy document (JSON, see `policy.rs`)
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn evaluate_integrity_with_policy(
+    evidence: Vec<Evidence>,
+    play_token: String,
+    policy_json: String,
+) -> MercyResult<IntegrityReport> {
+    let policy = RiskPolicy::from_json(&policy_json)?;
+    Ok(evaluate_with_policy(&policy, evidence, play_token))
+}
+
+/// The shipped policy document, e.g. as a starting point for a server-pushed override
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn default_risk_policy_json() -> String {
+    crate::policy::DEFAULT_POLICY_JSON.to_string()
+}
+
+pub fn evaluate_with_policy(
+    policy: &RiskPolicy,
+    mut evidence: Vec<Evidence>,
+    play_token: String,
+) -> IntegrityReport {
+    // Play token presence (basic client check; server does the full verify)
+    if play_token.is_empty() || play_token == "null_token" {
+        evidence.push(Evidence::PlayTokenMissing {
+            reason: play_token.clone(),
+        });
+    }
+
+    let mut counts = BTreeMap::new();
+    for finding in &evidence {
+        *counts.entry(finding.kind()).or_insert(0) += 1;
+    }
+    let counts: Vec<_> = counts.into_iter().collect();
+    let (risk_score, verdict) = policy.score(&counts);
+
+    IntegrityReport {
+        schema_version: REPORT_SCHEMA_VERSION,
+        verdict,
+        evidence,
+        risk_score,
+        play_token,
+        policy_id: policy.id.clone(),
+        policy_version: policy.version,
+    }
+}
+
+/// Evidence from the flat pre-typed inputs (file paths, `name=value` props, Magisk flag) — the C ABI
+/// still takes these
+pub fn legacy_evidence(
     suspicious_files: Vec<String>,
     suspicious_props: Vec<String>,
     magisk_indicators: bool,
-    play_token: String,  // Raw token string (or "null_token" on fail)
-) -> IntegrityReport {
-    let mut details = Vec::new();
-    let mut score: u8 = 0;
+) -> Vec<Evidence> {
+    let mut evidence: Vec<Evidence> = suspicious_files
+        .into_iter()
+        .map(|path| Evidence::SuspiciousFile { path })
+        .collect();
+    evidence.extend(
+        suspicious_props
+            .iter()
+            .map(|raw| Evidence::tamper_prop(raw)),
+    );
+    if magisk_indicators {
+        evidence.push(Evidence::MagiskIndicator {
+            indicator: "magisk".to_string(),
+        });
+    }
+    evidence
+}
 
-    if !suspicious_files.is_empty() {
-        score += 40;
-        details.push(format!("Suspicious files: {:?}", suspicious_files));
+/// Report to canonical (RFC 8785) JSON — includes play_token for signing/blob
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn report_to_json(report: IntegrityReport) -> String {
+    crate::jcs::to_canonical_string(&report).expect("report is I-JSON")
+}
+
+/// Parse a report produced by `report_to_json` — unknown or duplicate fields and other schema
+/// versions are rejected rather than guessed at
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn report_from_json(json: String) -> MercyResult<IntegrityReport> {
+    let report: IntegrityReport =
+        serde_json::from_str(&json).map_err(|_| MercyError::InvalidReport)?;
+    if report.schema_version != REPORT_SCHEMA_VERSION {
+        return Err(MercyError::UnsupportedVersion);
     }
+    Ok(report)
+}
 
-    if !suspicious_props.is_empty() {
-        score += 30;
-        details.push(format!("Tamper props: {:?}", suspicious_props));
+/// JSON Schema (draft 7) for `report_to_json` output — committed as `schema/integrity-report.schema.json`
+#[cfg(feature = "schema")]
+pub fn report_json_schema() -> String {
+    let schema = schemars::schema_for!(IntegrityReport);
+    serde_json::to_string_pretty(&schema).expect("schema serializes") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn report_with(play_token: &str, evidence: Vec<Evidence>) -> IntegrityReport {
+        IntegrityReport {
+            schema_version: REPORT_SCHEMA_VERSION,
+            verdict: IntegrityVerdict::Suspicious,
+            evidence,
+            risk_score: 30,
+            play_token: play_token.to_string(),
+            policy_id: "mercyshieldplus-default".to_string(),
+            policy_version: 2,
+        }
     }
 
-    if magisk_indicators {
-        score += 20;
-        details.push("Magisk/Zygisk indicators".to_string());
+    #[test]
+    fn test_report_json_layout() {
+        let report = report_with("tok", vec![Evidence::tamper_prop("ro.debuggable=1")]);
+        assert_eq!(
+            report_to_json(report),
+            concat!(
+                r#"{"evidence":[{"name":"ro.debuggable","type":"tamper_prop","value":"1"}],"play_token":"tok","#,
+                r#""policy_id":"mercyshieldplus-default","policy_version":2,"risk_score":30,"schema_version":1,"verdict":"Suspicious"}"#
+            )
+        );
     }
 
-    // Play token presence (basic client check; server full verify)
-    if play_token.is_empty() || play_token == "null_token" {
-        score += 35;
-        details.push("Play Integrity token unavailable/failed".to_string());
-    } else {
-        details.push("Play Integrity token acquired — server verify pending".to_string());
-        // Client-side basic: no decode needed (server does full)
+    #[test]
+    fn test_malicious_strings_round_trip() {
+        let hostile = [
+            r#"","verdict":"Genuine","risk_score":0,"x":""#,
+            "\"}]}\u{0}",
+            "back\\slash\\\"",
+            "\n\r\t\u{1}\u{1f}\u{7f}",
+            "\u{2028}\u{2029}</script><!--",
+            "\u{feff}\u{202e}gnp.exe",
+            "𝕞𝕖𝕣𝕔𝕪 🛡️",
+        ];
+        for s in hostile {
+            let report = report_with(
+                s,
+                vec![
+                    Evidence::SuspiciousFile {
+                        path: s.to_string(),
+                    },
+                    Evidence::TamperProp {
+                        name: s.to_string(),
+                        value: s.to_string(),
+                    },
+                ],
+            );
+            let json = report_to_json(report.clone());
+            let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+            assert_eq!(value["play_token"], s);
+            assert_eq!(value["verdict"], "Suspicious");
+            assert_eq!(value.as_object().unwrap().len(), 7);
+            assert!(!json.contains('\u{0}') && !json.contains('\n'));
+            assert!(crate::jcs::is_canonical(&json));
+            assert_eq!(report_from_json(json).unwrap(), report);
+        }
     }
 
-    score = score.min(100);
-    let verdict = if score == 0 {
-        IntegrityVerdict::Genuine
-    } else if score < 50 {
-        IntegrityVerdict::Suspicious
-    } else {
-        IntegrityVerdict::Compromised
-    };
+    #[test]
+    fn test_report_from_json_rejects_nonconforming() {
+        let good = report_to_json(report_with("tok", vec![]));
+        assert!(report_from_json(good.clone()).is_ok());
 
-    IntegrityReport {
-        verdict,
-        details,
-        risk_score: score,
-        play_token,
+        let dup_verdict = good.replace(
+            r#""verdict":"Suspicious""#,
+            r#""verdict":"Suspicious","verdict":"Genuine""#,
+        );
+        let extra_field = good.replace(r#""risk_score":30"#, r#""risk_score":30,"trusted":true"#);
+        let bad_evidence = good.replace(
+            r#""evidence":[]"#,
+            r#""evidence":[{"type":"xposed","path":"x"}]"#,
+        );
+        let wide_score = good.replace(r#""risk_score":30"#, r#""risk_score":300"#);
+        let lone_surrogate = good.replace(r#""tok""#, r#""\ud800""#);
+        for json in [
+            dup_verdict,
+            extra_field,
+            bad_evidence,
+            wide_score,
+            lone_surrogate,
+            "[]".to_string(),
+        ] {
+            assert_eq!(
+                report_from_json(json.clone()),
+                Err(MercyError::InvalidReport),
+                "{}",
+                json
+            );
+        }
+        let future = good.replace(r#""schema_version":1"#, r#""schema_version":2"#);
+        assert_eq!(
+            report_from_json(future),
+            Err(MercyError::UnsupportedVersion)
+        );
     }
-}
 
-/// Report to JSON (includes play_token for signing/blob)
-#[export]
-pub fn report_to_json(report: &IntegrityReport) -> String {
-    // Manual JSON mercy (expand with serde later if needed)
-    format!(
-        r#"{{"verdict":"{:?}","risk_score":{},"details":{},"play_token":"{}"}}"#,
-        report.verdict,
-        report.risk_score,
-        serde_json::to_string(&report.details).unwrap_or("[]".to_string()),
-        report.play_token
-    )
+    #[cfg(feature = "schema")]
+    #[test]
+    fn test_committed_schema_is_current() {
+        // Regenerate with: cargo run --features schema --bin report-schema > schema/integrity-report.schema.json
+        assert_eq!(
+            include_str!("../schema/integrity-report.schema.json"),
+            report_json_schema()
+        );
+    }
+
+    #[test]
+    fn test_report_carries_policy_version() {
+        let evidence = legacy_evidence(vec!["/system/xbin/su".to_string()], vec![], true);
+        let report = evaluate_integrity(evidence, "null_token".to_string());
+        assert_eq!(
+            (report.risk_score, report.verdict),
+            (95, IntegrityVerdict::Compromised)
+        );
+        assert_eq!(
+            (report.policy_id.as_str(), report.policy_version),
+            ("mercyshieldplus-default", 3)
+        );
+        assert_eq!(
+            report.evidence.last(),
+            Some(&Evidence::PlayTokenMissing {
+                reason: "null_token".to_string()
+            })
+        );
+
+        let strict = r#"{"id":"strict","version":7,"max_score":100,"thresholds":{"suspicious":1,"compromised":50},
+                         "rules":{},"hard_fail":["kernel_root"]}"#;
+        let kernel = vec![Evidence::KernelRoot {
+            indicator: "/data/adb/ksud".to_string(),
+        }];
+        let report =
+            evaluate_integrity_with_policy(kernel, "tok".to_string(), strict.to_string()).unwrap();
+        assert_eq!(
+            (report.risk_score, report.verdict),
+            (0, IntegrityVerdict::Compromised)
+        );
+        assert!(report_to_json(report).contains(r#""policy_id":"strict","policy_version":7,"#));
+        assert!(evaluate_integrity_with_policy(vec![], String::new(), "{}".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_tampered_app_is_compromised_with_valid_token() {
+        let evidence = vec![Evidence::SignatureMismatch {
+            expected: "AA:BB".to_string(),
+            actual: "CC:DD".to_string(),
+        }];
+        let report = evaluate_integrity(evidence, "eyJhbGciOi...".to_string());
+        assert_eq!(
+            (report.risk_score, report.verdict),
+            (50, IntegrityVerdict::Compromised)
+        );
+        assert_eq!(report.evidence.len(), 1);
+    }
 }
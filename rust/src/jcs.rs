@@ -0,0 +1,365 @@
+// rust/src/jcs.rs — Canonical JSON Eternal (RFC 8785 JSON Canonicalization Scheme)
+//
+// Anything that is signed or hashed as JSON goes through here first, so two serializers (Gson on
+// the device, serde_json on the verifier) can never disagree about the bytes:
+//
+//   - no insignificant whitespace
+//   - object members sorted by the UTF-16 code units of their names
+//   - strings escaped minimally (`\"`, `\\`, short escapes, `\u00xx` for other controls)
+//   - numbers as IEEE-754 doubles printed like ECMAScript `Number.prototype.toString`
+//
+// Input must be I-JSON (RFC 7493): duplicate member names, lone surrogates and numbers outside the
+// double range are rejected instead of being silently resolved one way or the other.
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Write as _};
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::Serialize;
+
+use crate::error::{MercyError, MercyResult};
+
+/// Canonical form of a JSON text
+pub fn canonicalize(json: &str) -> MercyResult<String> {
+    let value: Value = serde_json::from_str(json).map_err(|_| MercyError::InvalidJson)?;
+    let mut out = String::with_capacity(json.len());
+    write_value(&mut out, &value);
+    Ok(out)
+}
+
+/// Canonical JSON of any serializable value
+pub fn to_canonical_string<T: Serialize + ?Sized>(value: &T) -> MercyResult<String> {
+    canonicalize(&serde_json::to_string(value).map_err(|_| MercyError::InvalidJson)?)
+}
+
+/// Whether `json` is already byte-for-byte canonical
+pub fn is_canonical(json: &str) -> bool {
+    canonicalize(json).is_ok_and(|canonical| canonical == json)
+}
+
+/// Canonicalize JSON before signing or hashing it
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn canonicalize_json(json: String) -> MercyResult<String> {
+    canonicalize(&json)
+}
+
+/// Parsed I-JSON — members keep input order until `write_value` sorts them
+enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an I-JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    // Integers are doubles too — 2^53 + 1 canonicalizes to 9007199254740992, as in JS
+    fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::Number(v as f64))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+        Ok(Value::Number(v as f64))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Value, E> {
+        if !v.is_finite() {
+            return Err(E::custom("number outside the double range"));
+        }
+        Ok(Value::Number(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.into()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut seen = BTreeSet::new();
+        let mut members = Vec::new();
+        while let Some(name) = map.next_key::<String>()? {
+            if !seen.insert(name.clone()) {
+                return Err(de::Error::custom("duplicate member name"));
+            }
+            members.push((name, map.next_value()?));
+        }
+        Ok(Value::Object(members))
+    }
+}
+
+fn write_value(out: &mut String, value: &Value) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => write_number(out, *n),
+        Value::String(s) => write_string(out, s),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(out, item);
+            }
+            out.push(']');
+        }
+        Value::Object(members) => {
+            let mut sorted: Vec<&(String, Value)> = members.iter().collect();
+            sorted.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i, (name, member)) in sorted.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(out, name);
+                out.push(':');
+                write_value(out, member);
+            }
+            out.push('}');
+        }
+    }
+}
+
+/// RFC 8785 §3.2.2.2
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\u{c}' => out.push_str("\\f"),
+            '\r' => out.push_str("\\r"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// RFC 8785 §3.2.2.3 — ECMAScript `Number.prototype.toString` for a finite double
+fn write_number(out: &mut String, n: f64) {
+    if n == 0.0 {
+        out.push('0'); // -0 too
+        return;
+    }
+    if n < 0.0 {
+        out.push('-');
+    }
+
+    let (digits, point) = shortest_digits(n.abs());
+    let k = digits.len() as i32;
+
+    if k <= point && point <= 21 {
+        out.push_str(&digits);
+        out.extend(core::iter::repeat_n('0', (point - k) as usize));
+    } else if 0 < point && point <= 21 {
+        let (int, frac) = digits.split_at(point as usize);
+        let _ = write!(out, "{}.{}", int, frac);
+    } else if -6 < point && point <= 0 {
+        out.push_str("0.");
+        out.extend(core::iter::repeat_n('0', (-point) as usize));
+        out.push_str(&digits);
+    } else {
+        let (first, rest) = digits.split_at(1);
+        out.push_str(first);
+        if !rest.is_empty() {
+            let _ = write!(out, ".{}", rest);
+        }
+        let _ = write!(
+            out,
+            "e{}{}",
+            if point > 0 { "+" } else { "-" },
+            (point - 1).abs()
+        );
+    }
+}
+
+/// Shortest round-trip digits of a positive double: value = 0.<digits> × 10^point
+fn shortest_digits(n: f64) -> (String, i32) {
+    let (mut digits, point) = scientific(&format!("{:e}", n));
+
+    // Exactly halfway between two shortest candidates ECMAScript takes the even one
+    // (1424953923781206.25 → "…206.2"); core::fmt rounds the tie up, so check the exact expansion
+    // (a double never needs more than 767 significant digits)
+    let (exact, exact_point) = scientific(&format!("{:.767e}", n));
+    let exact = exact.trim_end_matches('0');
+    let k = digits.len();
+    if exact_point == point && exact.len() == k + 1 && exact.ends_with('5') {
+        let mut even = String::from(&exact[..k]);
+        let last = even.pop().and_then(|c| c.to_digit(10)).unwrap_or(9);
+        if last < 9 {
+            even.push(char::from_digit(last + last % 2, 10).unwrap_or('0'));
+            if format!("0.{}e{}", even, point).parse::<f64>() == Ok(n) {
+                digits = even;
+            }
+        }
+    }
+    (digits, point)
+}
+
+/// Digits and point of `{:e}` output ("1.2345e-7" → ("12345", -6))
+fn scientific(sci: &str) -> (String, i32) {
+    let (mantissa, exponent) = sci
+        .split_once('e')
+        .expect("LowerExp always has an exponent");
+    let digits = mantissa.chars().filter(|&c| c != '.').collect();
+    (
+        digits,
+        exponent
+            .parse::<i32>()
+            .expect("LowerExp exponent is an integer")
+            + 1,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number(bits: u64) -> String {
+        let mut out = String::new();
+        write_number(&mut out, f64::from_bits(bits));
+        out
+    }
+
+    #[test]
+    fn test_rfc8785_number_vectors() {
+        // RFC 8785 Appendix B
+        for (bits, expected) in [
+            (0x0000000000000000, "0"),
+            (0x8000000000000000, "0"),
+            (0x0000000000000001, "5e-324"),
+            (0x8000000000000001, "-5e-324"),
+            (0x7fefffffffffffff, "1.7976931348623157e+308"),
+            (0xffefffffffffffff, "-1.7976931348623157e+308"),
+            (0x4340000000000000, "9007199254740992"),
+            (0xc340000000000000, "-9007199254740992"),
+            (0x4430000000000000, "295147905179352830000"),
+            (0x44b52d02c7e14af5, "9.999999999999997e+22"),
+            (0x44b52d02c7e14af6, "1e+23"),
+            (0x44b52d02c7e14af7, "1.0000000000000001e+23"),
+            (0x444b1ae4d6e2ef4e, "999999999999999700000"),
+            (0x444b1ae4d6e2ef4f, "999999999999999900000"),
+            (0x444b1ae4d6e2ef50, "1e+21"),
+            (0x3eb0c6f7a0b5ed8c, "9.999999999999997e-7"),
+            (0x3eb0c6f7a0b5ed8d, "0.000001"),
+            (0x41b3de4355555553, "333333333.3333332"),
+            (0x41b3de4355555554, "333333333.33333325"),
+            (0x41b3de4355555555, "333333333.3333333"),
+            (0x41b3de4355555556, "333333333.3333334"),
+            (0x41b3de4355555557, "333333333.33333343"),
+            (0xbecbf647612f3696, "-0.0000033333333333333333"),
+            (0x43143ff3c1cb0959, "1424953923781206.2"),
+        ] {
+            assert_eq!(number(bits), expected, "{:#018x}", bits);
+        }
+    }
+
+    #[test]
+    fn test_rfc8785_sample() {
+        // RFC 8785 §3.2.2
+        let input = r#"{
+          "numbers": [333333333.33333329, 1E30, 4.50,
+                      2e-3, 0.000000000000000000000000001],
+          "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+          "literals": [null, true, false]
+        }"#;
+        let expected = concat!(
+            r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"#,
+            r#""string":"€$\u000f\nA'B\"\\\\\"/"}"#
+        );
+        assert_eq!(canonicalize(input).unwrap(), expected);
+        assert!(is_canonical(expected) && !is_canonical(input));
+    }
+
+    #[test]
+    fn test_rfc8785_sorting() {
+        // RFC 8785 §3.2.3 — UTF-16 order puts U+1F600 (D83D DE00) before U+FB33
+        let input = r#"{
+          "\u20ac": "Euro Sign",
+          "\r": "Carriage Return",
+          "\ufb33": "Hebrew Letter Dalet With Dagesh",
+          "1": "One",
+          "\ud83d\ude00": "Emoji: Grinning Face",
+          "\u0080": "Control",
+          "\u00f6": "Latin Small Letter O With Diaeresis"
+        }"#;
+        let expected = concat!(
+            "{\"\\r\":\"Carriage Return\",\"1\":\"One\",\"\u{80}\":\"Control\",",
+            "\"ö\":\"Latin Small Letter O With Diaeresis\",\"€\":\"Euro Sign\",",
+            "\"😀\":\"Emoji: Grinning Face\",\"\u{fb33}\":\"Hebrew Letter Dalet With Dagesh\"}"
+        );
+        assert_eq!(canonicalize(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_non_i_json_rejected() {
+        for json in [
+            r#"{"a":1,"a":2}"#,
+            r#"{"a":{"b":1,"b":1}}"#,
+            r#"["\ud800"]"#,
+            "[1e400]",
+            "{} {}",
+            "",
+        ] {
+            assert_eq!(canonicalize(json), Err(MercyError::InvalidJson), "{}", json);
+        }
+        assert_eq!(
+            canonicalize("[9007199254740993, -0.0, 1.0]").unwrap(),
+            "[9007199254740992,0,1]"
+        );
+    }
+
+    #[test]
+    fn test_serialized_structs_are_sorted() {
+        #[derive(Serialize)]
+        struct Entry {
+            z: u32,
+            a: &'static str,
+        }
+        assert_eq!(
+            to_canonical_string(&Entry { z: 7, a: "x\u{7f}" }).unwrap(),
+            "{\"a\":\"x\u{7f}\",\"z\":7}"
+        );
+    }
+}
@@ -0,0 +1,115 @@
+// rust/src/kdf.rs — HKDF-SHA3-256 Key Schedule Eternal (shared by client + mercy_verifier)
+//
+//   transcript = SHA3-256(PROTOCOL_LABEL || SHA3-256(kem_ct) || SHA3-256(recipient_pk))
+//   prk        = HKDF-Extract(salt = transcript, ikm = ML-KEM shared secret)
+//   key[p]     = HKDF-Expand(prk, PROTOCOL_LABEL || 0x00 || purpose_label(p), 32)
+//
+// The raw shared secret never keys an AEAD directly, and every purpose gets its own key.
+use hkdf::Hkdf;
+use sha3::{Digest, Sha3_256};
+use zeroize::Zeroizing;
+
+use crate::error::{MercyError, MercyResult};
+
+pub const PROTOCOL_LABEL: &[u8] = b"MercyShieldPlus/kem-key-schedule/v1";
+pub const KEY_LEN: usize = 32;
+
+/// What a derived key is used for — each purpose yields an independent key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyPurpose {
+    /// AES-256-GCM key sealing a single-shot attestation blob
+    BlobAead,
+    /// Segment key for chunked (STREAM) report/log encryption
+    StreamAead,
+}
+
+impl KeyPurpose {
+    fn label(self) -> &'static [u8] {
+        match self {
+            KeyPurpose::BlobAead => b"blob-aead",
+            KeyPurpose::StreamAead => b"stream-aead",
+        }
+    }
+}
+
+/// SHA3-256 of the recipient KEM public key — verifier can cache this once at startup
+pub fn recipient_pk_hash(kem_pk_bytes: &[u8]) -> [u8; 32] {
+    Sha3_256::digest(kem_pk_bytes).into()
+}
+
+/// Key schedule bound to one KEM exchange (shared secret + ciphertext + recipient)
+pub struct KeySchedule {
+    hkdf: Hkdf<Sha3_256>,
+}
+
+impl KeySchedule {
+    pub fn new(shared_secret: &[u8], kem_ciphertext: &[u8], recipient_pk_hash: &[u8; 32]) -> Self {
+        let mut transcript = Sha3_256::new();
+        transcript.update(PROTOCOL_LABEL);
+        transcript.update(Sha3_256::digest(kem_ciphertext));
+        transcript.update(recipient_pk_hash);
+        let salt = transcript.finalize();
+
+        KeySchedule {
+            hkdf: Hkdf::<Sha3_256>::new(Some(&salt), shared_secret),
+        }
+    }
+
+    /// Derive the 32-byte key for `purpose`
+    pub fn derive_key(&self, purpose: KeyPurpose) -> MercyResult<Zeroizing<[u8; KEY_LEN]>> {
+        let mut key = Zeroizing::new([0u8; KEY_LEN]);
+        self.hkdf
+            .expand_multi_info(&[PROTOCOL_LABEL, &[0u8], purpose.label()], key.as_mut())
+            .map_err(|_| MercyError::CryptoFailure)?;
+        Ok(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SS: [u8; 32] = [0x11; 32];
+    const CT: [u8; 1088] = [0x22; 1088];
+
+    #[test]
+    fn test_key_schedule_deterministic() {
+        let pk_hash = recipient_pk_hash(b"server pk");
+        let a = KeySchedule::new(&SS, &CT, &pk_hash)
+            .derive_key(KeyPurpose::BlobAead)
+            .unwrap();
+        let b = KeySchedule::new(&SS, &CT, &pk_hash)
+            .derive_key(KeyPurpose::BlobAead)
+            .unwrap();
+        assert_eq!(*a, *b);
+        assert_ne!(*a, SS, "Shared secret must never be used raw");
+    }
+
+    #[test]
+    fn test_key_schedule_separates_purposes() {
+        let ks = KeySchedule::new(&SS, &CT, &recipient_pk_hash(b"server pk"));
+        let blob = ks.derive_key(KeyPurpose::BlobAead).unwrap();
+        let stream = ks.derive_key(KeyPurpose::StreamAead).unwrap();
+        assert_ne!(*blob, *stream, "Purposes must yield independent keys");
+    }
+
+    #[test]
+    fn test_key_schedule_binds_ciphertext_and_recipient() {
+        let pk_hash = recipient_pk_hash(b"server pk");
+        let base = KeySchedule::new(&SS, &CT, &pk_hash)
+            .derive_key(KeyPurpose::BlobAead)
+            .unwrap();
+
+        let mut other_ct = CT;
+        other_ct[0] ^= 1;
+        let ct_changed = KeySchedule::new(&SS, &other_ct, &pk_hash)
+            .derive_key(KeyPurpose::BlobAead)
+            .unwrap();
+        assert_ne!(*base, *ct_changed, "Key not bound to KEM ciphertext");
+
+        let pk_changed = KeySchedule::new(&SS, &CT, &recipient_pk_hash(b"other pk"))
+            .derive_key(KeyPurpose::BlobAead)
+            .unwrap();
+        assert_ne!(*base, *pk_changed, "Key not bound to recipient public key");
+    }
+}
@@ -0,0 +1,631 @@
+// rust/src/ledger.rs — On-Device Attestation Ledger Eternal (hash-chained, ML-DSA signed reports)
+//
+// Entry layout (all integers big-endian):
+//   magic         4   b"MSPL"
+//   version       1   LEDGER_VERSION
+//   seq           8   position in the ledger, genesis = 0
+//   timestamp_ms  8   wall clock at append (informational — ordering comes from seq)
+//   prev_hash    32   entry_hash of entry seq-1, all zero for genesis
+//   report      4+n   length-prefixed report JSON
+//   ------------- everything above is signed by the device key (LedgerEntry ctx) -------------
+//   signature   2+n   ML-DSA-65 signature
+//
+//   entry_hash = SHA3-256("MercyShieldPlus/ledger-entry-hash/v1" || encoded entry)
+//
+// Export layout: b"MSLX" | version 1 | count u64 | count × (u32 length || encoded entry).
+//
+// Editing, dropping, reordering or re-signing any past entry breaks either its own signature or
+// the prev_hash link of its successor, so `verify_chain` pins the first point of tampering.
+// The encoded entries are also the leaves of the ledger's Merkle tree (`merkle.rs`), whose signed
+// heads and proofs let an auditor check single entries and append-only growth.
+use alloc::vec::Vec;
+use rand_core::{CryptoRngCore, OsRng};
+use sha3::{Digest, Sha3_256};
+
+use crate::blob::Reader;
+use crate::context::MessageKind;
+use crate::error::{MercyError, MercyResult};
+use crate::merkle::{MerkleTree, SignedTreeHead};
+use crate::pq;
+
+pub const LEDGER_MAGIC: [u8; 4] = *b"MSPL";
+pub const LEDGER_EXPORT_MAGIC: [u8; 4] = *b"MSLX";
+pub const LEDGER_VERSION: u8 = 1;
+pub const ENTRY_HASH_LEN: usize = 32;
+
+const ENTRY_HASH_LABEL: &[u8] = b"MercyShieldPlus/ledger-entry-hash/v1";
+
+/// One signed report, linked to its predecessor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerEntry {
+    pub seq: u64,
+    pub timestamp_ms: u64,
+    pub prev_hash: [u8; ENTRY_HASH_LEN],
+    pub report: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl LedgerEntry {
+    /// Bytes covered by the device signature
+    pub fn signed_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(21 + ENTRY_HASH_LEN + 4 + self.report.len());
+        out.extend_from_slice(&LEDGER_MAGIC);
+        out.push(LEDGER_VERSION);
+        out.extend_from_slice(&self.seq.to_be_bytes());
+        out.extend_from_slice(&self.timestamp_ms.to_be_bytes());
+        out.extend_from_slice(&self.prev_hash);
+        out.extend_from_slice(&(self.report.len() as u32).to_be_bytes());
+        out.extend_from_slice(&self.report);
+        out
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = self.signed_bytes();
+        out.extend_from_slice(&(self.signature.len() as u16).to_be_bytes());
+        out.extend_from_slice(&self.signature);
+        out
+    }
+
+    pub fn parse(bytes: &[u8]) -> MercyResult<Self> {
+        let mut r = Reader::new(bytes);
+        if r.take(4)? != LEDGER_MAGIC {
+            return Err(MercyError::InvalidBlob);
+        }
+        if r.u8()? != LEDGER_VERSION {
+            return Err(MercyError::UnsupportedVersion);
+        }
+        let seq = r.u64()?;
+        let timestamp_ms = r.u64()?;
+        let mut prev_hash = [0u8; ENTRY_HASH_LEN];
+        prev_hash.copy_from_slice(r.take(ENTRY_HASH_LEN)?);
+        let report_len = r.u32()? as usize;
+        let report = r.take(report_len)?.to_vec();
+        let sig_len = r.u16()? as usize;
+        let signature = r.take(sig_len)?.to_vec();
+        if !r.is_empty() {
+            return Err(MercyError::InvalidBlob);
+        }
+        Ok(LedgerEntry {
+            seq,
+            timestamp_ms,
+            prev_hash,
+            report,
+            signature,
+        })
+    }
+
+    /// What the next entry's `prev_hash` must equal
+    pub fn hash(&self) -> [u8; ENTRY_HASH_LEN] {
+        let mut h = Sha3_256::new();
+        h.update(ENTRY_HASH_LABEL);
+        h.update(self.encode());
+        h.finalize().into()
+    }
+}
+
+/// Outcome of walking the chain from genesis
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct LedgerVerification {
+    /// Number of entries checked
+    pub length: u64,
+    /// Seq of the first entry with a bad signature, sequence number or link — `None` if intact
+    pub first_invalid: Option<u64>,
+    /// Hash of the last entry of the intact prefix (empty if even genesis fails)
+    pub head_hash: Vec<u8>,
+}
+
+impl LedgerVerification {
+    pub fn is_intact(&self) -> bool {
+        self.first_invalid.is_none()
+    }
+}
+
+/// Append-only, in-memory ledger — persist it with `export`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Ledger {
+    entries: Vec<LedgerEntry>,
+}
+
+impl Ledger {
+    pub fn new() -> Self {
+        Ledger::default()
+    }
+
+    pub fn entries(&self) -> &[LedgerEntry] {
+        &self.entries
+    }
+
+    pub fn head(&self) -> Option<&LedgerEntry> {
+        self.entries.last()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Sign `report` onto the head of the chain
+    pub fn append(
+        &mut self,
+        report: &[u8],
+        timestamp_ms: u64,
+        dsa_sk_bytes: &[u8],
+    ) -> MercyResult<&LedgerEntry> {
+        self.append_with_rng(report, timestamp_ms, dsa_sk_bytes, &mut OsRng)
+    }
+
+    /// `append` with an injected RNG for the randomized signature
+    pub fn append_with_rng(
+        &mut self,
+        report: &[u8],
+        timestamp_ms: u64,
+        dsa_sk_bytes: &[u8],
+        rng: &mut impl CryptoRngCore,
+    ) -> MercyResult<&LedgerEntry> {
+        if report.len() > u32::MAX as usize {
+            return Err(MercyError::InvalidBlob);
+        }
+        let dsa_sk = pq::dsa_signing_key(dsa_sk_bytes)?;
+        let mut entry = LedgerEntry {
+            seq: self.entries.len() as u64,
+            timestamp_ms,
+            prev_hash: self
+                .head()
+                .map(LedgerEntry::hash)
+                .unwrap_or([0; ENTRY_HASH_LEN]),
+            report: report.to_vec(),
+            signature: Vec::new(),
+        };
+        entry.signature = pq::dsa_sign(
+            &dsa_sk,
+            &entry.signed_bytes(),
+            MessageKind::LedgerEntry.context(),
+            rng,
+        )?;
+
+        self.entries.push(entry);
+        Ok(&self.entries[self.entries.len() - 1])
+    }
+
+    /// Merkle tree over the encoded entries, leaf index = seq
+    pub fn tree(&self) -> MerkleTree {
+        self.entries.iter().map(LedgerEntry::encode).collect()
+    }
+
+    /// Sign the current Merkle tree head
+    pub fn sign_tree_head(
+        &self,
+        timestamp_ms: u64,
+        dsa_sk_bytes: &[u8],
+    ) -> MercyResult<SignedTreeHead> {
+        SignedTreeHead::sign(&self.tree(), timestamp_ms, dsa_sk_bytes)
+    }
+
+    /// Walk the chain from genesis against the device public key
+    pub fn verify(&self, dsa_pk_bytes: &[u8]) -> MercyResult<LedgerVerification> {
+        verify_chain(&self.entries, dsa_pk_bytes)
+    }
+
+    pub fn export(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&LEDGER_EXPORT_MAGIC);
+        out.push(LEDGER_VERSION);
+        out.extend_from_slice(&(self.entries.len() as u64).to_be_bytes());
+        for entry in &self.entries {
+            let bytes = entry.encode();
+            out.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+            out.extend_from_slice(&bytes);
+        }
+        out
+    }
+
+    /// Parse an export — structure only, call `verify` before trusting it
+    pub fn import(bytes: &[u8]) -> MercyResult<Self> {
+        let mut r = Reader::new(bytes);
+        if r.take(4)? != LEDGER_EXPORT_MAGIC {
+            return Err(MercyError::InvalidBlob);
+        }
+        if r.u8()? != LEDGER_VERSION {
+            return Err(MercyError::UnsupportedVersion);
+        }
+        let count = r.u64()?;
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            let len = r.u32()? as usize;
+            entries.push(LedgerEntry::parse(r.take(len)?)?);
+        }
+        if !r.is_empty() {
+            return Err(MercyError::InvalidBlob);
+        }
+        Ok(Ledger { entries })
+    }
+}
+
+/// Check seq, prev_hash link and signature of every entry — a malformed key is an error, a broken
+/// chain is reported through `first_invalid`
+pub fn verify_chain(
+    entries: &[LedgerEntry],
+    dsa_pk_bytes: &[u8],
+) -> MercyResult<LedgerVerification> {
+    let dsa_pk = pq::dsa_verifying_key(dsa_pk_bytes)?;
+    let ctx = MessageKind::LedgerEntry.context();
+
+    let mut head: Option<[u8; ENTRY_HASH_LEN]> = None;
+    let mut first_invalid = None;
+    for (index, entry) in entries.iter().enumerate() {
+        let linked = entry.prev_hash == head.unwrap_or([0; ENTRY_HASH_LEN]);
+        let signed =
+            pq::dsa_verify(&dsa_pk, &entry.signed_bytes(), ctx, &entry.signature).unwrap_or(false);
+        if entry.seq != index as u64 || !linked || !signed {
+            first_invalid = Some(index as u64);
+            break;
+        }
+        head = Some(entry.hash());
+    }
+
+    Ok(LedgerVerification {
+        length: entries.len() as u64,
+        first_invalid,
+        head_hash: head.map(|h| h.to_vec()).unwrap_or_default(),
+    })
+}
+
+#[cfg(feature = "uniffi")]
+pub use self::ffi::{LedgerRecord, PqLedger};
+
+/// uniffi object holding the app's ledger between checks
+#[cfg(feature = "uniffi")]
+mod ffi {
+    use super::*;
+    use alloc::string::String;
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+    use std::sync::{Arc, Mutex};
+
+    use crate::merkle::{ConsistencyProof, InclusionProof};
+    use crate::secret::SecretBuffer;
+
+    /// One entry as the app displays it — verify the chain before trusting any of them
+    #[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+    pub struct LedgerRecord {
+        pub seq: u64,
+        pub timestamp_ms: u64,
+        /// Canonical report JSON
+        pub report: String,
+        pub entry_hash: Vec<u8>,
+    }
+
+    #[derive(uniffi::Object)]
+    pub struct PqLedger {
+        ledger: Mutex<Ledger>,
+    }
+
+    #[uniffi::export]
+    impl PqLedger {
+        #[uniffi::constructor]
+        pub fn new() -> Arc<Self> {
+            Arc::new(PqLedger {
+                ledger: Mutex::new(Ledger::new()),
+            })
+        }
+
+        /// Restore a persisted `export()` — structure only, call `verify` before trusting it
+        #[uniffi::constructor]
+        pub fn from_export(export: Vec<u8>) -> MercyResult<Arc<Self>> {
+            Ok(Arc::new(PqLedger {
+                ledger: Mutex::new(Ledger::import(&export)?),
+            }))
+        }
+
+        /// Sign a report JSON onto the chain (stored in RFC 8785 canonical form) — returns the new
+        /// head hash (base64)
+        pub fn append(
+            &self,
+            report: Vec<u8>,
+            timestamp_ms: u64,
+            dsa_sk_b64: String,
+        ) -> MercyResult<String> {
+            let report = core::str::from_utf8(&report).map_err(|_| MercyError::InvalidJson)?;
+            let report = crate::jcs::canonicalize(report)?;
+            let dsa_sk = SecretBuffer::from_base64(dsa_sk_b64)?;
+            let mut ledger = self.ledger.lock().map_err(|_| MercyError::InvalidState)?;
+            let entry = ledger.append(report.as_bytes(), timestamp_ms, &dsa_sk)?;
+            Ok(BASE64.encode(entry.hash()))
+        }
+
+        /// Walk the whole chain against the device DSA PK (base64)
+        pub fn verify(&self, dsa_pk_b64: String) -> MercyResult<LedgerVerification> {
+            let dsa_pk = BASE64
+                .decode(dsa_pk_b64)
+                .map_err(|_| MercyError::InvalidBase64)?;
+            self.ledger
+                .lock()
+                .map_err(|_| MercyError::InvalidState)?
+                .verify(&dsa_pk)
+        }
+
+        /// Encoded entry at `seq` — the Merkle leaf an inclusion proof is checked against
+        pub fn entry(&self, seq: u64) -> MercyResult<Vec<u8>> {
+            let ledger = self.ledger.lock().map_err(|_| MercyError::InvalidState)?;
+            let entry = ledger
+                .entries()
+                .get(seq as usize)
+                .ok_or(MercyError::InvalidTreeSize)?;
+            Ok(entry.encode())
+        }
+
+        /// Every entry, genesis first
+        pub fn records(&self) -> MercyResult<Vec<LedgerRecord>> {
+            let ledger = self.ledger.lock().map_err(|_| MercyError::InvalidState)?;
+            ledger
+                .entries()
+                .iter()
+                .map(|e| {
+                    Ok(LedgerRecord {
+                        seq: e.seq,
+                        timestamp_ms: e.timestamp_ms,
+                        report: String::from_utf8(e.report.clone())
+                            .map_err(|_| MercyError::InvalidJson)?,
+                        entry_hash: e.hash().to_vec(),
+                    })
+                })
+                .collect()
+        }
+
+        /// Sign the current Merkle tree head — returns the encoded head (base64)
+        pub fn sign_tree_head(&self, timestamp_ms: u64, dsa_sk_b64: String) -> MercyResult<String> {
+            let dsa_sk = SecretBuffer::from_base64(dsa_sk_b64)?;
+            let ledger = self.ledger.lock().map_err(|_| MercyError::InvalidState)?;
+            Ok(BASE64.encode(ledger.sign_tree_head(timestamp_ms, &dsa_sk)?.encode()))
+        }
+
+        /// Audit path for entry `seq` in the tree of the first `tree_size` entries
+        pub fn inclusion_proof(&self, seq: u64, tree_size: u64) -> MercyResult<InclusionProof> {
+            self.ledger
+                .lock()
+                .map_err(|_| MercyError::InvalidState)?
+                .tree()
+                .inclusion_proof(seq, tree_size)
+        }
+
+        /// Proof that the log at `old_size` is a prefix of the log at `new_size`
+        pub fn consistency_proof(
+            &self,
+            old_size: u64,
+            new_size: u64,
+        ) -> MercyResult<ConsistencyProof> {
+            self.ledger
+                .lock()
+                .map_err(|_| MercyError::InvalidState)?
+                .tree()
+                .consistency_proof(old_size, new_size)
+        }
+
+        /// Serialized ledger for persistence or off-device audit
+        pub fn export(&self) -> MercyResult<Vec<u8>> {
+            Ok(self
+                .ledger
+                .lock()
+                .map_err(|_| MercyError::InvalidState)?
+                .export())
+        }
+
+        pub fn len(&self) -> MercyResult<u64> {
+            Ok(self
+                .ledger
+                .lock()
+                .map_err(|_| MercyError::InvalidState)?
+                .len() as u64)
+        }
+
+        pub fn is_empty(&self) -> MercyResult<bool> {
+            Ok(self
+                .ledger
+                .lock()
+                .map_err(|_| MercyError::InvalidState)?
+                .is_empty())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::vec;
+
+    fn device_key() -> (Vec<u8>, Vec<u8>) {
+        let sk = pq::dsa_generate(&mut OsRng);
+        (
+            pq::dsa_secret_key_bytes(&sk).to_vec(),
+            pq::dsa_public_key_bytes(&sk),
+        )
+    }
+
+    fn ledger_of(sk: &[u8], n: u64) -> Ledger {
+        let mut ledger = Ledger::new();
+        for i in 0..n {
+            ledger
+                .append(
+                    format!("{{\"risk_score\":{}}}", i).as_bytes(),
+                    1_700_000_000_000 + i,
+                    sk,
+                )
+                .unwrap();
+        }
+        ledger
+    }
+
+    #[test]
+    fn test_entry_encode_parse_roundtrip() {
+        let entry = LedgerEntry {
+            seq: 3,
+            timestamp_ms: 42,
+            prev_hash: [7; ENTRY_HASH_LEN],
+            report: b"{\"risk_score\":0}".to_vec(),
+            signature: vec![9; 11],
+        };
+        let bytes = entry.encode();
+        assert_eq!(LedgerEntry::parse(&bytes), Ok(entry));
+
+        assert_eq!(
+            LedgerEntry::parse(&bytes[..bytes.len() - 1]),
+            Err(MercyError::InvalidBlob)
+        );
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(LedgerEntry::parse(&trailing), Err(MercyError::InvalidBlob));
+        let mut version = bytes;
+        version[4] = 9;
+        assert_eq!(
+            LedgerEntry::parse(&version),
+            Err(MercyError::UnsupportedVersion)
+        );
+    }
+
+    #[test]
+    fn test_chain_verifies_and_exports() {
+        let (sk, pk) = device_key();
+        let ledger = ledger_of(&sk, 3);
+        assert_eq!(ledger.entries()[0].prev_hash, [0; ENTRY_HASH_LEN]);
+        assert_eq!(ledger.entries()[2].prev_hash, ledger.entries()[1].hash());
+
+        let verdict = ledger.verify(&pk).unwrap();
+        assert!(verdict.is_intact());
+        assert_eq!(verdict.length, 3);
+        assert_eq!(verdict.head_hash, ledger.head().unwrap().hash().to_vec());
+
+        let restored = Ledger::import(&ledger.export()).unwrap();
+        assert_eq!(restored, ledger);
+        assert_eq!(
+            Ledger::new().verify(&pk).unwrap().head_hash,
+            Vec::<u8>::new()
+        );
+    }
+
+    #[test]
+    fn test_tampering_detected() {
+        let (sk, pk) = device_key();
+        let ledger = ledger_of(&sk, 4);
+        let first_invalid =
+            |entries: &[LedgerEntry]| verify_chain(entries, &pk).unwrap().first_invalid;
+
+        let mut edited = ledger.entries().to_vec();
+        edited[1].report = b"{\"risk_score\":99}".to_vec();
+        assert_eq!(first_invalid(&edited), Some(1));
+
+        let mut dropped = ledger.entries().to_vec();
+        dropped.remove(2);
+        assert_eq!(first_invalid(&dropped), Some(2));
+
+        let mut swapped = ledger.entries().to_vec();
+        swapped.swap(1, 2);
+        assert_eq!(first_invalid(&swapped), Some(1));
+
+        // A validly signed entry from another device cannot be spliced in
+        let (other_sk, _) = device_key();
+        let mut forged = ledger.clone();
+        forged.append(b"{}", 0, &other_sk).unwrap();
+        assert_eq!(forged.verify(&pk).unwrap().first_invalid, Some(4));
+
+        // Truncating the tail keeps the chain intact — the verifier pins the expected head
+        let truncated = verify_chain(&ledger.entries()[..2], &pk).unwrap();
+        assert!(truncated.is_intact());
+        assert_ne!(truncated.head_hash, ledger.verify(&pk).unwrap().head_hash);
+    }
+
+    #[test]
+    fn test_tree_heads_cover_ledger_entries() {
+        let (sk, pk) = device_key();
+        let mut ledger = ledger_of(&sk, 3);
+        let old = ledger.sign_tree_head(10, &sk).unwrap();
+        ledger.append(b"{\"risk_score\":40}", 11, &sk).unwrap();
+        let new = ledger.sign_tree_head(12, &sk).unwrap();
+        assert_eq!((old.tree_size, new.tree_size), (3, 4));
+
+        let tree = ledger.tree();
+        let entry = ledger.entries()[1].encode();
+        let inclusion = tree.inclusion_proof(1, 4).unwrap();
+        assert_eq!(
+            crate::merkle::verify_inclusion(&new, &pk, &entry, &inclusion),
+            Ok(true)
+        );
+        let consistency = tree.consistency_proof(3, 4).unwrap();
+        assert_eq!(
+            crate::merkle::verify_append_only(&old, &new, &pk, &consistency),
+            Ok(true)
+        );
+
+        // Rewriting an old entry (even re-signed and re-chained) changes every later head
+        let mut rewritten = Ledger::new();
+        rewritten.append(b"{\"risk_score\":0}", 0, &sk).unwrap();
+        for _ in 1..4 {
+            rewritten.append(b"{}", 0, &sk).unwrap();
+        }
+        let forged = rewritten.sign_tree_head(12, &sk).unwrap();
+        let consistency = rewritten.tree().consistency_proof(3, 4).unwrap();
+        assert_eq!(
+            crate::merkle::verify_append_only(&old, &forged, &pk, &consistency),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn test_signature_bound_to_ledger_context() {
+        let (sk, pk) = device_key();
+        let ledger = ledger_of(&sk, 1);
+        let entry = &ledger.entries()[0];
+        assert_eq!(
+            crate::verify_data(
+                &pk,
+                &entry.signed_bytes(),
+                &entry.signature,
+                MessageKind::AttestationReport
+            ),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn test_import_rejects_malformed() {
+        let (sk, _) = device_key();
+        let export = ledger_of(&sk, 2).export();
+        assert_eq!(
+            Ledger::import(&export[..export.len() - 1]),
+            Err(MercyError::InvalidBlob)
+        );
+        let mut trailing = export.clone();
+        trailing.push(0);
+        assert_eq!(Ledger::import(&trailing), Err(MercyError::InvalidBlob));
+        assert_eq!(Ledger::import(b"MSPL"), Err(MercyError::InvalidBlob));
+        assert_eq!(verify_chain(&[], &[1, 2, 3]), Err(MercyError::InvalidKey));
+    }
+
+    #[cfg(feature = "uniffi")]
+    #[test]
+    fn test_ffi_append_persist_records() {
+        use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+
+        let (sk, pk) = device_key();
+        let ledger = PqLedger::new();
+        let head = ledger
+            .append(
+                b"{\"verdict\":\"Genuine\", \"risk\":0}".to_vec(),
+                7,
+                BASE64.encode(&sk),
+            )
+            .unwrap();
+
+        // What the app does on the next launch: restore the export, verify, then list
+        let restored = PqLedger::from_export(ledger.export().unwrap()).unwrap();
+        assert!(restored.verify(BASE64.encode(&pk)).unwrap().is_intact());
+        let records = restored.records().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!((records[0].seq, records[0].timestamp_ms), (0, 7));
+        assert_eq!(records[0].report, "{\"risk\":0,\"verdict\":\"Genuine\"}");
+        assert_eq!(BASE64.encode(&records[0].entry_hash), head);
+    }
+}
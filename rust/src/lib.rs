@@ -1,131 +1,395 @@
+// rust/src/lib.rs — MercyShieldPlus Core Eternal
+//
+// The core (key ops, blob seal/open, report model) is `no_std + alloc` and builds for bare-metal
+// targets such as `thumbv7em-none-eabihf` with `--no-default-features`. `std` adds the io adapters,
+// `uniffi` the Kotlin/Swift/Python exports, `capi` the plain C ABI and `wasm` the verify-only JS
+// bindings on top. Without an OS, `OsRng` goes through getrandom's `custom` backend — the TEE
+// firmware must `register_custom_getrandom!` its TRNG, or call the `*_with_rng` APIs directly.
 #![no_std]
+// uniffi 0.25 scaffolding compares fn pointers, which current rustc lints against
+#![cfg_attr(feature = "uniffi", allow(unpredictable_function_pointer_comparisons))]
 extern crate alloc;
-
-use ml_kem::{
-    MlKem768, MlKem768Ciphertext, MlKem768PublicKey, MlKem768SecretKey, MlKem768SharedSecret,
-};
-use ml_dsa::MlDsa65;
-use ml_dsa::signature::{Signature, Signer, Verifier};
-use rand_core::{OsRng};
-use zeroize::{Zeroize, Zeroizing};
-use aes_gcm::{AeadInPlace, Aes256Gcm, KeyInit, Nonce};
-use uniffi::export;
-use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
-
-fn derive_aes_key(shared_secret: &MlKem768SharedSecret) -> Zeroizing<[u8; 32]> {
-    let mut key = Zeroizing::new([0u8; 32]);
-    key.copy_from_slice(shared_secret.as_bytes());
-    key
+// Hosted targets always link std, which supplies the panic handler and allocator the cdylib /
+// staticlib artifacts need even without the `std` feature; bare metal builds the rlib only
+#[cfg(any(feature = "std", not(target_os = "none")))]
+extern crate std;
+
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!();
+
+pub mod backup;
+#[cfg(feature = "batch")]
+pub mod batch;
+pub mod blob;
+#[cfg(feature = "capi")]
+pub mod capi;
+pub mod challenge;
+pub mod collector;
+pub mod context;
+pub mod error;
+pub mod evidence;
+pub mod instrumentation;
+pub mod integrity;
+pub mod jcs;
+pub mod kdf;
+pub mod key_encoding;
+pub mod ledger;
+pub mod merkle;
+pub mod policy;
+pub mod pq;
+pub mod prehash;
+pub mod rng;
+pub mod rotation;
+pub mod secret;
+pub mod stream;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+/// Std-prelude names the `JsonSchema` derive expands to — glob-imported where it is derived
+#[cfg(feature = "schema")]
+mod schema_prelude {
+    pub use std::{borrow::ToOwned, boxed::Box, vec};
 }
 
-/// Generate PQ keypair — returns base64 encoded (KEM PK, DSA PK)
-#[export]
-pub fn generate_pq_keypair() -> (String, String) {
-    let (kem_sk, kem_pk) = MlKem768::generate(&mut OsRng);
-    let kem_pk_b64 = BASE64.encode(kem_pk.into_bytes());
-
-    let dsa_sk = MlDsa65::new(&mut OsRng);
-    let dsa_pk = dsa_sk.verifying_key();
-    let dsa_pk_b64 = BASE64.encode(dsa_pk.to_bytes());
-
-    drop(kem_sk);
-    drop(dsa_sk);
-
-    (kem_pk_b64, dsa_pk_b64)
+use alloc::string::String;
+use alloc::vec::Vec;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use rand_core::{CryptoRngCore, OsRng};
+
+use blob::{AeadAlgorithm, BlobHeader, BlobPayload, KemAlgorithm, SignatureAlgorithm, NONCE_LEN};
+pub use context::MessageKind;
+pub use error::{MercyError, MercyResult};
+pub use pq::{MlKem768PublicKey, MlKem768SecretKey};
+use secret::SecretBuffer;
+
+/// Freshly generated device identity, base64 encoded — the secret keys belong in the keystore
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct PqKeyPair {
+    pub kem_pk_b64: String,
+    pub dsa_pk_b64: String,
+    pub kem_sk_b64: String,
+    pub dsa_sk_b64: String,
 }
 
-/// Sign data with ML-DSA-65 (input: DSA SK base64 + message bytes)
-#[export]
-pub fn pq_sign_data(dsa_sk_b64: String, message: Vec<u8>) -> String {
-    let dsa_sk_bytes = BASE64.decode(dsa_sk_b64).expect("Invalid DSA SK base64");
-    let dsa_sk = MlDsa65::from_bytes(&dsa_sk_bytes).expect("Invalid DSA SK");
-
-    let signature: Signature = dsa_sk.try_sign(&message).expect("Signing failed");
-    let sig_b64 = BASE64.encode(signature.to_bytes());
+/// Generate PQ keypair — returns base64 encoded KEM + DSA public and secret keys
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn generate_pq_keypair() -> PqKeyPair {
+    generate_pq_keypair_with_rng(&mut OsRng)
+}
 
-    let mut sk_bytes = dsa_sk_bytes;
-    sk_bytes.zeroize();
+/// `generate_pq_keypair` with an injected RNG (seeded in KATs — see `rng.rs`)
+pub fn generate_pq_keypair_with_rng(rng: &mut impl CryptoRngCore) -> PqKeyPair {
+    let (kem_sk, kem_pk) = pq::kem_generate(rng);
+    let kem_pk_b64 = BASE64.encode(pq::kem_public_key_bytes(&kem_pk));
+    let kem_sk_b64 = BASE64.encode(pq::kem_secret_key_bytes(&kem_sk));
+
+    let dsa_sk = pq::dsa_generate(rng);
+    let dsa_pk_b64 = BASE64.encode(pq::dsa_public_key_bytes(&dsa_sk));
+    let dsa_sk_b64 = BASE64.encode(pq::dsa_secret_key_bytes(&dsa_sk));
+
+    PqKeyPair {
+        kem_pk_b64,
+        dsa_pk_b64,
+        kem_sk_b64,
+        dsa_sk_b64,
+    }
+}
 
-    sig_b64
+/// Sign data with ML-DSA-65 (input: DSA SK base64 + message bytes + message kind → FIPS 204 ctx)
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn pq_sign_data(
+    dsa_sk_b64: String,
+    message: Vec<u8>,
+    kind: MessageKind,
+) -> MercyResult<String> {
+    let dsa_sk = SecretBuffer::from_base64(dsa_sk_b64)?;
+    let signature = sign_data_with_rng(&dsa_sk, &message, kind, &mut OsRng)?;
+
+    Ok(BASE64.encode(signature))
 }
 
-/// Verify ML-DSA-65 signature (PK base64, message, sig base64)
-#[export]
-pub fn pq_verify_data(dsa_pk_b64: String, message: Vec<u8>, signature_b64: String) -> bool {
-    let dsa_pk_bytes = BASE64.decode(dsa_pk_b64).expect("Invalid DSA PK");
-    let dsa_pk = ml_dsa::VerifyingKey::from_bytes(&dsa_pk_bytes).expect("Invalid DSA PK");
+/// `pq_sign_data` over raw SK bytes with an injected RNG — returns raw signature bytes
+pub fn sign_data_with_rng(
+    dsa_sk_bytes: &[u8],
+    message: &[u8],
+    kind: MessageKind,
+    rng: &mut impl CryptoRngCore,
+) -> MercyResult<Vec<u8>> {
+    let dsa_sk = pq::dsa_signing_key(dsa_sk_bytes)?;
+    pq::dsa_sign(&dsa_sk, message, kind.context(), rng)
+}
 
-    let sig_bytes = BASE64.decode(signature_b64).expect("Invalid signature");
-    let signature = Signature::from_bytes(&sig_bytes).expect("Invalid signature format");
+/// Verify ML-DSA-65 signature (PK base64, message, sig base64, message kind it must have been signed as)
+/// Malformed inputs are errors; a well-formed but wrong (or cross-context) signature is `Ok(false)`
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn pq_verify_data(
+    dsa_pk_b64: String,
+    message: Vec<u8>,
+    signature_b64: String,
+    kind: MessageKind,
+) -> MercyResult<bool> {
+    let dsa_pk_bytes = BASE64
+        .decode(dsa_pk_b64)
+        .map_err(|_| MercyError::InvalidBase64)?;
+    let sig_bytes = BASE64
+        .decode(signature_b64)
+        .map_err(|_| MercyError::InvalidBase64)?;
+    verify_data(&dsa_pk_bytes, &message, &sig_bytes, kind)
+}
 
-    dsa_pk.verify(&message, &signature).is_ok()
+/// `pq_verify_data` over raw PK / signature bytes
+pub fn verify_data(
+    dsa_pk_bytes: &[u8],
+    message: &[u8],
+    signature_bytes: &[u8],
+    kind: MessageKind,
+) -> MercyResult<bool> {
+    let dsa_pk = pq::dsa_verifying_key(dsa_pk_bytes)?;
+    pq::dsa_verify(&dsa_pk, message, kind.context(), signature_bytes)
 }
 
-/// Secure off-device attestation blob
+/// Secure off-device attestation blob (format v2 — see `blob.rs`)
 /// Input: report JSON bytes, server KEM PK base64, optional local DSA SK base64
 /// Output: base64 encoded blob
-#[export]
+#[cfg_attr(feature = "uniffi", uniffi::export)]
 pub fn pq_secure_attestation_blob(
     report: Vec<u8>,
     server_kem_pk_b64: String,
     local_dsa_sk_b64: Option<String>,
-) -> String {
-    let server_pk_bytes = BASE64.decode(server_kem_pk_b64).expect("Invalid server PK");
-    let server_pk = MlKem768PublicKey::from_bytes(&server_pk_bytes).expect("Invalid server PK format");
-
-    let mut payload = report.clone();
-    let mut signature = Vec::new();
+) -> MercyResult<String> {
+    let server_pk_bytes = BASE64
+        .decode(server_kem_pk_b64)
+        .map_err(|_| MercyError::InvalidBase64)?;
+    let dsa_sk = local_dsa_sk_b64
+        .map(SecretBuffer::from_base64)
+        .transpose()?;
+
+    let blob =
+        secure_attestation_blob_with_rng(report, &server_pk_bytes, dsa_sk.as_deref(), &mut OsRng)?;
+    Ok(BASE64.encode(blob))
+}
 
-    if let Some(dsa_sk_b64) = local_dsa_sk_b64 {
-        let sig_b64 = pq_sign_data(dsa_sk_b64, report);
-        signature = BASE64.decode(sig_b64).expect("Invalid local signature");
-        payload.extend_from_slice(&signature);
-    }
+/// `pq_secure_attestation_blob` over raw key bytes with an injected RNG — returns raw blob bytes
+/// (RNG feeds, in order: report signature, KEM encapsulation, AEAD nonce)
+pub fn secure_attestation_blob_with_rng(
+    report: Vec<u8>,
+    server_pk_bytes: &[u8],
+    dsa_sk_bytes: Option<&[u8]>,
+    rng: &mut impl CryptoRngCore,
+) -> MercyResult<Vec<u8>> {
+    let server_pk = pq::kem_public_key(server_pk_bytes)?;
+
+    let signature = match dsa_sk_bytes {
+        Some(sk) => Some(sign_data_with_rng(
+            sk,
+            &report,
+            MessageKind::AttestationReport,
+            rng,
+        )?),
+        None => None,
+    };
+    let sig_alg = if signature.is_some() {
+        SignatureAlgorithm::MlDsa65
+    } else {
+        SignatureAlgorithm::None
+    };
+
+    let (kem_ciphertext, shared_secret) = pq::kem_encapsulate(&server_pk, rng)?;
+
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill_bytes(&mut nonce);
+
+    let header = BlobHeader {
+        kem_alg: KemAlgorithm::MlKem768,
+        aead_alg: AeadAlgorithm::Aes256Gcm,
+        sig_alg,
+        key_id: blob::key_id(server_pk_bytes),
+        kem_ciphertext,
+        nonce,
+    };
+    blob::seal(
+        &header,
+        &*shared_secret,
+        server_pk_bytes,
+        &BlobPayload { report, signature },
+    )
+}
 
-    let (ciphertext, shared_secret) = MlKem768::encapsulate(&server_pk, &mut OsRng);
+/// Outcome of checking the detached report signature inside an opened blob
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+pub enum SignatureStatus {
+    /// Blob carried no signature
+    Unsigned,
+    /// Signed, but no DSA PK was supplied to check it
+    NotChecked,
+    Valid,
+    Invalid,
+}
 
-    let aes_key = derive_aes_key(&shared_secret);
-    let cipher = Aes256Gcm::new(&aes_key);
+/// Decrypted attestation blob — report bytes, detached signature + verification status
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct OpenedBlob {
+    pub report: Vec<u8>,
+    pub signature: Option<Vec<u8>>,
+    pub signature_status: SignatureStatus,
+}
 
-    let mut nonce_bytes = [0u8; 12];
-    OsRng.fill_bytes(&mut nonce_bytes);
-    let nonce = Nonce::from_slice(&nonce_bytes);
+/// Check a detached ML-DSA-65 report signature (AttestationReport context) against raw DSA PK bytes
+pub fn signature_status(
+    report: &[u8],
+    signature: Option<&[u8]>,
+    dsa_pk_bytes: Option<&[u8]>,
+) -> MercyResult<SignatureStatus> {
+    let Some(sig_bytes) = signature else {
+        return Ok(SignatureStatus::Unsigned);
+    };
+    let Some(pk_bytes) = dsa_pk_bytes else {
+        return Ok(SignatureStatus::NotChecked);
+    };
+
+    let valid = verify_data(pk_bytes, report, sig_bytes, MessageKind::AttestationReport)?;
+    Ok(if valid {
+        SignatureStatus::Valid
+    } else {
+        SignatureStatus::Invalid
+    })
+}
 
-    let mut encrypted_payload = payload;
-    cipher.encrypt_in_place(nonce, b"", &mut encrypted_payload).expect("Encryption failed");
+/// Open a v2 blob with the recipient KEM secret key (Rust-native — shared with mercy_verifier)
+pub fn open_attestation_blob(
+    blob: &[u8],
+    kem_sk: &MlKem768SecretKey,
+    dsa_pk_bytes: Option<&[u8]>,
+) -> MercyResult<OpenedBlob> {
+    let (header, header_len) = BlobHeader::parse(blob)?;
+
+    let recipient_pk_bytes = pq::kem_public_key_bytes(kem_sk.encapsulation_key());
+    if header.key_id != blob::key_id(&recipient_pk_bytes) {
+        return Err(MercyError::WrongRecipient);
+    }
 
-    let mut blob = ciphertext.into_bytes().to_vec();
-    blob.extend_from_slice(&nonce_bytes);
-    blob.extend_from_slice(&encrypted_payload);
+    let shared_secret = pq::kem_decapsulate(kem_sk, &header.kem_ciphertext)?;
+
+    let payload = blob::open_sealed(
+        blob,
+        &header,
+        header_len,
+        &*shared_secret,
+        &recipient_pk_bytes,
+    )?;
+    let signature_status =
+        signature_status(&payload.report, payload.signature.as_deref(), dsa_pk_bytes)?;
+
+    Ok(OpenedBlob {
+        report: payload.report,
+        signature: payload.signature,
+        signature_status,
+    })
+}
 
-    BASE64.encode(blob)
+/// Open attestation blob (decapsulate, decrypt, verify)
+/// Input: base64 blob, recipient KEM SK base64, optional client DSA PK base64 to check the signature
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn pq_open_attestation_blob(
+    blob_b64: String,
+    server_kem_sk_b64: String,
+    client_dsa_pk_b64: Option<String>,
+) -> MercyResult<OpenedBlob> {
+    let blob = BASE64
+        .decode(blob_b64)
+        .map_err(|_| MercyError::InvalidBase64)?;
+
+    let kem_sk_bytes = SecretBuffer::from_base64(server_kem_sk_b64)?;
+    let kem_sk = pq::kem_secret_key(&kem_sk_bytes)?;
+
+    let dsa_pk_bytes = client_dsa_pk_b64
+        .map(|pk| BASE64.decode(pk))
+        .transpose()
+        .map_err(|_| MercyError::InvalidBase64)?;
+
+    open_attestation_blob(&blob, &kem_sk, dsa_pk_bytes.as_deref())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn test_kem_keypair_b64() -> (String, String) {
+        let (sk, pk) = pq::kem_generate(&mut OsRng);
+        (
+            BASE64.encode(pq::kem_secret_key_bytes(&sk)),
+            BASE64.encode(pq::kem_public_key_bytes(&pk)),
+        )
+    }
+
+    fn test_dsa_keypair_b64() -> (String, String) {
+        let sk = pq::dsa_generate(&mut OsRng);
+        (
+            BASE64.encode(pq::dsa_secret_key_bytes(&sk)),
+            BASE64.encode(pq::dsa_public_key_bytes(&sk)),
+        )
+    }
+
     #[test]
     fn test_key_generation_non_empty() {
-        let (kem_pk_b64, dsa_pk_b64) = generate_pq_keypair();
+        let PqKeyPair {
+            kem_pk_b64,
+            dsa_pk_b64,
+            kem_sk_b64,
+            dsa_sk_b64,
+        } = generate_pq_keypair();
         assert!(!kem_pk_b64.is_empty(), "KEM PK empty");
         assert!(!dsa_pk_b64.is_empty(), "DSA PK empty");
 
         let kem_pk_bytes = BASE64.decode(&kem_pk_b64).unwrap();
-        assert_eq!(kem_pk_bytes.len(), MlKem768PublicKey::BYTE_LEN, "Wrong KEM PK size");
+        assert_eq!(kem_pk_bytes.len(), pq::KEM_PK_LEN, "Wrong KEM PK size");
+        assert_eq!(
+            BASE64.decode(&kem_sk_b64).unwrap().len(),
+            pq::KEM_SK_LEN,
+            "Wrong KEM SK size"
+        );
+
+        // The returned SKs are the pair of the returned PKs
+        let sig = pq_sign_data(
+            dsa_sk_b64,
+            b"report".to_vec(),
+            MessageKind::AttestationReport,
+        )
+        .unwrap();
+        assert_eq!(
+            pq_verify_data(
+                dsa_pk_b64,
+                b"report".to_vec(),
+                sig,
+                MessageKind::AttestationReport
+            ),
+            Ok(true)
+        );
+        let blob = pq_secure_attestation_blob(b"{}".to_vec(), kem_pk_b64, None).unwrap();
+        assert_eq!(
+            pq_open_attestation_blob(blob, kem_sk_b64, None)
+                .unwrap()
+                .report,
+            b"{}".to_vec()
+        );
     }
 
     #[test]
     fn test_sign_verify_roundtrip() {
-        let message = b"MercyShieldPlus Eternal Integrity Report ⚡️".to_vec();
+        let message = "MercyShieldPlus Eternal Integrity Report ⚡️"
+            .as_bytes()
+            .to_vec();
 
-        let (_kem_pk_b64, dsa_pk_b64) = generate_pq_keypair();  // Fresh keys
-        let (kem_pk_b64_2, dsa_pk_b64_2) = generate_pq_keypair();  // Another for SK
+        let (dsa_sk_b64, dsa_pk_b64) = test_dsa_keypair_b64(); // Persisted-style SK + PK
 
-        // Use second pair's SK to sign (simulate persisted)
-        let sig_b64 = pq_sign_data(dsa_pk_b64_2.clone(), message.clone());
+        let sig_b64 =
+            pq_sign_data(dsa_sk_b64, message.clone(), MessageKind::AttestationReport).unwrap();
 
-        let verified = pq_verify_data(dsa_pk_b64_2, message, sig_b64);
+        let verified =
+            pq_verify_data(dsa_pk_b64, message, sig_b64, MessageKind::AttestationReport).unwrap();
         assert!(verified, "Signature verification failed on roundtrip");
     }
 
@@ -134,152 +398,310 @@ mod tests {
         let message = b"Original Report".to_vec();
         let tampered = b"Tampered Report".to_vec();
 
-        let (_kem_pk_b64, dsa_pk_b64) = generate_pq_keypair();
-        let (kem_pk_b64_2, dsa_pk_b64_2) = generate_pq_keypair();
+        let (dsa_sk_b64, dsa_pk_b64) = test_dsa_keypair_b64();
 
-        let sig_b64 = pq_sign_data(dsa_pk_b64_2.clone(), message);
+        let sig_b64 = pq_sign_data(dsa_sk_b64, message, MessageKind::AttestationReport).unwrap();
 
-        let verified = pq_verify_data(dsa_pk_b64_2, tampered, sig_b64);
+        let verified = pq_verify_data(
+            dsa_pk_b64,
+            tampered,
+            sig_b64,
+            MessageKind::AttestationReport,
+        )
+        .unwrap();
         assert!(!verified, "Tampered message verified — security failure");
     }
 
+    #[test]
+    fn test_signature_bound_to_message_kind() {
+        let message = b"{\"epoch\":7}".to_vec();
+        let (dsa_sk_b64, dsa_pk_b64) = test_dsa_keypair_b64();
+
+        for signed_as in MessageKind::ALL {
+            let sig_b64 = pq_sign_data(dsa_sk_b64.clone(), message.clone(), signed_as).unwrap();
+            for verified_as in MessageKind::ALL {
+                let ok = pq_verify_data(
+                    dsa_pk_b64.clone(),
+                    message.clone(),
+                    sig_b64.clone(),
+                    verified_as,
+                )
+                .unwrap();
+                assert_eq!(
+                    ok,
+                    signed_as == verified_as,
+                    "{:?} signature accepted as {:?}",
+                    signed_as,
+                    verified_as
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_blob_rejects_non_report_signature() {
+        let report = b"{\"risk\":0}".to_vec();
+        let (dsa_sk_b64, dsa_pk_b64) = test_dsa_keypair_b64();
+        let dsa_pk_bytes = BASE64.decode(dsa_pk_b64).unwrap();
+
+        // A ledger-entry signature over identical bytes must not pass as a report signature
+        let ledger_sig = BASE64
+            .decode(pq_sign_data(dsa_sk_b64, report.clone(), MessageKind::LedgerEntry).unwrap())
+            .unwrap();
+        assert_eq!(
+            signature_status(&report, Some(&ledger_sig), Some(&dsa_pk_bytes)),
+            Ok(SignatureStatus::Invalid)
+        );
+    }
+
     #[test]
     fn test_blob_generation_format() {
         let report = b"{\"status\":\"genuine\"}".to_vec();
-        let server_pk_b64 = generate_pq_keypair().0;  // Use generated KEM PK as "server"
+        let server_pk_b64 = generate_pq_keypair().kem_pk_b64; // Use generated KEM PK as "server"
 
-        let blob_b64 = pq_secure_attestation_blob(report.clone(), server_pk_b64.clone(), None);
+        let blob_b64 =
+            pq_secure_attestation_blob(report.clone(), server_pk_b64.clone(), None).unwrap();
 
         let blob_bytes = BASE64.decode(blob_b64).unwrap();
-        assert!(blob_bytes.len() > MlKem768Ciphertext::BYTE_LEN + 12 + 16, "Blob too short");
+        assert!(
+            blob_bytes.len() > pq::KEM_CT_LEN + 12 + 16,
+            "Blob too short"
+        );
+
+        let (header, _) = BlobHeader::parse(&blob_bytes).unwrap();
+        assert_eq!(header.sig_alg, SignatureAlgorithm::None);
+        assert_eq!(header.kem_ciphertext.len(), pq::KEM_CT_LEN);
+        assert_eq!(
+            header.key_id,
+            blob::key_id(&BASE64.decode(server_pk_b64).unwrap())
+        );
     }
 
     #[test]
     fn test_blob_with_signature() {
         let report = b"{\"risk\":0}".to_vec();
 
-        let (_kem_pk, dsa_pk_b64) = generate_pq_keypair();
-        let (kem_pk_server, dsa_pk_client) = generate_pq_keypair();  // Client has SK
+        let (_kem_sk_server, kem_pk_server) = test_kem_keypair_b64();
+        let (dsa_sk_client, _dsa_pk_client) = test_dsa_keypair_b64(); // Client has SK
 
         let blob_b64 = pq_secure_attestation_blob(
             report.clone(),
             kem_pk_server,
-            Some(dsa_pk_client.clone()),  // Sign with client SK
-        );
+            Some(dsa_sk_client), // Sign with client SK
+        )
+        .unwrap();
 
         let blob_bytes = BASE64.decode(blob_b64).unwrap();
         assert!(blob_bytes.len() > 4000, "Blob missing signature length");
+
+        let (header, _) = BlobHeader::parse(&blob_bytes).unwrap();
+        assert_eq!(header.sig_alg, SignatureAlgorithm::MlDsa65);
     }
 
     #[test]
     fn test_derive_aes_key() {
-        let (_sk, _pk) = MlKem768::generate(&mut OsRng);
-        let (ct, ss) = MlKem768::encapsulate(&_pk, &mut OsRng);
+        let (_sk, pk) = pq::kem_generate(&mut OsRng);
+        let (ct_bytes, ss) = pq::kem_encapsulate(&pk, &mut OsRng).unwrap();
+        let pk_bytes = pq::kem_public_key_bytes(&pk);
 
-        let key = derive_aes_key(&ss);
+        let key = blob::blob_key(&*ss, &ct_bytes, &pk_bytes).unwrap();
         assert_eq!(key.len(), 32, "Derived key wrong size");
         assert_ne!(*key, [0u8; 32], "Derived key all zero");
+        assert_ne!(&key[..], &ss[..], "Raw shared secret used as AES key");
     }
-}
-    let (ciphertext, shared_secret) = MlKem768::encapsulate(&server_pk, &mut OsRng);
 
-    let aes_key = derive_aes_key(&shared_secret);
-    let cipher = Aes256Gcm::new(&aes_key);
+    #[test]
+    fn test_blob_open_roundtrip_unsigned() {
+        let (kem_sk, kem_pk) = test_kem_keypair_b64();
+        let report = b"{\"status\":\"genuine\"}".to_vec();
 
-    let mut nonce_bytes = [0u8; 12];
-    OsRng.fill_bytes(&mut nonce_bytes);
-    let nonce = Nonce::from_slice(&nonce_bytes);
+        let blob_b64 = pq_secure_attestation_blob(report.clone(), kem_pk, None).unwrap();
+        let opened = pq_open_attestation_blob(blob_b64, kem_sk, None).unwrap();
 
-    let mut encrypted_payload = payload;
-    cipher.encrypt_in_place(nonce, b"", &mut encrypted_payload).expect("Encryption failed");
+        assert_eq!(opened.report, report);
+        assert_eq!(opened.signature, None);
+        assert_eq!(opened.signature_status, SignatureStatus::Unsigned);
+    }
 
-    let mut blob = ciphertext.into_bytes().to_vec();
-    blob.extend_from_slice(&nonce_bytes);
-    blob.extend_from_slice(&encrypted_payload);
+    #[test]
+    fn test_blob_open_roundtrip_signed() {
+        let (kem_sk, kem_pk) = test_kem_keypair_b64();
+        let (dsa_sk, dsa_pk) = test_dsa_keypair_b64();
+        let (_other_sk, other_pk) = test_dsa_keypair_b64();
+        let report = b"{\"risk\":0}".to_vec();
 
-    BASE64.encode(blob)
-}
+        let blob_b64 = pq_secure_attestation_blob(report.clone(), kem_pk, Some(dsa_sk)).unwrap();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let opened =
+            pq_open_attestation_blob(blob_b64.clone(), kem_sk.clone(), Some(dsa_pk)).unwrap();
+        assert_eq!(opened.report, report);
+        assert_eq!(opened.signature_status, SignatureStatus::Valid);
 
-    #[test]
-    fn test_key_generation_non_empty() {
-        let (kem_pk_b64, dsa_pk_b64) = generate_pq_keypair();
-        assert!(!kem_pk_b64.is_empty(), "KEM PK empty");
-        assert!(!dsa_pk_b64.is_empty(), "DSA PK empty");
+        let unchecked = pq_open_attestation_blob(blob_b64.clone(), kem_sk.clone(), None).unwrap();
+        assert_eq!(unchecked.signature_status, SignatureStatus::NotChecked);
 
-        let kem_pk_bytes = BASE64.decode(&kem_pk_b64).unwrap();
-        assert_eq!(kem_pk_bytes.len(), MlKem768PublicKey::BYTE_LEN, "Wrong KEM PK size");
+        let wrong_key = pq_open_attestation_blob(blob_b64, kem_sk, Some(other_pk)).unwrap();
+        assert_eq!(wrong_key.signature_status, SignatureStatus::Invalid);
     }
 
     #[test]
-    fn test_sign_verify_roundtrip() {
-        let message = b"MercyShieldPlus Eternal Integrity Report ⚡️".to_vec();
+    fn test_blob_open_rejects_tamper_and_wrong_recipient() {
+        let (kem_sk, kem_pk) = test_kem_keypair_b64();
+        let (other_kem_sk, _) = test_kem_keypair_b64();
+
+        let blob_b64 = pq_secure_attestation_blob(b"{}".to_vec(), kem_pk, None).unwrap();
+        assert_eq!(
+            pq_open_attestation_blob(blob_b64.clone(), other_kem_sk, None),
+            Err(MercyError::WrongRecipient)
+        );
 
-        let (_kem_pk_b64, dsa_pk_b64) = generate_pq_keypair();  // Fresh keys
-        let (kem_pk_b64_2, dsa_pk_b64_2) = generate_pq_keypair();  // Another for SK
+        let mut blob = BASE64.decode(&blob_b64).unwrap();
+        let (_, header_len) = BlobHeader::parse(&blob).unwrap();
 
-        // Use second pair's SK to sign (simulate persisted)
-        let sig_b64 = pq_sign_data(dsa_pk_b64_2.clone(), message.clone());
+        // Header is AAD — flipping a nonce byte must fail authentication
+        let mut tampered_header = blob.clone();
+        tampered_header[header_len - 1] ^= 0x01;
+        assert_eq!(
+            pq_open_attestation_blob(BASE64.encode(tampered_header), kem_sk.clone(), None),
+            Err(MercyError::DecryptionFailed)
+        );
 
-        let verified = pq_verify_data(dsa_pk_b64_2, message, sig_b64);
-        assert!(verified, "Signature verification failed on roundtrip");
+        let last = blob.len() - 1;
+        blob[last] ^= 0x80;
+        assert_eq!(
+            pq_open_attestation_blob(BASE64.encode(blob), kem_sk, None),
+            Err(MercyError::DecryptionFailed)
+        );
     }
 
     #[test]
-    fn test_sign_verify_tampered_message() {
-        let message = b"Original Report".to_vec();
-        let tampered = b"Tampered Report".to_vec();
-
-        let (_kem_pk_b64, dsa_pk_b64) = generate_pq_keypair();
-        let (kem_pk_b64_2, dsa_pk_b64_2) = generate_pq_keypair();
+    fn test_malformed_inputs_return_errors() {
+        let PqKeyPair {
+            kem_pk_b64,
+            dsa_pk_b64,
+            ..
+        } = generate_pq_keypair();
+
+        assert_eq!(
+            pq_sign_data("not base64!".into(), b"x".to_vec(), MessageKind::LogExport),
+            Err(MercyError::InvalidBase64)
+        );
+        assert_eq!(
+            pq_sign_data(
+                BASE64.encode([0u8; 7]),
+                b"x".to_vec(),
+                MessageKind::LogExport
+            ),
+            Err(MercyError::InvalidKey)
+        );
 
-        let sig_b64 = pq_sign_data(dsa_pk_b64_2.clone(), message);
+        let kind = MessageKind::LedgerEntry;
+        assert_eq!(
+            pq_verify_data("%%".into(), b"x".to_vec(), "AAAA".into(), kind),
+            Err(MercyError::InvalidBase64)
+        );
+        assert_eq!(
+            pq_verify_data(BASE64.encode([1u8; 3]), b"x".to_vec(), "AAAA".into(), kind),
+            Err(MercyError::InvalidKey)
+        );
+        assert_eq!(
+            pq_verify_data(dsa_pk_b64, b"x".to_vec(), BASE64.encode([0u8; 5]), kind),
+            Err(MercyError::InvalidSignature)
+        );
 
-        let verified = pq_verify_data(dsa_pk_b64_2, tampered, sig_b64);
-        assert!(!verified, "Tampered message verified — security failure");
+        assert_eq!(
+            pq_secure_attestation_blob(b"{}".to_vec(), "@@".into(), None),
+            Err(MercyError::InvalidBase64)
+        );
+        assert_eq!(
+            pq_secure_attestation_blob(b"{}".to_vec(), BASE64.encode([0u8; 10]), None),
+            Err(MercyError::InvalidKey)
+        );
+        assert_eq!(
+            pq_secure_attestation_blob(b"{}".to_vec(), kem_pk_b64, Some("!!".into())),
+            Err(MercyError::InvalidBase64)
+        );
     }
 
     #[test]
-    fn test_blob_generation_format() {
-        let report = b"{\"status\":\"genuine\"}".to_vec();
-        let server_pk_b64 = generate_pq_keypair().0;  // Use generated KEM PK as "server"
-
-        let blob_b64 = pq_secure_attestation_blob(report.clone(), server_pk_b64.clone(), None);
-
-        let blob_bytes = BASE64.decode(blob_b64).unwrap();
-        assert!(blob_bytes.len() > MlKem768Ciphertext::BYTE_LEN + 12 + 16, "Blob too short");
-
-        // Basic structure check
-        assert_eq!(blob_bytes.len() % 4 == 0, false);  // Base64 decoded
+    #[cfg(feature = "uniffi")]
+    fn test_prehash_sign_verify_streamed() {
+        let (dsa_sk_b64, dsa_pk_b64) = test_dsa_keypair_b64();
+        let chunks: [&[u8]; 3] = [b"log line 1\n", b"log line 2\n", b"log line 3\n"];
+
+        for alg in [
+            prehash::PrehashAlgorithm::Sha512,
+            prehash::PrehashAlgorithm::Shake256,
+        ] {
+            let signer = prehash::PqPrehashState::new(alg);
+            for c in chunks {
+                signer.update(c.to_vec()).unwrap();
+            }
+            let sig_b64 = signer
+                .sign(dsa_sk_b64.clone(), MessageKind::LogExport)
+                .unwrap();
+            assert_eq!(
+                signer.update(b"late".to_vec()),
+                Err(MercyError::InvalidState)
+            );
+
+            let verifier = prehash::PqPrehashState::new(alg);
+            verifier.update(chunks.concat()).unwrap();
+            assert!(verifier
+                .verify(dsa_pk_b64.clone(), sig_b64.clone(), MessageKind::LogExport)
+                .unwrap());
+
+            // Pre-hash signatures never verify as pure ML-DSA, nor under another context
+            let pure = pq_verify_data(
+                dsa_pk_b64.clone(),
+                chunks.concat(),
+                sig_b64.clone(),
+                MessageKind::LogExport,
+            );
+            assert_eq!(pure, Ok(false));
+            let other = prehash::PqPrehashState::new(alg);
+            other.update(chunks.concat()).unwrap();
+            assert!(!other
+                .verify(dsa_pk_b64.clone(), sig_b64, MessageKind::LedgerEntry)
+                .unwrap());
+        }
     }
 
     #[test]
-    fn test_blob_with_signature() {
-        let report = b"{\"risk\":0}".to_vec();
-
-        let (_kem_pk, dsa_pk_b64) = generate_pq_keypair();
-        let (kem_pk_server, dsa_pk_client) = generate_pq_keypair();  // Client has SK
+    fn test_seeded_rng_reproduces_keys_and_blobs() {
+        use rng::SeededRng;
 
-        let blob_b64 = pq_secure_attestation_blob(
-            report.clone(),
-            kem_pk_server,
-            Some(dsa_pk_client.clone()),  // Sign with client SK
+        assert_eq!(
+            generate_pq_keypair_with_rng(&mut SeededRng::from_seed(b"kat-keypair")),
+            generate_pq_keypair_with_rng(&mut SeededRng::from_seed(b"kat-keypair"))
         );
 
-        let blob_bytes = BASE64.decode(blob_b64).unwrap();
-        assert!(blob_bytes.len() > 4000, "Blob missing signature length");  // Rough sig append
-    }
-
-    #[test]
-    fn test_derive_aes_key() {
-        let (_sk, _pk) = MlKem768::generate(&mut OsRng);
-        let (ct, ss) = MlKem768::encapsulate(&_pk, &mut OsRng);
+        let (kem_sk, kem_pk) = pq::kem_generate(&mut SeededRng::from_seed(b"kat-server"));
+        let dsa_sk = pq::dsa_generate(&mut SeededRng::from_seed(b"kat-device"));
+        let dsa_sk_bytes = pq::dsa_secret_key_bytes(&dsa_sk);
+        let dsa_pk_bytes = pq::dsa_public_key_bytes(&dsa_sk);
+        let pk_bytes = pq::kem_public_key_bytes(&kem_pk);
+        let report = b"{\"timestamp\":1700000000000,\"details\":[]}".to_vec();
+
+        let seal = |seed: &[u8]| {
+            secure_attestation_blob_with_rng(
+                report.clone(),
+                &pk_bytes,
+                Some(&dsa_sk_bytes[..]),
+                &mut SeededRng::from_seed(seed),
+            )
+            .unwrap()
+        };
+        let blob = seal(b"kat-blob");
+        assert_eq!(
+            blob,
+            seal(b"kat-blob"),
+            "same seed must give a byte-identical blob"
+        );
+        assert_ne!(blob, seal(b"kat-blob-2"));
 
-        let key = derive_aes_key(&ss);
-        assert_eq!(key.len(), 32, "Derived key wrong size");
-        assert_ne!(*key, [0u8; 32], "Derived key all zero");
+        let opened = open_attestation_blob(&blob, &kem_sk, Some(&dsa_pk_bytes)).unwrap();
+        assert_eq!(opened.report, report);
+        assert_eq!(opened.signature_status, SignatureStatus::Valid);
     }
 }
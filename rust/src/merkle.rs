@@ -0,0 +1,646 @@
+// rust/src/merkle.rs — Transparency Log Eternal (RFC 6962 / 9162 Merkle tree over ledger entries)
+//
+//   leaf_hash   = SHA-256(0x00 || leaf)
+//   node_hash   = SHA-256(0x01 || left || right)
+//   MTH({})     = SHA-256("")
+//
+// The split point, audit paths and consistency proofs follow RFC 6962 §2.1 exactly (verification
+// per RFC 9162 §2.1.3.2 / §2.1.4.2), so any CT-style auditor can check them. Leaves are encoded
+// `LedgerEntry` bytes; the device signs tree heads so a third party holding two heads can confirm
+// the log was only appended to without seeing the entries.
+//
+// Signed tree head layout (all integers big-endian):
+//   magic         4   b"MSPT"
+//   version       1   TREE_HEAD_VERSION
+//   tree_size     8   number of leaves
+//   timestamp_ms  8   when the head was signed
+//   root_hash    32   MTH of the first tree_size leaves
+//   ------------- everything above is signed by the device key (TreeHead ctx) -------------
+//   signature   2+n   ML-DSA-65 signature
+use alloc::vec::Vec;
+use rand_core::{CryptoRngCore, OsRng};
+use sha2::{Digest, Sha256};
+
+use crate::blob::Reader;
+use crate::context::MessageKind;
+use crate::error::{MercyError, MercyResult};
+use crate::pq;
+
+pub const TREE_HEAD_MAGIC: [u8; 4] = *b"MSPT";
+pub const TREE_HEAD_VERSION: u8 = 1;
+pub const HASH_LEN: usize = 32;
+
+pub type Hash = [u8; HASH_LEN];
+
+pub fn leaf_hash(leaf: &[u8]) -> Hash {
+    let mut h = Sha256::new();
+    h.update([0x00]);
+    h.update(leaf);
+    h.finalize().into()
+}
+
+pub fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut h = Sha256::new();
+    h.update([0x01]);
+    h.update(left);
+    h.update(right);
+    h.finalize().into()
+}
+
+/// Largest power of two strictly below `n` (n ≥ 2)
+fn split(n: usize) -> usize {
+    1 << (usize::BITS - 1 - (n - 1).leading_zeros())
+}
+
+fn subtree_root(leaves: &[Hash]) -> Hash {
+    match leaves.len() {
+        0 => Sha256::digest(b"").into(),
+        1 => leaves[0],
+        n => {
+            let k = split(n);
+            node_hash(&subtree_root(&leaves[..k]), &subtree_root(&leaves[k..]))
+        }
+    }
+}
+
+fn audit_path(index: usize, leaves: &[Hash], out: &mut Vec<Hash>) {
+    let n = leaves.len();
+    if n <= 1 {
+        return;
+    }
+    let k = split(n);
+    if index < k {
+        audit_path(index, &leaves[..k], out);
+        out.push(subtree_root(&leaves[k..]));
+    } else {
+        audit_path(index - k, &leaves[k..], out);
+        out.push(subtree_root(&leaves[..k]));
+    }
+}
+
+fn subproof(m: usize, leaves: &[Hash], complete: bool, out: &mut Vec<Hash>) {
+    let n = leaves.len();
+    if m == n {
+        if !complete {
+            out.push(subtree_root(leaves));
+        }
+        return;
+    }
+    let k = split(n);
+    if m <= k {
+        subproof(m, &leaves[..k], complete, out);
+        out.push(subtree_root(&leaves[k..]));
+    } else {
+        subproof(m - k, &leaves[k..], false, out);
+        out.push(subtree_root(&leaves[..k]));
+    }
+}
+
+fn to_hashes(path: &[Vec<u8>]) -> Option<Vec<Hash>> {
+    path.iter()
+        .map(|node| Hash::try_from(node.as_slice()).ok())
+        .collect()
+}
+
+/// Audit path proving one leaf is in the tree of `tree_size` leaves
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct InclusionProof {
+    pub leaf_index: u64,
+    pub tree_size: u64,
+    pub path: Vec<Vec<u8>>,
+}
+
+impl InclusionProof {
+    /// RFC 9162 §2.1.3.2 — a malformed path simply fails
+    pub fn verify(&self, leaf_hash: &Hash, root: &Hash) -> bool {
+        let Some(path) = to_hashes(&self.path) else {
+            return false;
+        };
+        if self.leaf_index >= self.tree_size {
+            return false;
+        }
+        let (mut fn_, mut sn) = (self.leaf_index, self.tree_size - 1);
+        let mut r = *leaf_hash;
+        for p in &path {
+            if sn == 0 {
+                return false;
+            }
+            if fn_ & 1 == 1 || fn_ == sn {
+                r = node_hash(p, &r);
+                while fn_ & 1 == 0 && fn_ != 0 {
+                    fn_ >>= 1;
+                    sn >>= 1;
+                }
+            } else {
+                r = node_hash(&r, p);
+            }
+            fn_ >>= 1;
+            sn >>= 1;
+        }
+        sn == 0 && r == *root
+    }
+}
+
+/// Proof that the tree of `old_size` leaves is a prefix of the tree of `new_size` leaves
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct ConsistencyProof {
+    pub old_size: u64,
+    pub new_size: u64,
+    pub path: Vec<Vec<u8>>,
+}
+
+impl ConsistencyProof {
+    /// RFC 9162 §2.1.4.2 — the empty tree is consistent with every tree
+    pub fn verify(&self, old_root: &Hash, new_root: &Hash) -> bool {
+        let Some(mut path) = to_hashes(&self.path) else {
+            return false;
+        };
+        if self.old_size > self.new_size {
+            return false;
+        }
+        if self.old_size == 0 {
+            return path.is_empty();
+        }
+        if self.old_size == self.new_size {
+            return path.is_empty() && old_root == new_root;
+        }
+        if self.old_size.is_power_of_two() {
+            path.insert(0, *old_root);
+        }
+        let Some((first, rest)) = path.split_first() else {
+            return false;
+        };
+
+        let (mut fn_, mut sn) = (self.old_size - 1, self.new_size - 1);
+        while fn_ & 1 == 1 {
+            fn_ >>= 1;
+            sn >>= 1;
+        }
+        let (mut fr, mut sr) = (*first, *first);
+        for c in rest {
+            if sn == 0 {
+                return false;
+            }
+            if fn_ & 1 == 1 || fn_ == sn {
+                fr = node_hash(c, &fr);
+                sr = node_hash(c, &sr);
+                while fn_ & 1 == 0 && fn_ != 0 {
+                    fn_ >>= 1;
+                    sn >>= 1;
+                }
+            } else {
+                sr = node_hash(&sr, c);
+            }
+            fn_ >>= 1;
+            sn >>= 1;
+        }
+        fr == *old_root && sr == *new_root && sn == 0
+    }
+}
+
+/// Append-only Merkle tree over leaf hashes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MerkleTree {
+    leaves: Vec<Hash>,
+}
+
+impl MerkleTree {
+    pub fn new() -> Self {
+        MerkleTree::default()
+    }
+
+    /// Hash and append a leaf — returns its index
+    pub fn push(&mut self, leaf: &[u8]) -> u64 {
+        self.leaves.push(leaf_hash(leaf));
+        self.leaves.len() as u64 - 1
+    }
+
+    pub fn size(&self) -> u64 {
+        self.leaves.len() as u64
+    }
+
+    pub fn root(&self) -> Hash {
+        subtree_root(&self.leaves)
+    }
+
+    /// Root of the first `tree_size` leaves (an earlier version of this tree)
+    pub fn root_at(&self, tree_size: u64) -> MercyResult<Hash> {
+        Ok(subtree_root(self.prefix(tree_size)?))
+    }
+
+    pub fn inclusion_proof(&self, leaf_index: u64, tree_size: u64) -> MercyResult<InclusionProof> {
+        let leaves = self.prefix(tree_size)?;
+        if leaf_index >= tree_size {
+            return Err(MercyError::InvalidTreeSize);
+        }
+        let mut path = Vec::new();
+        audit_path(leaf_index as usize, leaves, &mut path);
+        Ok(InclusionProof {
+            leaf_index,
+            tree_size,
+            path: path.iter().map(|h| h.to_vec()).collect(),
+        })
+    }
+
+    pub fn consistency_proof(&self, old_size: u64, new_size: u64) -> MercyResult<ConsistencyProof> {
+        let leaves = self.prefix(new_size)?;
+        if old_size > new_size {
+            return Err(MercyError::InvalidTreeSize);
+        }
+        let mut path = Vec::new();
+        if old_size > 0 {
+            subproof(old_size as usize, leaves, true, &mut path);
+        }
+        Ok(ConsistencyProof {
+            old_size,
+            new_size,
+            path: path.iter().map(|h| h.to_vec()).collect(),
+        })
+    }
+
+    fn prefix(&self, tree_size: u64) -> MercyResult<&[Hash]> {
+        usize::try_from(tree_size)
+            .ok()
+            .and_then(|n| self.leaves.get(..n))
+            .ok_or(MercyError::InvalidTreeSize)
+    }
+}
+
+impl<L: AsRef<[u8]>> FromIterator<L> for MerkleTree {
+    fn from_iter<I: IntoIterator<Item = L>>(leaves: I) -> Self {
+        MerkleTree {
+            leaves: leaves
+                .into_iter()
+                .map(|leaf| leaf_hash(leaf.as_ref()))
+                .collect(),
+        }
+    }
+}
+
+/// Device-signed commitment to the log at `tree_size`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedTreeHead {
+    pub tree_size: u64,
+    pub timestamp_ms: u64,
+    pub root_hash: Hash,
+    pub signature: Vec<u8>,
+}
+
+impl SignedTreeHead {
+    /// Sign the current head of `tree`
+    pub fn sign(tree: &MerkleTree, timestamp_ms: u64, dsa_sk_bytes: &[u8]) -> MercyResult<Self> {
+        SignedTreeHead::sign_with_rng(tree, timestamp_ms, dsa_sk_bytes, &mut OsRng)
+    }
+
+    /// `sign` with an injected RNG for the randomized signature
+    pub fn sign_with_rng(
+        tree: &MerkleTree,
+        timestamp_ms: u64,
+        dsa_sk_bytes: &[u8],
+        rng: &mut impl CryptoRngCore,
+    ) -> MercyResult<Self> {
+        let dsa_sk = pq::dsa_signing_key(dsa_sk_bytes)?;
+        let mut sth = SignedTreeHead {
+            tree_size: tree.size(),
+            timestamp_ms,
+            root_hash: tree.root(),
+            signature: Vec::new(),
+        };
+        sth.signature = pq::dsa_sign(
+            &dsa_sk,
+            &sth.signed_bytes(),
+            MessageKind::TreeHead.context(),
+            rng,
+        )?;
+        Ok(sth)
+    }
+
+    /// Bytes covered by the device signature
+    pub fn signed_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(21 + HASH_LEN);
+        out.extend_from_slice(&TREE_HEAD_MAGIC);
+        out.push(TREE_HEAD_VERSION);
+        out.extend_from_slice(&self.tree_size.to_be_bytes());
+        out.extend_from_slice(&self.timestamp_ms.to_be_bytes());
+        out.extend_from_slice(&self.root_hash);
+        out
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = self.signed_bytes();
+        out.extend_from_slice(&(self.signature.len() as u16).to_be_bytes());
+        out.extend_from_slice(&self.signature);
+        out
+    }
+
+    pub fn parse(bytes: &[u8]) -> MercyResult<Self> {
+        let mut r = Reader::new(bytes);
+        if r.take(4)? != TREE_HEAD_MAGIC {
+            return Err(MercyError::InvalidBlob);
+        }
+        if r.u8()? != TREE_HEAD_VERSION {
+            return Err(MercyError::UnsupportedVersion);
+        }
+        let tree_size = r.u64()?;
+        let timestamp_ms = r.u64()?;
+        let mut root_hash = [0u8; HASH_LEN];
+        root_hash.copy_from_slice(r.take(HASH_LEN)?);
+        let sig_len = r.u16()? as usize;
+        let signature = r.take(sig_len)?.to_vec();
+        if !r.is_empty() {
+            return Err(MercyError::InvalidBlob);
+        }
+        Ok(SignedTreeHead {
+            tree_size,
+            timestamp_ms,
+            root_hash,
+            signature,
+        })
+    }
+
+    /// Malformed key/signature encodings are errors; a wrong signature is `Ok(false)`
+    pub fn verify(&self, dsa_pk_bytes: &[u8]) -> MercyResult<bool> {
+        crate::verify_data(
+            dsa_pk_bytes,
+            &self.signed_bytes(),
+            &self.signature,
+            MessageKind::TreeHead,
+        )
+    }
+}
+
+/// Auditor check: `leaf` sits at `proof.leaf_index` of the log the device signed in `sth`
+pub fn verify_inclusion(
+    sth: &SignedTreeHead,
+    dsa_pk_bytes: &[u8],
+    leaf: &[u8],
+    proof: &InclusionProof,
+) -> MercyResult<bool> {
+    Ok(sth.verify(dsa_pk_bytes)?
+        && proof.tree_size == sth.tree_size
+        && proof.verify(&leaf_hash(leaf), &sth.root_hash))
+}
+
+/// Auditor check: the log signed in `new` only appended to the log signed in `old`
+pub fn verify_append_only(
+    old: &SignedTreeHead,
+    new: &SignedTreeHead,
+    dsa_pk_bytes: &[u8],
+    proof: &ConsistencyProof,
+) -> MercyResult<bool> {
+    Ok(old.verify(dsa_pk_bytes)?
+        && new.verify(dsa_pk_bytes)?
+        && proof.old_size == old.tree_size
+        && proof.new_size == new.tree_size
+        && proof.verify(&old.root_hash, &new.root_hash))
+}
+
+#[cfg(feature = "uniffi")]
+pub use self::ffi::{pq_verify_consistency, pq_verify_inclusion};
+
+#[cfg(feature = "uniffi")]
+mod ffi {
+    use super::*;
+    use alloc::string::String;
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+
+    fn decode_sth(sth_b64: String) -> MercyResult<SignedTreeHead> {
+        SignedTreeHead::parse(
+            &BASE64
+                .decode(sth_b64)
+                .map_err(|_| MercyError::InvalidBase64)?,
+        )
+    }
+
+    /// Check an encoded ledger entry against a base64 signed tree head and its audit path
+    #[uniffi::export]
+    pub fn pq_verify_inclusion(
+        sth_b64: String,
+        dsa_pk_b64: String,
+        entry: Vec<u8>,
+        proof: InclusionProof,
+    ) -> MercyResult<bool> {
+        let sth = decode_sth(sth_b64)?;
+        let dsa_pk = BASE64
+            .decode(dsa_pk_b64)
+            .map_err(|_| MercyError::InvalidBase64)?;
+        verify_inclusion(&sth, &dsa_pk, &entry, &proof)
+    }
+
+    /// Check that the newer signed tree head only appended to the older one
+    #[uniffi::export]
+    pub fn pq_verify_consistency(
+        old_sth_b64: String,
+        new_sth_b64: String,
+        dsa_pk_b64: String,
+        proof: ConsistencyProof,
+    ) -> MercyResult<bool> {
+        let (old, new) = (decode_sth(old_sth_b64)?, decode_sth(new_sth_b64)?);
+        let dsa_pk = BASE64
+            .decode(dsa_pk_b64)
+            .map_err(|_| MercyError::InvalidBase64)?;
+        verify_append_only(&old, &new, &dsa_pk, &proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    // RFC 6962 reference leaves and roots (certificate-transparency test data)
+    const LEAVES: [&[u8]; 8] = [
+        b"",
+        b"\x00",
+        b"\x10",
+        b"\x20\x21",
+        b"\x30\x31",
+        b"\x40\x41\x42\x43",
+        b"\x50\x51\x52\x53\x54\x55\x56\x57",
+        b"\x60\x61\x62\x63\x64\x65\x66\x67\x68\x69\x6a\x6b\x6c\x6d\x6e\x6f",
+    ];
+    const ROOTS: [&str; 8] = [
+        "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d",
+        "fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125",
+        "aeb6bcfe274b70a14fb067a5e5578264db0fa9b51af5e0ba159158f329e06e77",
+        "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+        "4e3bbb1f7b478dcfe71fb631631519a3bca12c9aefca1612bfce4c13a86264d4",
+        "76e67dadbcdf1e10e1b74ddc608abd2f98dfb16fbce75277b5232a127f2087ef",
+        "ddb89be403809e325750d3d263cd78929c2942b7942a34b77e122c9594a74c8c",
+        "5dc9da79a70659a9ad559cb701ded9a2ab9d823aad2f4960cfe370eff4604328",
+    ];
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn reference_tree() -> MerkleTree {
+        LEAVES.iter().collect()
+    }
+
+    #[test]
+    fn test_rfc6962_roots() {
+        let tree = reference_tree();
+        for (i, root) in ROOTS.iter().enumerate() {
+            assert_eq!(
+                tree.root_at(i as u64 + 1).unwrap().to_vec(),
+                hex(root),
+                "size {}",
+                i + 1
+            );
+        }
+        assert_eq!(
+            MerkleTree::new().root().to_vec(),
+            hex("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+        );
+    }
+
+    #[test]
+    fn test_rfc6962_proof_vectors() {
+        let tree = reference_tree();
+        let inclusion = tree.inclusion_proof(5, 8).unwrap();
+        assert_eq!(
+            inclusion.path,
+            vec![
+                hex("bc1a0643b12e4d2d7c77918f44e0f4f79a838b6cf9ec5b5c283e1f4d88599e6b"),
+                hex("ca854ea128ed050b41b35ffc1b87b8eb2bde461e9e3b5596ece6b9d5975a0ae0"),
+                hex("d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7"),
+            ]
+        );
+        let consistency = tree.consistency_proof(6, 8).unwrap();
+        assert_eq!(
+            consistency.path,
+            vec![
+                hex("0ebc5d3437fbe2db158b9f126a1d118e308181031d0a949f8dededebc558ef6a"),
+                hex("ca854ea128ed050b41b35ffc1b87b8eb2bde461e9e3b5596ece6b9d5975a0ae0"),
+                hex("d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7"),
+            ]
+        );
+        assert_eq!(tree.consistency_proof(4, 8).unwrap().path.len(), 1);
+    }
+
+    #[test]
+    fn test_every_inclusion_and_consistency_proof_verifies() {
+        let tree = reference_tree();
+        for size in 1..=8 {
+            let root = tree.root_at(size).unwrap();
+            for index in 0..size {
+                let proof = tree.inclusion_proof(index, size).unwrap();
+                assert!(
+                    proof.verify(&leaf_hash(LEAVES[index as usize]), &root),
+                    "{} in {}",
+                    index,
+                    size
+                );
+                let wrong_leaf = leaf_hash(b"forged");
+                assert!(!proof.verify(&wrong_leaf, &root));
+            }
+            for old in 0..=size {
+                let proof = tree.consistency_proof(old, size).unwrap();
+                let old_root = tree.root_at(old).unwrap();
+                assert!(proof.verify(&old_root, &root), "{} -> {}", old, size);
+                if old > 0 && old < size {
+                    assert!(!proof.verify(&root, &root));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_tampered_proofs_rejected() {
+        let tree = reference_tree();
+        let root = tree.root();
+
+        let mut proof = tree.inclusion_proof(2, 8).unwrap();
+        proof.leaf_index = 3;
+        assert!(!proof.verify(&leaf_hash(LEAVES[2]), &root));
+        let mut proof = tree.inclusion_proof(2, 8).unwrap();
+        proof.path.pop();
+        assert!(!proof.verify(&leaf_hash(LEAVES[2]), &root));
+        let mut proof = tree.inclusion_proof(2, 8).unwrap();
+        proof.path[0].truncate(31);
+        assert!(!proof.verify(&leaf_hash(LEAVES[2]), &root));
+
+        // A rewritten history is not consistent with the head it replaced
+        let mut rewritten: Vec<&[u8]> = LEAVES.to_vec();
+        rewritten[1] = b"rewritten";
+        let rewritten: MerkleTree = rewritten.iter().collect();
+        let proof = rewritten.consistency_proof(4, 8).unwrap();
+        assert!(!proof.verify(&tree.root_at(4).unwrap(), &rewritten.root()));
+    }
+
+    #[test]
+    fn test_out_of_range_requests() {
+        let tree = reference_tree();
+        assert_eq!(tree.inclusion_proof(8, 8), Err(MercyError::InvalidTreeSize));
+        assert_eq!(tree.inclusion_proof(0, 9), Err(MercyError::InvalidTreeSize));
+        assert_eq!(
+            tree.consistency_proof(5, 4),
+            Err(MercyError::InvalidTreeSize)
+        );
+        assert_eq!(tree.root_at(9), Err(MercyError::InvalidTreeSize));
+    }
+
+    #[test]
+    fn test_tree_head_encode_parse_roundtrip() {
+        let sth = SignedTreeHead {
+            tree_size: 8,
+            timestamp_ms: 5,
+            root_hash: [3; HASH_LEN],
+            signature: vec![1; 9],
+        };
+        let bytes = sth.encode();
+        assert_eq!(SignedTreeHead::parse(&bytes), Ok(sth));
+        assert_eq!(
+            SignedTreeHead::parse(&bytes[..bytes.len() - 1]),
+            Err(MercyError::InvalidBlob)
+        );
+        let mut version = bytes;
+        version[4] = 2;
+        assert_eq!(
+            SignedTreeHead::parse(&version),
+            Err(MercyError::UnsupportedVersion)
+        );
+    }
+
+    #[test]
+    fn test_signed_heads_audit_append_only() {
+        let sk = pq::dsa_generate(&mut OsRng);
+        let (sk, pk) = (pq::dsa_secret_key_bytes(&sk), pq::dsa_public_key_bytes(&sk));
+        let mut tree: MerkleTree = LEAVES[..3].iter().collect();
+        let old = SignedTreeHead::sign(&tree, 1, &sk).unwrap();
+        for leaf in &LEAVES[3..] {
+            tree.push(leaf);
+        }
+        let new = SignedTreeHead::sign(&tree, 2, &sk).unwrap();
+
+        let consistency = tree.consistency_proof(3, 8).unwrap();
+        assert_eq!(verify_append_only(&old, &new, &pk, &consistency), Ok(true));
+        assert_eq!(verify_append_only(&new, &old, &pk, &consistency), Ok(false));
+
+        let inclusion = tree.inclusion_proof(6, 8).unwrap();
+        assert_eq!(verify_inclusion(&new, &pk, LEAVES[6], &inclusion), Ok(true));
+        assert_eq!(
+            verify_inclusion(&new, &pk, LEAVES[5], &inclusion),
+            Ok(false)
+        );
+
+        let mut forged = new.clone();
+        forged.root_hash = old.root_hash;
+        assert_eq!(forged.verify(&pk), Ok(false));
+        assert_eq!(
+            crate::verify_data(
+                &pk,
+                &new.signed_bytes(),
+                &new.signature,
+                MessageKind::LedgerEntry
+            ),
+            Ok(false)
+        );
+    }
+}
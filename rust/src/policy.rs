@@ -0,0 +1,305 @@
+// rust/src/policy.rs — Risk Scoring Policy Eternal (per-evidence weights, caps, thresholds, hard fails)
+//
+// A policy is a JSON document (the shipped one is `policy/default.json`):
+//
+//   {
+//     "id": "mercyshieldplus-default", "version": 1, "max_score": 100,
+//     "thresholds": { "suspicious": 1, "compromised": 50 },
+//     "rules": { "suspicious_file": { "weight": 40, "per_item": 0, "cap": 40 }, ... },
+//     "hard_fail": ["suspicious_file"]
+//   }
+//
+// Each present evidence kind contributes min(cap, weight + per_item × (count − 1)); kinds without a
+// rule contribute nothing. The total is clamped to max_score and mapped to a verdict through the
+// thresholds, except that any hard-fail kind forces Compromised. `id`/`version` are copied into
+// every report so the verifier knows which policy produced a score.
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::Deserialize;
+
+use crate::error::{MercyError, MercyResult};
+use crate::integrity::IntegrityVerdict;
+
+/// The policy document compiled into the core
+pub const DEFAULT_POLICY_JSON: &str = include_str!("../policy/default.json");
+
+/// Scored evidence category — the policy document's rule keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvidenceKind {
+    SuspiciousFile,
+    TamperProp,
+    RootPackage,
+    Magisk,
+    KernelRoot,
+    SignatureMismatch,
+    ApkChecksumMismatch,
+    PlayTokenMissing,
+    Debugger,
+    Frida,
+    RwxMemory,
+}
+
+impl EvidenceKind {
+    /// Rule key in the policy document and `type` tag in report JSON
+    pub fn name(self) -> &'static str {
+        match self {
+            EvidenceKind::SuspiciousFile => "suspicious_file",
+            EvidenceKind::TamperProp => "tamper_prop",
+            EvidenceKind::RootPackage => "root_package",
+            EvidenceKind::Magisk => "magisk",
+            EvidenceKind::KernelRoot => "kernel_root",
+            EvidenceKind::SignatureMismatch => "signature_mismatch",
+            EvidenceKind::ApkChecksumMismatch => "apk_checksum_mismatch",
+            EvidenceKind::PlayTokenMissing => "play_token_missing",
+            EvidenceKind::Debugger => "debugger",
+            EvidenceKind::Frida => "frida",
+            EvidenceKind::RwxMemory => "rwx_memory",
+        }
+    }
+}
+
+/// How much one evidence kind adds to the risk score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EvidenceRule {
+    /// Score for the first occurrence
+    pub weight: u8,
+    /// Score for each further occurrence
+    #[serde(default)]
+    pub per_item: u8,
+    /// Most this kind can contribute in total
+    pub cap: u8,
+}
+
+/// Lowest scores that earn each non-genuine verdict
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VerdictThresholds {
+    pub suspicious: u8,
+    pub compromised: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RiskPolicy {
+    pub id: String,
+    pub version: u32,
+    pub max_score: u8,
+    pub thresholds: VerdictThresholds,
+    pub rules: BTreeMap<EvidenceKind, EvidenceRule>,
+    #[serde(default)]
+    pub hard_fail: Vec<EvidenceKind>,
+}
+
+impl RiskPolicy {
+    /// Parse and validate a policy document
+    pub fn from_json(json: &str) -> MercyResult<Self> {
+        let policy: RiskPolicy =
+            serde_json::from_str(json).map_err(|_| MercyError::InvalidPolicy)?;
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    /// The shipped policy (`policy/default.json`)
+    pub fn default_policy() -> Self {
+        RiskPolicy::from_json(DEFAULT_POLICY_JSON).expect("shipped policy is valid")
+    }
+
+    fn validate(&self) -> MercyResult<()> {
+        let t = &self.thresholds;
+        if self.id.is_empty()
+            || self.max_score > 100
+            || t.suspicious == 0
+            || t.suspicious > t.compromised
+            || t.compromised > self.max_score
+            || self.rules.values().any(|rule| rule.weight > rule.cap)
+        {
+            return Err(MercyError::InvalidPolicy);
+        }
+        Ok(())
+    }
+
+    /// Score `evidence` (kind, number of findings) — zero counts are ignored
+    pub fn score(&self, evidence: &[(EvidenceKind, usize)]) -> (u8, IntegrityVerdict) {
+        let mut total: u32 = 0;
+        let mut hard_fail = false;
+        for &(kind, count) in evidence.iter().filter(|(_, count)| *count > 0) {
+            if let Some(rule) = self.rules.get(&kind) {
+                let extra = u32::from(rule.per_item)
+                    .saturating_mul(u32::try_from(count - 1).unwrap_or(u32::MAX));
+                total += extra
+                    .saturating_add(u32::from(rule.weight))
+                    .min(u32::from(rule.cap));
+            }
+            hard_fail |= self.hard_fail.contains(&kind);
+        }
+
+        let score = total.min(u32::from(self.max_score)) as u8;
+        let verdict = if hard_fail || score >= self.thresholds.compromised {
+            IntegrityVerdict::Compromised
+        } else if score >= self.thresholds.suspicious {
+            IntegrityVerdict::Suspicious
+        } else {
+            IntegrityVerdict::Genuine
+        };
+        (score, verdict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_KINDS: [EvidenceKind; 11] = [
+        EvidenceKind::SuspiciousFile,
+        EvidenceKind::TamperProp,
+        EvidenceKind::RootPackage,
+        EvidenceKind::Magisk,
+        EvidenceKind::KernelRoot,
+        EvidenceKind::SignatureMismatch,
+        EvidenceKind::ApkChecksumMismatch,
+        EvidenceKind::PlayTokenMissing,
+        EvidenceKind::Debugger,
+        EvidenceKind::Frida,
+        EvidenceKind::RwxMemory,
+    ];
+
+    #[test]
+    fn test_default_policy_matches_legacy_weights() {
+        let policy = RiskPolicy::default_policy();
+        assert_eq!(
+            (policy.id.as_str(), policy.version),
+            ("mercyshieldplus-default", 3)
+        );
+        assert!(ALL_KINDS.iter().all(|kind| policy.rules.contains_key(kind)));
+
+        assert_eq!(policy.score(&[]), (0, IntegrityVerdict::Genuine));
+        assert_eq!(
+            policy.score(&[(EvidenceKind::Magisk, 1)]),
+            (20, IntegrityVerdict::Suspicious)
+        );
+        assert_eq!(
+            policy.score(&[(EvidenceKind::TamperProp, 3)]),
+            (30, IntegrityVerdict::Suspicious)
+        );
+        assert_eq!(
+            policy.score(&[(EvidenceKind::PlayTokenMissing, 1)]),
+            (35, IntegrityVerdict::Suspicious)
+        );
+        assert_eq!(
+            policy.score(&[(EvidenceKind::SuspiciousFile, 5)]),
+            (40, IntegrityVerdict::Suspicious)
+        );
+        assert_eq!(
+            policy.score(&[
+                (EvidenceKind::SuspiciousFile, 1),
+                (EvidenceKind::TamperProp, 0),
+                (EvidenceKind::Magisk, 1)
+            ]),
+            (60, IntegrityVerdict::Compromised)
+        );
+        let everything: Vec<_> = ALL_KINDS.iter().map(|&kind| (kind, 2)).collect();
+        assert_eq!(
+            policy.score(&everything),
+            (100, IntegrityVerdict::Compromised)
+        );
+    }
+
+    #[test]
+    fn test_default_policy_hard_fails_repackaged_app() {
+        let policy = RiskPolicy::default_policy();
+        assert_eq!(
+            policy.hard_fail,
+            [
+                EvidenceKind::SignatureMismatch,
+                EvidenceKind::ApkChecksumMismatch,
+                EvidenceKind::Frida
+            ]
+        );
+        assert_eq!(
+            policy.score(&[(EvidenceKind::ApkChecksumMismatch, 1)]).1,
+            IntegrityVerdict::Compromised
+        );
+        assert_eq!(
+            policy.score(&[(EvidenceKind::Debugger, 1)]),
+            (50, IntegrityVerdict::Compromised)
+        );
+        assert_eq!(
+            policy.score(&[(EvidenceKind::RwxMemory, 4)]),
+            (35, IntegrityVerdict::Suspicious)
+        );
+        assert_eq!(
+            policy.score(&[(EvidenceKind::KernelRoot, 1)]),
+            (40, IntegrityVerdict::Suspicious)
+        );
+        assert_eq!(
+            policy.score(&[(EvidenceKind::RootPackage, 2)]),
+            (35, IntegrityVerdict::Suspicious)
+        );
+    }
+
+    #[test]
+    fn test_per_item_caps_and_hard_fail() {
+        let policy = RiskPolicy::from_json(
+            r#"{"id":"strict","version":3,"max_score":90,
+                "thresholds":{"suspicious":10,"compromised":70},
+                "rules":{"tamper_prop":{"weight":10,"per_item":5,"cap":25},"magisk":{"weight":5,"cap":5}},
+                "hard_fail":["suspicious_file"]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            policy.score(&[(EvidenceKind::TamperProp, 2)]),
+            (15, IntegrityVerdict::Suspicious)
+        );
+        assert_eq!(
+            policy.score(&[(EvidenceKind::TamperProp, 9)]),
+            (25, IntegrityVerdict::Suspicious)
+        );
+        assert_eq!(
+            policy.score(&[(EvidenceKind::Magisk, 1)]),
+            (5, IntegrityVerdict::Genuine)
+        );
+        // No rule → no score, but the hard-fail still applies
+        assert_eq!(
+            policy.score(&[(EvidenceKind::SuspiciousFile, 1)]),
+            (0, IntegrityVerdict::Compromised)
+        );
+        assert_eq!(
+            policy.score(&[(EvidenceKind::PlayTokenMissing, 1)]),
+            (0, IntegrityVerdict::Genuine)
+        );
+    }
+
+    #[test]
+    fn test_kind_names_match_policy_keys() {
+        for kind in ALL_KINDS {
+            let json = alloc::format!("\"{}\"", kind.name());
+            assert_eq!(serde_json::from_str::<EvidenceKind>(&json).unwrap(), kind);
+        }
+    }
+
+    #[test]
+    fn test_invalid_policies_rejected() {
+        for json in [
+            "not json",
+            r#"{"id":"x","version":1,"max_score":100,"thresholds":{"suspicious":1,"compromised":50},"rules":{}"#,
+            r#"{"id":"","version":1,"max_score":100,"thresholds":{"suspicious":1,"compromised":50},"rules":{}}"#,
+            r#"{"id":"x","version":1,"max_score":101,"thresholds":{"suspicious":1,"compromised":50},"rules":{}}"#,
+            r#"{"id":"x","version":1,"max_score":100,"thresholds":{"suspicious":0,"compromised":50},"rules":{}}"#,
+            r#"{"id":"x","version":1,"max_score":100,"thresholds":{"suspicious":60,"compromised":50},"rules":{}}"#,
+            r#"{"id":"x","version":1,"max_score":40,"thresholds":{"suspicious":1,"compromised":50},"rules":{}}"#,
+            r#"{"id":"x","version":1,"max_score":100,"thresholds":{"suspicious":1,"compromised":50},"rules":{"magisk":{"weight":30,"cap":20}}}"#,
+            r#"{"id":"x","version":1,"max_score":100,"thresholds":{"suspicious":1,"compromised":50},"rules":{"xposed":{"weight":1,"cap":1}}}"#,
+            r#"{"id":"x","version":1,"max_score":100,"thresholds":{"suspicious":1,"compromised":50},"rules":{},"weights":{}}"#,
+        ] {
+            assert_eq!(
+                RiskPolicy::from_json(json),
+                Err(MercyError::InvalidPolicy),
+                "{}",
+                json
+            );
+        }
+    }
+}
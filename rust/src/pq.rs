@@ -0,0 +1,249 @@
+// rust/src/pq.rs — ML-KEM-768 / ML-DSA-65 Primitives Eternal (raw encodings, hedged signing)
+//
+// The only module that talks to the ml-kem / ml-dsa APIs. Everywhere else keys, ciphertexts and
+// signatures are plain FIPS 203 / FIPS 204 byte strings:
+//
+//   ML-KEM-768   ek 1184   dk 2400 (expanded)   ciphertext 1088   shared secret 32
+//   ML-DSA-65    pk 1952   sk 32 (seed ξ)       signature 3309
+//
+// All randomness comes from the caller's `CryptoRngCore` (see `rng.rs`): ML-DSA key generation
+// draws ξ and hedged signing draws the 32-byte `rnd` of ML-DSA.Sign_internal from it, so a seeded
+// RNG pins keys, ciphertexts and signatures alike.
+use alloc::vec::Vec;
+use ml_dsa::{Keypair, MlDsa65, B32};
+use ml_kem::kem::{Decapsulate, DecapsulationKey, Encapsulate, EncapsulationKey};
+use ml_kem::{Encoded, EncodedSizeUser, KemCore, MlKem768, MlKem768Params};
+use rand_core::CryptoRngCore;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::error::{MercyError, MercyResult};
+
+pub const KEM_PK_LEN: usize = 1184;
+pub const KEM_SK_LEN: usize = 2400;
+pub const KEM_CT_LEN: usize = 1088;
+pub const SHARED_SECRET_LEN: usize = 32;
+pub const DSA_PK_LEN: usize = 1952;
+pub const DSA_SK_LEN: usize = 32;
+pub const DSA_SIG_LEN: usize = 3309;
+
+pub type MlKem768SecretKey = DecapsulationKey<MlKem768Params>;
+pub type MlKem768PublicKey = EncapsulationKey<MlKem768Params>;
+pub type MlKem768Ciphertext = ml_kem::Ciphertext<MlKem768>;
+pub type DsaSigningKey = ml_dsa::SigningKey<MlDsa65>;
+pub type DsaVerifyingKey = ml_dsa::VerifyingKey<MlDsa65>;
+pub type DsaSignature = ml_dsa::Signature<MlDsa65>;
+
+/// Fresh ML-KEM-768 (decapsulation, encapsulation) key pair
+pub fn kem_generate(rng: &mut impl CryptoRngCore) -> (MlKem768SecretKey, MlKem768PublicKey) {
+    MlKem768::generate(rng)
+}
+
+pub fn kem_public_key(bytes: &[u8]) -> MercyResult<MlKem768PublicKey> {
+    let encoded =
+        Encoded::<MlKem768PublicKey>::try_from(bytes).map_err(|_| MercyError::InvalidKey)?;
+    Ok(MlKem768PublicKey::from_bytes(&encoded))
+}
+
+pub fn kem_secret_key(bytes: &[u8]) -> MercyResult<MlKem768SecretKey> {
+    let mut encoded =
+        Encoded::<MlKem768SecretKey>::try_from(bytes).map_err(|_| MercyError::InvalidKey)?;
+    let sk = MlKem768SecretKey::from_bytes(&encoded);
+    encoded.as_mut_slice().zeroize();
+    Ok(sk)
+}
+
+pub fn kem_public_key_bytes(pk: &MlKem768PublicKey) -> Vec<u8> {
+    pk.as_bytes().to_vec()
+}
+
+pub fn kem_secret_key_bytes(sk: &MlKem768SecretKey) -> Zeroizing<Vec<u8>> {
+    let mut encoded = sk.as_bytes();
+    let bytes = Zeroizing::new(encoded.to_vec());
+    encoded.as_mut_slice().zeroize();
+    bytes
+}
+
+/// Encapsulate to `pk` — returns (ciphertext bytes, shared secret)
+pub fn kem_encapsulate(
+    pk: &MlKem768PublicKey,
+    rng: &mut impl CryptoRngCore,
+) -> MercyResult<(Vec<u8>, Zeroizing<[u8; SHARED_SECRET_LEN]>)> {
+    let (ciphertext, mut shared) = pk.encapsulate(rng).map_err(|_| MercyError::CryptoFailure)?;
+    let secret = Zeroizing::new(shared.0);
+    shared.as_mut_slice().zeroize();
+    Ok((ciphertext.to_vec(), secret))
+}
+
+/// Decapsulate raw ciphertext bytes — a wrong-length ciphertext is `InvalidBlob`
+pub fn kem_decapsulate(
+    sk: &MlKem768SecretKey,
+    ciphertext: &[u8],
+) -> MercyResult<Zeroizing<[u8; SHARED_SECRET_LEN]>> {
+    let ciphertext =
+        MlKem768Ciphertext::try_from(ciphertext).map_err(|_| MercyError::InvalidBlob)?;
+    let mut shared = sk
+        .decapsulate(&ciphertext)
+        .map_err(|_| MercyError::CryptoFailure)?;
+    let secret = Zeroizing::new(shared.0);
+    shared.as_mut_slice().zeroize();
+    Ok(secret)
+}
+
+/// Fresh ML-DSA-65 signing key from a 32-byte seed drawn from `rng`
+pub fn dsa_generate(rng: &mut impl CryptoRngCore) -> DsaSigningKey {
+    let mut seed = B32::default();
+    rng.fill_bytes(&mut seed);
+    let sk = DsaSigningKey::from_seed(&seed);
+    seed.as_mut_slice().zeroize();
+    sk
+}
+
+/// Signing key from its 32-byte seed encoding
+pub fn dsa_signing_key(bytes: &[u8]) -> MercyResult<DsaSigningKey> {
+    let mut seed = B32::try_from(bytes).map_err(|_| MercyError::InvalidKey)?;
+    let sk = DsaSigningKey::from_seed(&seed);
+    seed.as_mut_slice().zeroize();
+    Ok(sk)
+}
+
+pub fn dsa_verifying_key(bytes: &[u8]) -> MercyResult<DsaVerifyingKey> {
+    let encoded = ml_dsa::EncodedVerifyingKey::<MlDsa65>::try_from(bytes)
+        .map_err(|_| MercyError::InvalidKey)?;
+    Ok(DsaVerifyingKey::decode(&encoded))
+}
+
+pub fn dsa_signature(bytes: &[u8]) -> MercyResult<DsaSignature> {
+    let encoded = ml_dsa::EncodedSignature::<MlDsa65>::try_from(bytes)
+        .map_err(|_| MercyError::InvalidSignature)?;
+    DsaSignature::decode(&encoded).ok_or(MercyError::InvalidSignature)
+}
+
+/// Seed encoding of a signing key
+pub fn dsa_secret_key_bytes(sk: &DsaSigningKey) -> Zeroizing<Vec<u8>> {
+    Zeroizing::new(sk.as_seed().to_vec())
+}
+
+pub fn dsa_public_key_bytes(sk: &DsaSigningKey) -> Vec<u8> {
+    sk.verifying_key().encode().to_vec()
+}
+
+/// Hedged ML-DSA.Sign with a FIPS 204 context string — returns raw signature bytes
+pub fn dsa_sign(
+    sk: &DsaSigningKey,
+    message: &[u8],
+    ctx: &[u8],
+    rng: &mut impl CryptoRngCore,
+) -> MercyResult<Vec<u8>> {
+    let ctx_len = u8::try_from(ctx.len()).map_err(|_| MercyError::CryptoFailure)?;
+    Ok(dsa_sign_internal(
+        sk,
+        &[&[0x00, ctx_len], ctx, message],
+        rng,
+    ))
+}
+
+/// ML-DSA.Sign_internal over M' (given in pieces) with `rnd` drawn from `rng`
+pub fn dsa_sign_internal(
+    sk: &DsaSigningKey,
+    m_prime: &[&[u8]],
+    rng: &mut impl CryptoRngCore,
+) -> Vec<u8> {
+    let mut rnd = Zeroizing::new(B32::default());
+    rng.fill_bytes(&mut rnd);
+    sk.expanded_key()
+        .sign_internal(m_prime, &rnd)
+        .encode()
+        .to_vec()
+}
+
+/// ML-DSA.Verify with a FIPS 204 context string — an unparsable signature is `InvalidSignature`,
+/// a well-formed but wrong one is `Ok(false)`
+pub fn dsa_verify(
+    pk: &DsaVerifyingKey,
+    message: &[u8],
+    ctx: &[u8],
+    signature: &[u8],
+) -> MercyResult<bool> {
+    let signature = dsa_signature(signature)?;
+    Ok(pk.verify_with_context(message, ctx, &signature))
+}
+
+/// ML-DSA.Verify_internal over a complete M'
+pub fn dsa_verify_internal(
+    pk: &DsaVerifyingKey,
+    m_prime: &[u8],
+    signature: &[u8],
+) -> MercyResult<bool> {
+    let signature = dsa_signature(signature)?;
+    Ok(pk.verify_internal(m_prime, &signature))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::SeededRng;
+
+    #[test]
+    fn test_encoding_lengths() {
+        let mut rng = SeededRng::from_seed(b"pq-lengths");
+        let (kem_sk, kem_pk) = kem_generate(&mut rng);
+        assert_eq!(kem_public_key_bytes(&kem_pk).len(), KEM_PK_LEN);
+        assert_eq!(kem_secret_key_bytes(&kem_sk).len(), KEM_SK_LEN);
+        let (ct, _) = kem_encapsulate(&kem_pk, &mut rng).unwrap();
+        assert_eq!(ct.len(), KEM_CT_LEN);
+
+        let dsa_sk = dsa_generate(&mut rng);
+        assert_eq!(dsa_secret_key_bytes(&dsa_sk).len(), DSA_SK_LEN);
+        assert_eq!(dsa_public_key_bytes(&dsa_sk).len(), DSA_PK_LEN);
+        assert_eq!(
+            dsa_sign(&dsa_sk, b"m", b"ctx", &mut rng).unwrap().len(),
+            DSA_SIG_LEN
+        );
+    }
+
+    #[test]
+    fn test_kem_roundtrip_through_bytes() {
+        let mut rng = SeededRng::from_seed(b"pq-kem");
+        let (sk, pk) = kem_generate(&mut rng);
+        let sk = kem_secret_key(&kem_secret_key_bytes(&sk)).unwrap();
+        let pk = kem_public_key(&kem_public_key_bytes(&pk)).unwrap();
+
+        let (ct, sent) = kem_encapsulate(&pk, &mut rng).unwrap();
+        assert_eq!(kem_decapsulate(&sk, &ct).unwrap(), sent);
+        assert_eq!(kem_decapsulate(&sk, &ct[1..]), Err(MercyError::InvalidBlob));
+        assert_eq!(
+            kem_public_key(&[0u8; 10]).err(),
+            Some(MercyError::InvalidKey)
+        );
+        assert_eq!(
+            kem_secret_key(&[0u8; KEM_PK_LEN]).err(),
+            Some(MercyError::InvalidKey)
+        );
+    }
+
+    #[test]
+    fn test_dsa_seed_roundtrip_and_context() {
+        let mut rng = SeededRng::from_seed(b"pq-dsa");
+        let sk = dsa_generate(&mut rng);
+        let restored = dsa_signing_key(&dsa_secret_key_bytes(&sk)).unwrap();
+        assert_eq!(dsa_public_key_bytes(&restored), dsa_public_key_bytes(&sk));
+
+        let pk = dsa_verifying_key(&dsa_public_key_bytes(&sk)).unwrap();
+        let sig = dsa_sign(&restored, b"report", b"ctx-a", &mut rng).unwrap();
+        assert_eq!(dsa_verify(&pk, b"report", b"ctx-a", &sig), Ok(true));
+        assert_eq!(dsa_verify(&pk, b"report", b"ctx-b", &sig), Ok(false));
+        assert_eq!(
+            dsa_verify(&pk, b"report", b"ctx-a", &sig[1..]),
+            Err(MercyError::InvalidSignature)
+        );
+
+        // Pure ML-DSA is Sign_internal over 0x00 || len(ctx) || ctx || M
+        let m_prime = [&[0x00, 5][..], b"ctx-a", b"report"].concat();
+        assert_eq!(dsa_verify_internal(&pk, &m_prime, &sig), Ok(true));
+        assert!(dsa_sign(&sk, b"m", &[0u8; 256], &mut rng).is_err());
+        assert_eq!(
+            dsa_signing_key(&[0u8; 31]).err(),
+            Some(MercyError::InvalidKey)
+        );
+    }
+}
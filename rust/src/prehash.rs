@@ -0,0 +1,350 @@
+// rust/src/prehash.rs — HashML-DSA Pre-Hash Signing Eternal (FIPS 204 §5.4, streaming log/ledger exports)
+//
+//   M' = 0x01 || len(ctx) || ctx || DER(OID(PH)) || PH(M)
+//   signature = ML-DSA.Sign_internal(sk, M', rnd)
+//
+// PH is SHA-512 or SHAKE256 (512-bit output). The hasher is fed incrementally, so a whole log
+// bundle never has to sit in memory just to be signed or verified.
+use alloc::vec::Vec;
+use rand_core::{CryptoRngCore, OsRng};
+use sha2::{Digest, Sha512};
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::Shake256;
+
+use crate::context::MessageKind;
+use crate::error::MercyResult;
+use crate::pq;
+
+pub const PREHASH_LEN: usize = 64;
+
+/// DER OID for id-sha512 — 2.16.840.1.101.3.4.2.3
+const OID_SHA512: &[u8] = &[
+    0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03,
+];
+/// DER OID for id-shake256 — 2.16.840.1.101.3.4.2.12
+const OID_SHAKE256: &[u8] = &[
+    0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x0C,
+];
+
+/// Pre-hash function for HashML-DSA
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+pub enum PrehashAlgorithm {
+    Sha512,
+    Shake256,
+}
+
+impl PrehashAlgorithm {
+    fn oid(self) -> &'static [u8] {
+        match self {
+            PrehashAlgorithm::Sha512 => OID_SHA512,
+            PrehashAlgorithm::Shake256 => OID_SHAKE256,
+        }
+    }
+}
+
+enum HashState {
+    Sha512(Sha512),
+    Shake256(Shake256),
+}
+
+/// Incremental PH(M) — feed chunks with `update`, then `finalize` once
+pub struct PrehashHasher {
+    alg: PrehashAlgorithm,
+    state: HashState,
+}
+
+impl PrehashHasher {
+    pub fn new(alg: PrehashAlgorithm) -> Self {
+        let state = match alg {
+            PrehashAlgorithm::Sha512 => HashState::Sha512(Sha512::new()),
+            PrehashAlgorithm::Shake256 => HashState::Shake256(Shake256::default()),
+        };
+        PrehashHasher { alg, state }
+    }
+
+    pub fn algorithm(&self) -> PrehashAlgorithm {
+        self.alg
+    }
+
+    pub fn update(&mut self, chunk: &[u8]) {
+        match &mut self.state {
+            HashState::Sha512(h) => Digest::update(h, chunk),
+            HashState::Shake256(h) => Update::update(h, chunk),
+        }
+    }
+
+    pub fn finalize(self) -> [u8; PREHASH_LEN] {
+        let mut out = [0u8; PREHASH_LEN];
+        match self.state {
+            HashState::Sha512(h) => out.copy_from_slice(&h.finalize()),
+            HashState::Shake256(h) => h.finalize_xof().read(&mut out),
+        }
+        out
+    }
+}
+
+/// Build the FIPS 204 HashML-DSA message representative M'
+pub fn prehash_message(
+    kind: MessageKind,
+    alg: PrehashAlgorithm,
+    digest: &[u8; PREHASH_LEN],
+) -> Vec<u8> {
+    let ctx = kind.context();
+    let mut m_prime = Vec::with_capacity(2 + ctx.len() + alg.oid().len() + PREHASH_LEN);
+    m_prime.push(0x01); // pre-hash domain separator (pure ML-DSA uses 0x00)
+    m_prime.push(ctx.len() as u8);
+    m_prime.extend_from_slice(ctx);
+    m_prime.extend_from_slice(alg.oid());
+    m_prime.extend_from_slice(digest);
+    m_prime
+}
+
+/// HashML-DSA-65 sign over a finished pre-hash digest — returns raw signature bytes
+pub fn sign_prehashed(
+    dsa_sk_bytes: &[u8],
+    kind: MessageKind,
+    alg: PrehashAlgorithm,
+    digest: &[u8; PREHASH_LEN],
+) -> MercyResult<Vec<u8>> {
+    sign_prehashed_with_rng(dsa_sk_bytes, kind, alg, digest, &mut OsRng)
+}
+
+/// `sign_prehashed` with an injected RNG (hedged signing randomness)
+pub fn sign_prehashed_with_rng(
+    dsa_sk_bytes: &[u8],
+    kind: MessageKind,
+    alg: PrehashAlgorithm,
+    digest: &[u8; PREHASH_LEN],
+    rng: &mut impl CryptoRngCore,
+) -> MercyResult<Vec<u8>> {
+    let dsa_sk = pq::dsa_signing_key(dsa_sk_bytes)?;
+    Ok(sign_prehashed_with_key(&dsa_sk, kind, alg, digest, rng))
+}
+
+fn sign_prehashed_with_key(
+    dsa_sk: &pq::DsaSigningKey,
+    kind: MessageKind,
+    alg: PrehashAlgorithm,
+    digest: &[u8; PREHASH_LEN],
+    rng: &mut impl CryptoRngCore,
+) -> Vec<u8> {
+    pq::dsa_sign_internal(dsa_sk, &[&prehash_message(kind, alg, digest)], rng)
+}
+
+/// HashML-DSA-65 verify over a finished pre-hash digest
+pub fn verify_prehashed(
+    dsa_pk_bytes: &[u8],
+    kind: MessageKind,
+    alg: PrehashAlgorithm,
+    digest: &[u8; PREHASH_LEN],
+    signature_bytes: &[u8],
+) -> MercyResult<bool> {
+    let dsa_pk = pq::dsa_verifying_key(dsa_pk_bytes)?;
+    let signature = pq::dsa_signature(signature_bytes)?;
+    Ok(verify_prehashed_with_key(
+        &dsa_pk, kind, alg, digest, &signature,
+    ))
+}
+
+fn verify_prehashed_with_key(
+    dsa_pk: &pq::DsaVerifyingKey,
+    kind: MessageKind,
+    alg: PrehashAlgorithm,
+    digest: &[u8; PREHASH_LEN],
+    signature: &pq::DsaSignature,
+) -> bool {
+    dsa_pk.verify_internal(&prehash_message(kind, alg, digest), signature)
+}
+
+#[cfg(feature = "uniffi")]
+pub use self::ffi::PqPrehashState;
+
+/// uniffi object so Kotlin can stream a file export through the hasher chunk by chunk
+#[cfg(feature = "uniffi")]
+mod ffi {
+    use super::*;
+    use alloc::string::String;
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+    use std::sync::{Arc, Mutex};
+
+    use crate::error::MercyError;
+    use crate::secret::SecretBuffer;
+
+    #[derive(uniffi::Object)]
+    pub struct PqPrehashState {
+        hasher: Mutex<Option<PrehashHasher>>,
+    }
+
+    impl PqPrehashState {
+        fn take_digest(&self) -> MercyResult<(PrehashAlgorithm, [u8; PREHASH_LEN])> {
+            let hasher = self
+                .hasher
+                .lock()
+                .map_err(|_| MercyError::InvalidState)?
+                .take();
+            let hasher = hasher.ok_or(MercyError::InvalidState)?;
+            Ok((hasher.algorithm(), hasher.finalize()))
+        }
+    }
+
+    #[uniffi::export]
+    impl PqPrehashState {
+        #[uniffi::constructor]
+        pub fn new(alg: PrehashAlgorithm) -> Arc<Self> {
+            Arc::new(PqPrehashState {
+                hasher: Mutex::new(Some(PrehashHasher::new(alg))),
+            })
+        }
+
+        /// Absorb the next chunk of the file/stream
+        pub fn update(&self, chunk: Vec<u8>) -> MercyResult<()> {
+            let mut guard = self.hasher.lock().map_err(|_| MercyError::InvalidState)?;
+            guard
+                .as_mut()
+                .ok_or(MercyError::InvalidState)?
+                .update(&chunk);
+            Ok(())
+        }
+
+        /// Finalize and sign (base64 signature) — the state cannot be reused afterwards
+        /// The key is parsed first, so a malformed key leaves the hasher untouched
+        pub fn sign(&self, dsa_sk_b64: String, kind: MessageKind) -> MercyResult<String> {
+            let dsa_sk = pq::dsa_signing_key(&SecretBuffer::from_base64(dsa_sk_b64)?)?;
+
+            let (alg, digest) = self.take_digest()?;
+            Ok(BASE64.encode(sign_prehashed_with_key(
+                &dsa_sk, kind, alg, &digest, &mut OsRng,
+            )))
+        }
+
+        /// Finalize and verify a base64 signature — the state cannot be reused afterwards
+        pub fn verify(
+            &self,
+            dsa_pk_b64: String,
+            signature_b64: String,
+            kind: MessageKind,
+        ) -> MercyResult<bool> {
+            let dsa_pk_bytes = BASE64
+                .decode(dsa_pk_b64)
+                .map_err(|_| MercyError::InvalidBase64)?;
+            let dsa_pk = pq::dsa_verifying_key(&dsa_pk_bytes)?;
+            let sig_bytes = BASE64
+                .decode(signature_b64)
+                .map_err(|_| MercyError::InvalidBase64)?;
+            let signature = pq::dsa_signature(&sig_bytes)?;
+
+            let (alg, digest) = self.take_digest()?;
+            Ok(verify_prehashed_with_key(
+                &dsa_pk, kind, alg, &digest, &signature,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest_of(alg: PrehashAlgorithm, chunks: &[&[u8]]) -> [u8; PREHASH_LEN] {
+        let mut h = PrehashHasher::new(alg);
+        for c in chunks {
+            h.update(c);
+        }
+        h.finalize()
+    }
+
+    #[test]
+    fn test_incremental_matches_one_shot() {
+        for alg in [PrehashAlgorithm::Sha512, PrehashAlgorithm::Shake256] {
+            let whole = digest_of(alg, &[b"ledger-entry-1|ledger-entry-2|ledger-entry-3"]);
+            let chunked = digest_of(
+                alg,
+                &[b"ledger-entry-1|", b"ledger-", b"entry-2|ledger-entry-3"],
+            );
+            assert_eq!(whole, chunked, "{:?}", alg);
+        }
+    }
+
+    #[test]
+    fn test_known_digests() {
+        // SHA-512("abc") and SHAKE256("abc", 512 bits) — FIPS 180-4 / FIPS 202 examples
+        let sha = digest_of(PrehashAlgorithm::Sha512, &[b"abc"]);
+        assert_eq!(&sha[..8], &[0xdd, 0xaf, 0x35, 0xa1, 0x93, 0x61, 0x7a, 0xba]);
+        let shake = digest_of(PrehashAlgorithm::Shake256, &[b"abc"]);
+        assert_eq!(
+            &shake[..8],
+            &[0x48, 0x33, 0x66, 0x60, 0x13, 0x60, 0xa8, 0x77]
+        );
+    }
+
+    #[test]
+    fn test_prehash_message_layout() {
+        let digest = [0xEE; PREHASH_LEN];
+        let kind = MessageKind::LogExport;
+        let m = prehash_message(kind, PrehashAlgorithm::Sha512, &digest);
+        let ctx = kind.context();
+
+        assert_eq!(m[0], 0x01);
+        assert_eq!(m[1] as usize, ctx.len());
+        assert_eq!(&m[2..2 + ctx.len()], ctx);
+        assert_eq!(&m[2 + ctx.len()..2 + ctx.len() + 11], OID_SHA512);
+        assert_eq!(&m[m.len() - PREHASH_LEN..], &digest);
+
+        let shake = prehash_message(kind, PrehashAlgorithm::Shake256, &digest);
+        assert_ne!(m, shake, "OID must separate pre-hash functions");
+    }
+
+    #[cfg(feature = "uniffi")]
+    #[test]
+    fn test_bad_key_leaves_state_usable() {
+        use crate::error::MercyError;
+        use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+
+        let sk = pq::dsa_generate(&mut OsRng);
+        let signer = PqPrehashState::new(PrehashAlgorithm::Sha512);
+        signer.update(b"log bundle".to_vec()).unwrap();
+        assert_eq!(
+            signer.sign("%%".into(), MessageKind::LogExport),
+            Err(MercyError::InvalidBase64)
+        );
+        assert_eq!(
+            signer.sign(BASE64.encode([0u8; 7]), MessageKind::LogExport),
+            Err(MercyError::InvalidKey)
+        );
+
+        let sig_b64 = signer
+            .sign(
+                BASE64.encode(pq::dsa_secret_key_bytes(&sk)),
+                MessageKind::LogExport,
+            )
+            .unwrap();
+        let verifier = PqPrehashState::new(PrehashAlgorithm::Sha512);
+        verifier.update(b"log bundle".to_vec()).unwrap();
+        let pk_b64 = BASE64.encode(pq::dsa_public_key_bytes(&sk));
+        assert_eq!(
+            verifier.verify("%%".into(), sig_b64.clone(), MessageKind::LogExport),
+            Err(MercyError::InvalidBase64)
+        );
+        assert_eq!(
+            verifier.verify(
+                BASE64.encode([0u8; 7]),
+                sig_b64.clone(),
+                MessageKind::LogExport
+            ),
+            Err(MercyError::InvalidKey)
+        );
+        assert_eq!(
+            verifier.verify(
+                pk_b64.clone(),
+                BASE64.encode([0u8; 5]),
+                MessageKind::LogExport
+            ),
+            Err(MercyError::InvalidSignature)
+        );
+        assert_eq!(
+            verifier.verify(pk_b64, sig_b64, MessageKind::LogExport),
+            Ok(true)
+        );
+    }
+}
@@ -0,0 +1,340 @@
+// rust/src/rotation.rs — Device Key Rotation Eternal (cross-signed RotationStatement, monotonic epochs)
+//
+// Statement layout (all integers big-endian):
+//   magic       4   b"MSPR"
+//   version     1   ROTATION_VERSION
+//   sig_alg     1   SignatureAlgorithm (ML-DSA-65 only)
+//   epoch       8   epoch of the NEW key (enrolled epoch + 1)
+//   old_pk    2+n   length-prefixed outgoing DSA public key
+//   new_pk    2+n   length-prefixed incoming DSA public key
+//   ------------- everything above is signed by both keys (KeyRotation ctx) -------------
+//   old_sig   2+n   signature by the outgoing key
+//   new_sig   2+n   signature by the incoming key (proof of possession)
+//
+// The verifier only accepts a statement whose old_pk is the currently enrolled key and whose epoch
+// is exactly one past the enrolled epoch, so a retired key can never sign its way back in.
+use alloc::string::String;
+use alloc::vec::Vec;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use rand_core::{CryptoRngCore, OsRng};
+use zeroize::Zeroizing;
+
+use crate::blob::{Reader, SignatureAlgorithm};
+use crate::context::MessageKind;
+use crate::error::{MercyError, MercyResult};
+use crate::pq;
+use crate::secret::SecretBuffer;
+
+pub const ROTATION_MAGIC: [u8; 4] = *b"MSPR";
+pub const ROTATION_VERSION: u8 = 1;
+
+/// Old key hands trust to the new key at `epoch`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RotationStatement {
+    pub epoch: u64,
+    pub old_pk: Vec<u8>,
+    pub new_pk: Vec<u8>,
+    pub old_signature: Vec<u8>,
+    pub new_signature: Vec<u8>,
+}
+
+fn put_prefixed(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+    out.extend_from_slice(bytes);
+}
+
+fn take_prefixed(r: &mut Reader<'_>) -> MercyResult<Vec<u8>> {
+    let len = r.u16()? as usize;
+    Ok(r.take(len)?.to_vec())
+}
+
+impl RotationStatement {
+    /// Bytes covered by both signatures
+    pub fn signed_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(14 + 4 + self.old_pk.len() + self.new_pk.len());
+        out.extend_from_slice(&ROTATION_MAGIC);
+        out.push(ROTATION_VERSION);
+        out.push(SignatureAlgorithm::MlDsa65 as u8);
+        out.extend_from_slice(&self.epoch.to_be_bytes());
+        put_prefixed(&mut out, &self.old_pk);
+        put_prefixed(&mut out, &self.new_pk);
+        out
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = self.signed_bytes();
+        put_prefixed(&mut out, &self.old_signature);
+        put_prefixed(&mut out, &self.new_signature);
+        out
+    }
+
+    pub fn parse(bytes: &[u8]) -> MercyResult<Self> {
+        let mut r = Reader::new(bytes);
+        if r.take(4)? != ROTATION_MAGIC {
+            return Err(MercyError::InvalidBlob);
+        }
+        if r.u8()? != ROTATION_VERSION {
+            return Err(MercyError::UnsupportedVersion);
+        }
+        if SignatureAlgorithm::try_from(r.u8()?)? != SignatureAlgorithm::MlDsa65 {
+            return Err(MercyError::UnsupportedAlgorithm);
+        }
+        let statement = RotationStatement {
+            epoch: r.u64()?,
+            old_pk: take_prefixed(&mut r)?,
+            new_pk: take_prefixed(&mut r)?,
+            old_signature: take_prefixed(&mut r)?,
+            new_signature: take_prefixed(&mut r)?,
+        };
+        if !r.is_empty() {
+            return Err(MercyError::InvalidBlob);
+        }
+        Ok(statement)
+    }
+}
+
+fn verify_rotation_signature(pk_bytes: &[u8], tbs: &[u8], sig_bytes: &[u8]) -> MercyResult<bool> {
+    let dsa_pk = pq::dsa_verifying_key(pk_bytes)?;
+    pq::dsa_verify(&dsa_pk, tbs, MessageKind::KeyRotation.context(), sig_bytes)
+}
+
+/// Generate the successor key for `current_epoch` — returns (new DSA SK bytes, cross-signed statement)
+pub fn rotate_device_key(
+    old_dsa_sk_bytes: &[u8],
+    current_epoch: u64,
+) -> MercyResult<(Zeroizing<Vec<u8>>, RotationStatement)> {
+    rotate_device_key_with_rng(old_dsa_sk_bytes, current_epoch, &mut OsRng)
+}
+
+/// `rotate_device_key` with an injected RNG (new key, then old and new signatures)
+pub fn rotate_device_key_with_rng(
+    old_dsa_sk_bytes: &[u8],
+    current_epoch: u64,
+    rng: &mut impl CryptoRngCore,
+) -> MercyResult<(Zeroizing<Vec<u8>>, RotationStatement)> {
+    let old_sk = pq::dsa_signing_key(old_dsa_sk_bytes)?;
+    let epoch = current_epoch.checked_add(1).ok_or(MercyError::StaleEpoch)?;
+
+    let new_sk = pq::dsa_generate(rng);
+    let mut statement = RotationStatement {
+        epoch,
+        old_pk: pq::dsa_public_key_bytes(&old_sk),
+        new_pk: pq::dsa_public_key_bytes(&new_sk),
+        old_signature: Vec::new(),
+        new_signature: Vec::new(),
+    };
+    let tbs = statement.signed_bytes();
+    let ctx = MessageKind::KeyRotation.context();
+    statement.old_signature = pq::dsa_sign(&old_sk, &tbs, ctx, rng)?;
+    statement.new_signature = pq::dsa_sign(&new_sk, &tbs, ctx, rng)?;
+
+    Ok((pq::dsa_secret_key_bytes(&new_sk), statement))
+}
+
+/// Accept a rotation away from the enrolled key — on success the caller enrolls
+/// `statement.new_pk` at `statement.epoch`
+pub fn verify_rotation(
+    statement: &RotationStatement,
+    enrolled_pk: &[u8],
+    enrolled_epoch: u64,
+) -> MercyResult<()> {
+    if statement.epoch <= enrolled_epoch {
+        return Err(MercyError::StaleEpoch);
+    }
+    if statement.epoch != enrolled_epoch + 1
+        || statement.old_pk != enrolled_pk
+        || statement.new_pk == enrolled_pk
+    {
+        return Err(MercyError::RotationRejected);
+    }
+
+    let tbs = statement.signed_bytes();
+    if !verify_rotation_signature(&statement.old_pk, &tbs, &statement.old_signature)?
+        || !verify_rotation_signature(&statement.new_pk, &tbs, &statement.new_signature)?
+    {
+        return Err(MercyError::RotationRejected);
+    }
+    Ok(())
+}
+
+/// Result of an on-device rotation — persist the new SK, upload the statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct RotatedKey {
+    pub new_dsa_sk_b64: String,
+    pub new_dsa_pk_b64: String,
+    pub epoch: u64,
+    pub statement_b64: String,
+}
+
+/// Rotate the device ML-DSA key (old DSA SK base64 + its epoch)
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn pq_rotate_device_key(old_dsa_sk_b64: String, current_epoch: u64) -> MercyResult<RotatedKey> {
+    let old_sk_bytes = SecretBuffer::from_base64(old_dsa_sk_b64)?;
+    let (new_sk_bytes, statement) = rotate_device_key(&old_sk_bytes, current_epoch)?;
+
+    Ok(RotatedKey {
+        new_dsa_sk_b64: BASE64.encode(&*new_sk_bytes),
+        new_dsa_pk_b64: BASE64.encode(&statement.new_pk),
+        epoch: statement.epoch,
+        statement_b64: BASE64.encode(statement.encode()),
+    })
+}
+
+/// Check a rotation statement against the enrolled key — returns the new DSA PK base64
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+pub fn pq_verify_rotation(
+    statement_b64: String,
+    enrolled_dsa_pk_b64: String,
+    enrolled_epoch: u64,
+) -> MercyResult<String> {
+    let statement_bytes = BASE64
+        .decode(statement_b64)
+        .map_err(|_| MercyError::InvalidBase64)?;
+    let enrolled_pk = BASE64
+        .decode(enrolled_dsa_pk_b64)
+        .map_err(|_| MercyError::InvalidBase64)?;
+
+    let statement = RotationStatement::parse(&statement_bytes)?;
+    verify_rotation(&statement, &enrolled_pk, enrolled_epoch)?;
+    Ok(BASE64.encode(&statement.new_pk))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn enrolled_key() -> (Vec<u8>, Vec<u8>) {
+        let sk = pq::dsa_generate(&mut OsRng);
+        (
+            pq::dsa_secret_key_bytes(&sk).to_vec(),
+            pq::dsa_public_key_bytes(&sk),
+        )
+    }
+
+    #[test]
+    fn test_statement_encode_parse_roundtrip() {
+        let statement = RotationStatement {
+            epoch: 7,
+            old_pk: vec![1; 5],
+            new_pk: vec![2; 6],
+            old_signature: vec![3; 7],
+            new_signature: vec![4; 8],
+        };
+        let bytes = statement.encode();
+        assert_eq!(RotationStatement::parse(&bytes), Ok(statement));
+
+        assert_eq!(
+            RotationStatement::parse(&bytes[..bytes.len() - 1]),
+            Err(MercyError::InvalidBlob)
+        );
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(
+            RotationStatement::parse(&trailing),
+            Err(MercyError::InvalidBlob)
+        );
+        let mut version = bytes;
+        version[4] = 9;
+        assert_eq!(
+            RotationStatement::parse(&version),
+            Err(MercyError::UnsupportedVersion)
+        );
+    }
+
+    #[test]
+    fn test_rotation_accepted_and_chains() {
+        let (sk0, pk0) = enrolled_key();
+        let (sk1, statement1) = rotate_device_key(&sk0, 0).unwrap();
+        assert_eq!(statement1.epoch, 1);
+        let statement1 = RotationStatement::parse(&statement1.encode()).unwrap();
+        assert_eq!(verify_rotation(&statement1, &pk0, 0), Ok(()));
+
+        let (_, statement2) = rotate_device_key(&sk1, 1).unwrap();
+        assert_eq!(verify_rotation(&statement2, &statement1.new_pk, 1), Ok(()));
+    }
+
+    #[test]
+    fn test_replay_and_old_epoch_rejected() {
+        let (sk0, pk0) = enrolled_key();
+        let (sk1, statement1) = rotate_device_key(&sk0, 0).unwrap();
+        let (_, statement2) = rotate_device_key(&sk1, 1).unwrap();
+
+        // Replaying epoch 1 after the verifier moved to epoch 2
+        assert_eq!(
+            verify_rotation(&statement1, &pk0, 2),
+            Err(MercyError::StaleEpoch)
+        );
+        // Retired key signs a fresh rotation claiming an older epoch
+        let (_, rollback) = rotate_device_key(&sk0, 0).unwrap();
+        assert_eq!(
+            verify_rotation(&rollback, &statement2.new_pk, 2),
+            Err(MercyError::StaleEpoch)
+        );
+        // Retired key signs a rotation at the current epoch — it is no longer the enrolled key
+        let (_, hijack) = rotate_device_key(&sk0, 2).unwrap();
+        assert_eq!(
+            verify_rotation(&hijack, &statement2.new_pk, 2),
+            Err(MercyError::RotationRejected)
+        );
+    }
+
+    #[test]
+    fn test_tampered_statement_rejected() {
+        let (sk0, pk0) = enrolled_key();
+        let (_, statement) = rotate_device_key(&sk0, 0).unwrap();
+
+        let (_, attacker_pk) = enrolled_key();
+        let mut swapped = statement.clone();
+        swapped.new_pk = attacker_pk;
+        assert_eq!(
+            verify_rotation(&swapped, &pk0, 0),
+            Err(MercyError::RotationRejected)
+        );
+
+        let mut skipped = statement.clone();
+        skipped.epoch = 5;
+        assert_eq!(
+            verify_rotation(&skipped, &pk0, 0),
+            Err(MercyError::RotationRejected)
+        );
+
+        let mut one_sig = statement;
+        one_sig.new_signature = one_sig.old_signature.clone();
+        assert_eq!(
+            verify_rotation(&one_sig, &pk0, 0),
+            Err(MercyError::RotationRejected)
+        );
+    }
+
+    #[test]
+    fn test_rotation_signature_bound_to_key_rotation_context() {
+        let (sk0, pk0) = enrolled_key();
+        let (_, statement) = rotate_device_key(&sk0, 0).unwrap();
+
+        let sig_b64 = BASE64.encode(&statement.old_signature);
+        let as_report = crate::pq_verify_data(
+            BASE64.encode(&pk0),
+            statement.signed_bytes(),
+            sig_b64,
+            MessageKind::AttestationReport,
+        );
+        assert_eq!(as_report, Ok(false));
+    }
+
+    #[test]
+    fn test_ffi_rotation_roundtrip() {
+        let (sk0, pk0) = enrolled_key();
+        let rotated = pq_rotate_device_key(BASE64.encode(&sk0), 0).unwrap();
+        assert_eq!(rotated.epoch, 1);
+
+        let new_pk =
+            pq_verify_rotation(rotated.statement_b64.clone(), BASE64.encode(&pk0), 0).unwrap();
+        assert_eq!(new_pk, rotated.new_dsa_pk_b64);
+        assert_eq!(
+            pq_verify_rotation(rotated.statement_b64, BASE64.encode(&pk0), 1),
+            Err(MercyError::StaleEpoch)
+        );
+    }
+}
@@ -0,0 +1,294 @@
+// rust/src/secret.rs — Locked Secret Memory Eternal (key material never sits in plain heap Vecs)
+//
+// With `std` on unix every SecretBuffer gets its own mapping:
+//
+//   [ guard page | data pages (mlock'd, MADV_DONTDUMP) ... secret | guard page ]
+//
+// Guard pages are PROT_NONE and the secret is right-aligned against the trailing guard, so a
+// read or write past the end faults instead of leaking into a neighbouring allocation. mlock is
+// best effort (RLIMIT_MEMLOCK can be tiny) — `is_locked` reports whether it took. Everywhere else
+// (no_std / TEE, wasm) the buffer is a plain boxed slice that is still zeroized on drop.
+//
+// SecretBuffer is deliberately not Clone: one secret, one owner, one wipe.
+use alloc::string::String;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+use zeroize::Zeroize;
+
+use crate::error::{MercyError, MercyResult};
+
+/// Owned secret bytes in locked, guard-paged memory — zeroized on drop
+pub struct SecretBuffer {
+    region: imp::Region,
+    len: usize,
+}
+
+impl SecretBuffer {
+    /// Zero-filled secret of `len` bytes
+    pub fn new(len: usize) -> MercyResult<Self> {
+        Ok(SecretBuffer {
+            region: imp::Region::alloc(len)?,
+            len,
+        })
+    }
+
+    /// Copy `bytes` into locked memory — the caller still owns (and should wipe) the source
+    pub fn from_slice(bytes: &[u8]) -> MercyResult<Self> {
+        let mut secret = SecretBuffer::new(bytes.len())?;
+        secret.copy_from_slice(bytes);
+        Ok(secret)
+    }
+
+    /// Decode base64 straight into locked memory; the input string is wiped either way
+    pub fn from_base64(mut encoded: String) -> MercyResult<Self> {
+        // Allocate the exact decoded length so the secret still ends at the guard page
+        let decoded =
+            SecretBuffer::new(padded_decoded_len(encoded.as_bytes())).and_then(|mut secret| {
+                let written = BASE64
+                    .decode_slice(encoded.as_bytes(), &mut secret)
+                    .map_err(|_| MercyError::InvalidBase64)?;
+                if written != secret.len {
+                    return Err(MercyError::InvalidBase64);
+                }
+                Ok(secret)
+            });
+        encoded.zeroize();
+        decoded
+    }
+
+    /// Whether the data pages are pinned in RAM (never swapped)
+    pub fn is_locked(&self) -> bool {
+        self.region.is_locked()
+    }
+}
+
+/// Decoded length of padded base64 — malformed input is left for the decoder to reject
+fn padded_decoded_len(encoded: &[u8]) -> usize {
+    let padding = encoded
+        .iter()
+        .rev()
+        .take(2)
+        .take_while(|&&b| b == b'=')
+        .count();
+    (encoded.len() / 4 * 3).saturating_sub(padding)
+}
+
+impl Deref for SecretBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.region.as_slice()[..self.len]
+    }
+}
+
+impl DerefMut for SecretBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.region.as_mut_slice()[..self.len]
+    }
+}
+
+impl fmt::Debug for SecretBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretBuffer({} bytes, redacted)", self.len)
+    }
+}
+
+#[cfg(all(feature = "std", unix))]
+mod imp {
+    use core::{ptr, slice};
+    use zeroize::Zeroize;
+
+    use crate::error::{MercyError, MercyResult};
+
+    /// One private anonymous mapping: guard page, data pages, guard page
+    pub struct Region {
+        base: *mut u8,
+        page: usize,
+        data_pages: usize,
+        cap: usize,
+        locked: bool,
+    }
+
+    // The mapping is uniquely owned; shared access is read-only through `&self`
+    unsafe impl Send for Region {}
+    unsafe impl Sync for Region {}
+
+    pub fn page_size() -> usize {
+        // SAFETY: sysconf has no preconditions
+        match unsafe { libc::sysconf(libc::_SC_PAGESIZE) } {
+            n if n > 0 => n as usize,
+            _ => 4096,
+        }
+    }
+
+    impl Region {
+        pub fn alloc(cap: usize) -> MercyResult<Self> {
+            let page = page_size();
+            let data_pages = cap.max(1).div_ceil(page) * page;
+            let mapped_len = data_pages + 2 * page;
+
+            // SAFETY: fresh anonymous mapping; every pointer below stays inside it
+            unsafe {
+                let base = libc::mmap(
+                    ptr::null_mut(),
+                    mapped_len,
+                    libc::PROT_READ | libc::PROT_WRITE,
+                    libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                    -1,
+                    0,
+                );
+                if base == libc::MAP_FAILED {
+                    return Err(MercyError::SecureMemory);
+                }
+                let base = base as *mut u8;
+                let data = base.add(page);
+
+                if libc::mprotect(base as *mut _, page, libc::PROT_NONE) != 0
+                    || libc::mprotect(data.add(data_pages) as *mut _, page, libc::PROT_NONE) != 0
+                {
+                    libc::munmap(base as *mut _, mapped_len);
+                    return Err(MercyError::SecureMemory);
+                }
+                #[cfg(any(target_os = "linux", target_os = "android"))]
+                libc::madvise(data as *mut _, data_pages, libc::MADV_DONTDUMP);
+                let locked = libc::mlock(data as *const _, data_pages) == 0;
+
+                Ok(Region {
+                    base,
+                    page,
+                    data_pages,
+                    cap,
+                    locked,
+                })
+            }
+        }
+
+        fn data(&self) -> *mut u8 {
+            // SAFETY: right-aligned inside the data pages, ending at the trailing guard
+            unsafe { self.base.add(self.page + self.data_pages - self.cap) }
+        }
+
+        pub fn as_slice(&self) -> &[u8] {
+            // SAFETY: `cap` readable bytes inside the data pages, valid for the lifetime of self
+            unsafe { slice::from_raw_parts(self.data(), self.cap) }
+        }
+
+        pub fn as_mut_slice(&mut self) -> &mut [u8] {
+            // SAFETY: as above, and `&mut self` guarantees exclusive access
+            unsafe { slice::from_raw_parts_mut(self.data(), self.cap) }
+        }
+
+        pub fn is_locked(&self) -> bool {
+            self.locked
+        }
+    }
+
+    impl Drop for Region {
+        fn drop(&mut self) {
+            self.as_mut_slice().zeroize();
+            // SAFETY: unmapping exactly the region mapped in `alloc`
+            unsafe {
+                if self.locked {
+                    libc::munlock(self.base.add(self.page) as *const _, self.data_pages);
+                }
+                libc::munmap(self.base as *mut _, self.data_pages + 2 * self.page);
+            }
+        }
+    }
+}
+
+#[cfg(not(all(feature = "std", unix)))]
+mod imp {
+    use alloc::boxed::Box;
+    use alloc::vec;
+    use zeroize::Zeroize;
+
+    use crate::error::MercyResult;
+
+    /// Heap fallback for targets without mmap/mlock (bare metal, wasm)
+    pub struct Region {
+        bytes: Box<[u8]>,
+    }
+
+    impl Region {
+        pub fn alloc(cap: usize) -> MercyResult<Self> {
+            Ok(Region {
+                bytes: vec![0u8; cap].into_boxed_slice(),
+            })
+        }
+
+        pub fn as_slice(&self) -> &[u8] {
+            &self.bytes
+        }
+
+        pub fn as_mut_slice(&mut self) -> &mut [u8] {
+            &mut self.bytes
+        }
+
+        pub fn is_locked(&self) -> bool {
+            false
+        }
+    }
+
+    impl Drop for Region {
+        fn drop(&mut self) {
+            self.bytes.zeroize();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_from_slice_and_base64() {
+        let secret = SecretBuffer::from_slice(b"dsa secret key bytes").unwrap();
+        assert_eq!(&*secret, b"dsa secret key bytes");
+
+        let decoded = SecretBuffer::from_base64(BASE64.encode(b"dsa secret key bytes")).unwrap();
+        assert_eq!(&*decoded, &*secret);
+
+        assert_eq!(
+            SecretBuffer::from_base64("not base64!".to_string()).unwrap_err(),
+            MercyError::InvalidBase64
+        );
+        assert!(SecretBuffer::from_base64(String::new()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_debug_redacted() {
+        let secret = SecretBuffer::from_slice(&[0x42; 32]).unwrap();
+        assert_eq!(format!("{:?}", secret), "SecretBuffer(32 bytes, redacted)");
+    }
+
+    #[test]
+    fn test_write_through_deref_mut() {
+        let mut secret = SecretBuffer::new(4).unwrap();
+        assert_eq!(&*secret, &[0; 4]);
+        secret[1] = 7;
+        assert_eq!(&*secret, &[0, 7, 0, 0]);
+    }
+
+    #[cfg(all(feature = "std", unix))]
+    #[test]
+    fn test_secret_ends_at_guard_page() {
+        for len in [1, 32, 4032, 4096, 4100] {
+            let secret = SecretBuffer::new(len).unwrap();
+            let end = secret.as_ptr() as usize + secret.len();
+            assert_eq!(end % imp::page_size(), 0, "len {}", len);
+
+            let decoded = SecretBuffer::from_base64(BASE64.encode(alloc::vec![0x5A; len])).unwrap();
+            let end = decoded.as_ptr() as usize + decoded.len();
+            assert_eq!(
+                (decoded.len(), end % imp::page_size()),
+                (len, 0),
+                "base64 len {}",
+                len
+            );
+        }
+    }
+}
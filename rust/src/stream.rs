@@ -0,0 +1,610 @@
+// rust/src/stream.rs — Chunked STREAM AEAD Eternal (large reports, log bundles, ledger exports)
+//
+// Header (integers big-endian) — bound as associated data of every segment:
+//   magic          4   b"MSPS"
+//   version        1   0x01
+//   kem_alg        1   KemAlgorithm
+//   aead_alg       1   AeadAlgorithm
+//   key_id        16   SHA3-256(recipient KEM PK)[..16]
+//   kem_ct       2+n   length-prefixed KEM ciphertext
+//   nonce_prefix   7   random per stream
+//   segment_size   4   plaintext bytes per non-final segment
+// Segments: AEAD(segment), nonce = nonce_prefix || counter u32 || last_flag u8
+// (Hoang–Reyhanitabar–Rogaway–Vizár STREAM) — reordered, dropped or truncated segments fail.
+use aes_gcm::{AeadInPlace, Aes256Gcm, KeyInit, Nonce};
+use alloc::vec::Vec;
+use rand_core::{CryptoRngCore, OsRng};
+
+use crate::blob::{self, AeadAlgorithm, KemAlgorithm, Reader, KEY_ID_LEN};
+use crate::error::{MercyError, MercyResult};
+use crate::kdf::{self, KeyPurpose, KeySchedule, KEY_LEN};
+use crate::pq::{self, MlKem768SecretKey};
+
+pub const STREAM_MAGIC: [u8; 4] = *b"MSPS";
+pub const STREAM_VERSION: u8 = 1;
+pub const NONCE_PREFIX_LEN: usize = 7;
+pub const TAG_LEN: usize = 16;
+pub const DEFAULT_SEGMENT_SIZE: u32 = 64 * 1024;
+pub const MAX_SEGMENT_SIZE: u32 = 16 * 1024 * 1024;
+
+/// Cleartext stream header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamHeader {
+    pub kem_alg: KemAlgorithm,
+    pub aead_alg: AeadAlgorithm,
+    pub key_id: [u8; KEY_ID_LEN],
+    pub kem_ciphertext: Vec<u8>,
+    pub nonce_prefix: [u8; NONCE_PREFIX_LEN],
+    pub segment_size: u32,
+}
+
+impl StreamHeader {
+    pub fn encode(&self) -> MercyResult<Vec<u8>> {
+        let ct_len =
+            u16::try_from(self.kem_ciphertext.len()).map_err(|_| MercyError::InvalidBlob)?;
+
+        let mut out = Vec::with_capacity(
+            7 + KEY_ID_LEN + 2 + self.kem_ciphertext.len() + NONCE_PREFIX_LEN + 4,
+        );
+        out.extend_from_slice(&STREAM_MAGIC);
+        out.push(STREAM_VERSION);
+        out.push(self.kem_alg as u8);
+        out.push(self.aead_alg as u8);
+        out.extend_from_slice(&self.key_id);
+        out.extend_from_slice(&ct_len.to_be_bytes());
+        out.extend_from_slice(&self.kem_ciphertext);
+        out.extend_from_slice(&self.nonce_prefix);
+        out.extend_from_slice(&self.segment_size.to_be_bytes());
+        Ok(out)
+    }
+
+    /// Parse header from the front of `bytes` — returns header + header length
+    pub fn parse(bytes: &[u8]) -> MercyResult<(Self, usize)> {
+        let mut r = Reader::new(bytes);
+
+        if r.take(4)? != STREAM_MAGIC {
+            return Err(MercyError::InvalidBlob);
+        }
+        if r.u8()? != STREAM_VERSION {
+            return Err(MercyError::UnsupportedVersion);
+        }
+
+        let kem_alg = KemAlgorithm::try_from(r.u8()?)?;
+        let aead_alg = AeadAlgorithm::try_from(r.u8()?)?;
+
+        let mut key_id = [0u8; KEY_ID_LEN];
+        key_id.copy_from_slice(r.take(KEY_ID_LEN)?);
+
+        let ct_len = r.u16()? as usize;
+        let kem_ciphertext = r.take(ct_len)?.to_vec();
+
+        let mut nonce_prefix = [0u8; NONCE_PREFIX_LEN];
+        nonce_prefix.copy_from_slice(r.take(NONCE_PREFIX_LEN)?);
+
+        let segment_size = r.u32()?;
+        if segment_size == 0 || segment_size > MAX_SEGMENT_SIZE {
+            return Err(MercyError::InvalidBlob);
+        }
+
+        let header = StreamHeader {
+            kem_alg,
+            aead_alg,
+            key_id,
+            kem_ciphertext,
+            nonce_prefix,
+            segment_size,
+        };
+        Ok((header, r.position()))
+    }
+}
+
+fn segment_nonce(prefix: &[u8; NONCE_PREFIX_LEN], counter: u32, last: bool) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[..NONCE_PREFIX_LEN].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_LEN..11].copy_from_slice(&counter.to_be_bytes());
+    nonce[11] = last as u8;
+    nonce
+}
+
+/// Segment-at-a-time sealer — call `encrypt_next` for full segments, `encrypt_last` exactly once
+pub struct StreamEncryptor {
+    cipher: Aes256Gcm,
+    nonce_prefix: [u8; NONCE_PREFIX_LEN],
+    counter: u32,
+    aad: Vec<u8>,
+}
+
+impl StreamEncryptor {
+    pub fn new(key: &[u8; KEY_LEN], nonce_prefix: [u8; NONCE_PREFIX_LEN], aad: Vec<u8>) -> Self {
+        StreamEncryptor {
+            cipher: Aes256Gcm::new(key.into()),
+            nonce_prefix,
+            counter: 0,
+            aad,
+        }
+    }
+
+    /// Start a stream sealed to `recipient_kem_pk_bytes` — returns header bytes to emit first
+    pub fn seal_to(
+        recipient_kem_pk_bytes: &[u8],
+        segment_size: u32,
+    ) -> MercyResult<(Vec<u8>, Self)> {
+        Self::seal_to_with_rng(recipient_kem_pk_bytes, segment_size, &mut OsRng)
+    }
+
+    /// `seal_to` with an injected RNG (KEM encapsulation, then nonce prefix)
+    pub fn seal_to_with_rng(
+        recipient_kem_pk_bytes: &[u8],
+        segment_size: u32,
+        rng: &mut impl CryptoRngCore,
+    ) -> MercyResult<(Vec<u8>, Self)> {
+        if segment_size == 0 || segment_size > MAX_SEGMENT_SIZE {
+            return Err(MercyError::InvalidBlob);
+        }
+        let recipient_pk = pq::kem_public_key(recipient_kem_pk_bytes)?;
+        let (kem_ciphertext, shared_secret) = pq::kem_encapsulate(&recipient_pk, rng)?;
+
+        let pk_hash = kdf::recipient_pk_hash(recipient_kem_pk_bytes);
+        let key = KeySchedule::new(&*shared_secret, &kem_ciphertext, &pk_hash)
+            .derive_key(KeyPurpose::StreamAead)?;
+
+        let mut nonce_prefix = [0u8; NONCE_PREFIX_LEN];
+        rng.fill_bytes(&mut nonce_prefix);
+
+        let header = StreamHeader {
+            kem_alg: KemAlgorithm::MlKem768,
+            aead_alg: AeadAlgorithm::Aes256Gcm,
+            key_id: blob::key_id(recipient_kem_pk_bytes),
+            kem_ciphertext,
+            nonce_prefix,
+            segment_size,
+        }
+        .encode()?;
+
+        let encryptor = StreamEncryptor::new(&key, nonce_prefix, header.clone());
+        Ok((header, encryptor))
+    }
+
+    pub fn encrypt_next(&mut self, segment: &mut Vec<u8>) -> MercyResult<()> {
+        self.seal(segment, false)?;
+        self.counter = self
+            .counter
+            .checked_add(1)
+            .ok_or(MercyError::CryptoFailure)?;
+        Ok(())
+    }
+
+    pub fn encrypt_last(self, segment: &mut Vec<u8>) -> MercyResult<()> {
+        self.seal(segment, true)
+    }
+
+    fn seal(&self, segment: &mut Vec<u8>, last: bool) -> MercyResult<()> {
+        let nonce = segment_nonce(&self.nonce_prefix, self.counter, last);
+        self.cipher
+            .encrypt_in_place(Nonce::from_slice(&nonce), &self.aad, segment)
+            .map_err(|_| MercyError::CryptoFailure)
+    }
+}
+
+/// Segment-at-a-time opener — mirrors `StreamEncryptor`
+pub struct StreamDecryptor {
+    cipher: Aes256Gcm,
+    nonce_prefix: [u8; NONCE_PREFIX_LEN],
+    counter: u32,
+    aad: Vec<u8>,
+}
+
+impl StreamDecryptor {
+    pub fn new(key: &[u8; KEY_LEN], nonce_prefix: [u8; NONCE_PREFIX_LEN], aad: Vec<u8>) -> Self {
+        StreamDecryptor {
+            cipher: Aes256Gcm::new(key.into()),
+            nonce_prefix,
+            counter: 0,
+            aad,
+        }
+    }
+
+    /// Open a stream whose parsed header (+ raw header bytes) was read from the front of the input
+    pub fn open(
+        header: &StreamHeader,
+        header_bytes: &[u8],
+        kem_sk: &MlKem768SecretKey,
+    ) -> MercyResult<Self> {
+        let recipient_pk_bytes = pq::kem_public_key_bytes(kem_sk.encapsulation_key());
+        if header.key_id != blob::key_id(&recipient_pk_bytes) {
+            return Err(MercyError::WrongRecipient);
+        }
+
+        let shared_secret = pq::kem_decapsulate(kem_sk, &header.kem_ciphertext)?;
+
+        let pk_hash = kdf::recipient_pk_hash(&recipient_pk_bytes);
+        let key = KeySchedule::new(&*shared_secret, &header.kem_ciphertext, &pk_hash)
+            .derive_key(KeyPurpose::StreamAead)?;
+
+        Ok(StreamDecryptor::new(
+            &key,
+            header.nonce_prefix,
+            header_bytes.to_vec(),
+        ))
+    }
+
+    pub fn decrypt_next(&mut self, segment: &mut Vec<u8>) -> MercyResult<()> {
+        self.open_segment(segment, false)
+            .map_err(|_| MercyError::DecryptionFailed)?;
+        self.counter = self
+            .counter
+            .checked_add(1)
+            .ok_or(MercyError::CryptoFailure)?;
+        Ok(())
+    }
+
+    /// Open the final segment — a non-final segment in last position is reported as truncation
+    pub fn decrypt_last(self, segment: &mut Vec<u8>) -> MercyResult<()> {
+        if segment.len() < TAG_LEN {
+            return Err(MercyError::StreamTruncated);
+        }
+        let original = segment.clone();
+        if self.open_segment(segment, true).is_ok() {
+            return Ok(());
+        }
+
+        let mut probe = original;
+        if self.open_segment(&mut probe, false).is_ok() {
+            Err(MercyError::StreamTruncated)
+        } else {
+            Err(MercyError::DecryptionFailed)
+        }
+    }
+
+    fn open_segment(&self, segment: &mut Vec<u8>, last: bool) -> Result<(), aes_gcm::Error> {
+        let nonce = segment_nonce(&self.nonce_prefix, self.counter, last);
+        self.cipher
+            .decrypt_in_place(Nonce::from_slice(&nonce), &self.aad, segment)
+    }
+}
+
+#[cfg(feature = "std")]
+pub use self::adapters::{StreamReader, StreamWriter};
+
+#[cfg(feature = "std")]
+mod adapters {
+    use super::*;
+    use core::mem;
+    use std::io::{self, Read, Write};
+
+    fn to_io(e: MercyError) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, e)
+    }
+
+    /// `Write` adapter — buffers one segment, seals it, forwards ciphertext. Call `finish()`.
+    pub struct StreamWriter<W: Write> {
+        inner: W,
+        encryptor: StreamEncryptor,
+        segment_size: usize,
+        buf: Vec<u8>,
+    }
+
+    impl<W: Write> StreamWriter<W> {
+        /// Emit a stream header sealed to the recipient KEM PK, then accept plaintext
+        pub fn new(inner: W, recipient_kem_pk_bytes: &[u8], segment_size: u32) -> io::Result<Self> {
+            let (header, encryptor) =
+                StreamEncryptor::seal_to(recipient_kem_pk_bytes, segment_size).map_err(to_io)?;
+            Self::with_encryptor(inner, &header, encryptor, segment_size)
+        }
+
+        /// Use an already keyed encryptor — `header` is written verbatim before the first segment
+        pub fn with_encryptor(
+            mut inner: W,
+            header: &[u8],
+            encryptor: StreamEncryptor,
+            segment_size: u32,
+        ) -> io::Result<Self> {
+            inner.write_all(header)?;
+            let segment_size = segment_size as usize;
+            Ok(StreamWriter {
+                inner,
+                encryptor,
+                segment_size,
+                buf: Vec::with_capacity(segment_size + 1),
+            })
+        }
+
+        /// Seal the final segment and return the inner writer — skipping this reads back as truncated
+        pub fn finish(self) -> io::Result<W> {
+            let StreamWriter {
+                mut inner,
+                encryptor,
+                mut buf,
+                ..
+            } = self;
+            encryptor.encrypt_last(&mut buf).map_err(to_io)?;
+            inner.write_all(&buf)?;
+            inner.flush()?;
+            Ok(inner)
+        }
+    }
+
+    impl<W: Write> Write for StreamWriter<W> {
+        fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+            // Hold back at least one byte so the final segment is always sealed by finish()
+            let room = self.segment_size + 1 - self.buf.len();
+            let n = data.len().min(room);
+            self.buf.extend_from_slice(&data[..n]);
+
+            if self.buf.len() > self.segment_size {
+                let rest = self.buf.split_off(self.segment_size);
+                let mut segment = mem::replace(&mut self.buf, rest);
+                self.encryptor.encrypt_next(&mut segment).map_err(to_io)?;
+                self.inner.write_all(&segment)?;
+            }
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.inner.flush()
+        }
+    }
+
+    /// `Read` adapter — reads one segment ahead so the final segment is known before opening it
+    pub struct StreamReader<R: Read> {
+        inner: R,
+        decryptor: Option<StreamDecryptor>,
+        segment_ct_len: usize,
+        pending: Vec<u8>,
+        plain: Vec<u8>,
+        pos: usize,
+    }
+
+    impl<R: Read> StreamReader<R> {
+        /// Read the stream header from `inner` and open it with the recipient KEM SK
+        pub fn new(mut inner: R, kem_sk: &MlKem768SecretKey) -> io::Result<Self> {
+            let header_bytes = read_header(&mut inner)?;
+            let (header, _) = StreamHeader::parse(&header_bytes).map_err(to_io)?;
+            let decryptor = StreamDecryptor::open(&header, &header_bytes, kem_sk).map_err(to_io)?;
+            Ok(Self::with_decryptor(inner, decryptor, header.segment_size))
+        }
+
+        /// Use an already keyed decryptor — `inner` must be positioned at the first segment
+        pub fn with_decryptor(inner: R, decryptor: StreamDecryptor, segment_size: u32) -> Self {
+            StreamReader {
+                inner,
+                decryptor: Some(decryptor),
+                segment_ct_len: segment_size as usize + TAG_LEN,
+                pending: Vec::new(),
+                plain: Vec::new(),
+                pos: 0,
+            }
+        }
+
+        fn fill_pending(&mut self) -> io::Result<()> {
+            let want = self.segment_ct_len + 1;
+            while self.pending.len() < want {
+                let start = self.pending.len();
+                self.pending.resize(want, 0);
+                let res = self.inner.read(&mut self.pending[start..]);
+                self.pending.truncate(start + *res.as_ref().unwrap_or(&0));
+                match res {
+                    Ok(0) => break,
+                    Ok(_) => {}
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            Ok(())
+        }
+
+        fn next_segment(&mut self) -> io::Result<()> {
+            self.fill_pending()?;
+
+            if self.pending.len() > self.segment_ct_len {
+                let rest = self.pending.split_off(self.segment_ct_len);
+                let mut segment = mem::replace(&mut self.pending, rest);
+                if let Some(decryptor) = self.decryptor.as_mut() {
+                    decryptor.decrypt_next(&mut segment).map_err(to_io)?;
+                }
+                self.plain = segment;
+            } else if let Some(decryptor) = self.decryptor.take() {
+                let mut segment = mem::take(&mut self.pending);
+                decryptor.decrypt_last(&mut segment).map_err(to_io)?;
+                self.plain = segment;
+            }
+            self.pos = 0;
+            Ok(())
+        }
+    }
+
+    impl<R: Read> Read for StreamReader<R> {
+        fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+            while self.pos == self.plain.len() {
+                if self.decryptor.is_none() {
+                    return Ok(0);
+                }
+                self.next_segment()?;
+            }
+            let n = out.len().min(self.plain.len() - self.pos);
+            out[..n].copy_from_slice(&self.plain[self.pos..self.pos + n]);
+            self.pos += n;
+            Ok(n)
+        }
+    }
+
+    /// Read exactly one stream header (fixed prefix, then the variable KEM ciphertext part)
+    fn read_header<R: Read>(inner: &mut R) -> io::Result<Vec<u8>> {
+        let mut header = alloc::vec![0u8; 4 + 3 + KEY_ID_LEN + 2];
+        inner.read_exact(&mut header)?;
+        let len = header.len();
+        let ct_len = u16::from_be_bytes([header[len - 2], header[len - 1]]) as usize;
+
+        header.resize(len + ct_len + NONCE_PREFIX_LEN + 4, 0);
+        inner.read_exact(&mut header[len..])?;
+        Ok(header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    const KEY: [u8; KEY_LEN] = [0x42; KEY_LEN];
+    const PREFIX: [u8; NONCE_PREFIX_LEN] = [9; NONCE_PREFIX_LEN];
+
+    fn seal_segments(chunks: &[&[u8]]) -> Vec<Vec<u8>> {
+        let mut enc = StreamEncryptor::new(&KEY, PREFIX, b"hdr".to_vec());
+        let (last, body) = chunks.split_last().unwrap();
+
+        let mut out: Vec<Vec<u8>> = body
+            .iter()
+            .map(|chunk| {
+                let mut seg = chunk.to_vec();
+                enc.encrypt_next(&mut seg).unwrap();
+                seg
+            })
+            .collect();
+
+        let mut seg = last.to_vec();
+        enc.encrypt_last(&mut seg).unwrap();
+        out.push(seg);
+        out
+    }
+
+    #[test]
+    fn test_stream_segments_roundtrip() {
+        let segs = seal_segments(&[b"alpha", b"bravo", b"end"]);
+
+        let mut dec = StreamDecryptor::new(&KEY, PREFIX, b"hdr".to_vec());
+        let mut s0 = segs[0].clone();
+        let mut s1 = segs[1].clone();
+        let mut s2 = segs[2].clone();
+        dec.decrypt_next(&mut s0).unwrap();
+        dec.decrypt_next(&mut s1).unwrap();
+        dec.decrypt_last(&mut s2).unwrap();
+        assert_eq!([s0, s1, s2].concat(), b"alphabravoend".to_vec());
+    }
+
+    #[test]
+    fn test_stream_rejects_reorder_and_wrong_aad() {
+        let segs = seal_segments(&[b"alpha", b"bravo", b"end"]);
+
+        let mut dec = StreamDecryptor::new(&KEY, PREFIX, b"hdr".to_vec());
+        let mut swapped = segs[1].clone();
+        assert_eq!(
+            dec.decrypt_next(&mut swapped),
+            Err(MercyError::DecryptionFailed)
+        );
+
+        let mut other_hdr = StreamDecryptor::new(&KEY, PREFIX, b"HDR".to_vec());
+        let mut s0 = segs[0].clone();
+        assert_eq!(
+            other_hdr.decrypt_next(&mut s0),
+            Err(MercyError::DecryptionFailed)
+        );
+    }
+
+    #[test]
+    fn test_stream_detects_truncation() {
+        let segs = seal_segments(&[b"alpha", b"bravo", b"end"]);
+
+        // Drop the final segment — bravo now sits in last position
+        let mut dec = StreamDecryptor::new(&KEY, PREFIX, b"hdr".to_vec());
+        let mut s0 = segs[0].clone();
+        dec.decrypt_next(&mut s0).unwrap();
+        let mut s1 = segs[1].clone();
+        assert_eq!(dec.decrypt_last(&mut s1), Err(MercyError::StreamTruncated));
+
+        let dec = StreamDecryptor::new(&KEY, PREFIX, b"hdr".to_vec());
+        assert_eq!(
+            dec.decrypt_last(&mut vec![0u8; 3]),
+            Err(MercyError::StreamTruncated)
+        );
+    }
+
+    #[test]
+    fn test_stream_header_roundtrip_and_limits() {
+        let header = StreamHeader {
+            kem_alg: KemAlgorithm::MlKem768,
+            aead_alg: AeadAlgorithm::Aes256Gcm,
+            key_id: blob::key_id(b"recipient"),
+            kem_ciphertext: vec![5u8; 1088],
+            nonce_prefix: PREFIX,
+            segment_size: DEFAULT_SEGMENT_SIZE,
+        };
+        let bytes = header.encode().unwrap();
+        assert_eq!(
+            StreamHeader::parse(&bytes).unwrap(),
+            (header.clone(), bytes.len())
+        );
+
+        let zero = StreamHeader {
+            segment_size: 0,
+            ..header.clone()
+        }
+        .encode()
+        .unwrap();
+        assert_eq!(StreamHeader::parse(&zero), Err(MercyError::InvalidBlob));
+
+        let mut bad_version = bytes;
+        bad_version[4] = 9;
+        assert_eq!(
+            StreamHeader::parse(&bad_version),
+            Err(MercyError::UnsupportedVersion)
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_stream_io_adapters_roundtrip() {
+        use std::io::{Read, Write};
+
+        for len in [0usize, 1, 63, 64, 65, 1000] {
+            let plaintext: Vec<u8> = (0..len).map(|i| i as u8).collect();
+
+            let enc = StreamEncryptor::new(&KEY, PREFIX, b"hdr".to_vec());
+            let mut writer = StreamWriter::with_encryptor(Vec::new(), b"", enc, 64).unwrap();
+            for chunk in plaintext.chunks(7) {
+                writer.write_all(chunk).unwrap();
+            }
+            let sealed = writer.finish().unwrap();
+
+            let dec = StreamDecryptor::new(&KEY, PREFIX, b"hdr".to_vec());
+            let mut reader = StreamReader::with_decryptor(sealed.as_slice(), dec, 64);
+            let mut opened = Vec::new();
+            reader.read_to_end(&mut opened).unwrap();
+            assert_eq!(opened, plaintext, "len {}", len);
+
+            // Chop the last segment off at a segment boundary
+            if len > 64 {
+                let cut = (sealed.len() - 1) / (64 + TAG_LEN) * (64 + TAG_LEN);
+                let dec = StreamDecryptor::new(&KEY, PREFIX, b"hdr".to_vec());
+                let mut reader = StreamReader::with_decryptor(&sealed[..cut], dec, 64);
+                assert!(
+                    reader.read_to_end(&mut Vec::new()).is_err(),
+                    "truncated len {} accepted",
+                    len
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_stream_kem_sealed_roundtrip() {
+        use std::io::{Read, Write};
+
+        let (kem_sk, kem_pk) = pq::kem_generate(&mut OsRng);
+        let kem_pk_bytes = pq::kem_public_key_bytes(&kem_pk);
+        let logs = vec![b'L'; 200_000];
+
+        let mut writer =
+            StreamWriter::new(Vec::new(), &kem_pk_bytes, DEFAULT_SEGMENT_SIZE).unwrap();
+        writer.write_all(&logs).unwrap();
+        let sealed = writer.finish().unwrap();
+
+        let mut opened = Vec::new();
+        StreamReader::new(sealed.as_slice(), &kem_sk)
+            .unwrap()
+            .read_to_end(&mut opened)
+            .unwrap();
+        assert_eq!(opened, logs);
+    }
+}
@@ -0,0 +1,168 @@
+// rust/src/wasm.rs — Browser Verification Eternal (wasm-bindgen, verify-only)
+//
+//   wasm-pack build --target web -- --no-default-features --features wasm
+//
+// Lets support staff check an exported attestation proof entirely client side. Only public-key
+// operations are exposed — nothing here takes a secret key or needs randomness. Malformed input
+// throws a JS `Error` carrying the `MercyError` message; a well-formed but wrong signature is `false`.
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use wasm_bindgen::prelude::*;
+
+use crate::challenge::ChallengedReport;
+use crate::context::MessageKind;
+use crate::error::MercyError;
+use crate::ledger::Ledger;
+use crate::merkle::{self, ConsistencyProof, InclusionProof, SignedTreeHead, HASH_LEN};
+
+fn js_error(err: MercyError) -> JsError {
+    JsError::new(&err.to_string())
+}
+
+fn message_kind(kind: &str) -> Result<MessageKind, JsError> {
+    MessageKind::from_name(kind).ok_or_else(|| JsError::new("unknown message kind"))
+}
+
+/// Verify an ML-DSA-65 signature — `kind` is a wire name such as `"attestation_report"`
+#[wasm_bindgen(js_name = verifyData)]
+pub fn verify_data(
+    dsa_pk: &[u8],
+    message: &[u8],
+    signature: &[u8],
+    kind: &str,
+) -> Result<bool, JsError> {
+    crate::verify_data(dsa_pk, message, signature, message_kind(kind)?).map_err(js_error)
+}
+
+/// Verify a detached report signature (always the attestation-report context)
+#[wasm_bindgen(js_name = verifyReportSignature)]
+pub fn verify_report_signature(
+    dsa_pk: &[u8],
+    report: &[u8],
+    signature: &[u8],
+) -> Result<bool, JsError> {
+    crate::verify_data(dsa_pk, report, signature, MessageKind::AttestationReport).map_err(js_error)
+}
+
+/// Challenged report split into the verifier nonce and the report JSON text
+#[wasm_bindgen]
+pub struct ParsedReport {
+    nonce: Vec<u8>,
+    report_json: String,
+}
+
+#[wasm_bindgen]
+impl ParsedReport {
+    #[wasm_bindgen(getter)]
+    pub fn nonce(&self) -> Vec<u8> {
+        self.nonce.clone()
+    }
+
+    /// Report JSON as signed — hand to `JSON.parse` for display only, verify the raw bytes
+    #[wasm_bindgen(getter, js_name = reportJson)]
+    pub fn report_json(&self) -> String {
+        self.report_json.clone()
+    }
+}
+
+/// Parse the signed bytes of a challenged report (see `challenge.rs`)
+#[wasm_bindgen(js_name = parseChallengedReport)]
+pub fn parse_challenged_report(bytes: &[u8]) -> Result<ParsedReport, JsError> {
+    let parsed = ChallengedReport::parse(bytes).map_err(js_error)?;
+    let report_json =
+        String::from_utf8(parsed.report).map_err(|_| js_error(MercyError::InvalidBlob))?;
+    Ok(ParsedReport {
+        nonce: parsed.nonce.to_vec(),
+        report_json,
+    })
+}
+
+/// Result of walking an exported device ledger (see `ledger.rs`)
+#[wasm_bindgen]
+pub struct LedgerStatus {
+    length: u64,
+    first_invalid: Option<u64>,
+    head_hash: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl LedgerStatus {
+    #[wasm_bindgen(getter)]
+    pub fn intact(&self) -> bool {
+        self.first_invalid.is_none()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> u64 {
+        self.length
+    }
+
+    /// Seq of the first tampered entry, `undefined` if the chain is intact
+    #[wasm_bindgen(getter, js_name = firstInvalid)]
+    pub fn first_invalid(&self) -> Option<u64> {
+        self.first_invalid
+    }
+
+    /// Hash of the last entry of the intact prefix — compare with the head the verifier pinned
+    #[wasm_bindgen(getter, js_name = headHash)]
+    pub fn head_hash(&self) -> Vec<u8> {
+        self.head_hash.clone()
+    }
+}
+
+/// Verify the hash chain and every signature of a ledger export against the device key
+#[wasm_bindgen(js_name = verifyLedgerChain)]
+pub fn verify_ledger_chain(export: &[u8], dsa_pk: &[u8]) -> Result<LedgerStatus, JsError> {
+    let verification = Ledger::import(export)
+        .and_then(|ledger| ledger.verify(dsa_pk))
+        .map_err(js_error)?;
+    Ok(LedgerStatus {
+        length: verification.length,
+        first_invalid: verification.first_invalid,
+        head_hash: verification.head_hash,
+    })
+}
+
+fn proof_path(flat: &[u8]) -> Result<Vec<Vec<u8>>, JsError> {
+    if !flat.len().is_multiple_of(HASH_LEN) {
+        return Err(JsError::new("proof path is not a multiple of 32 bytes"));
+    }
+    Ok(flat.chunks(HASH_LEN).map(<[u8]>::to_vec).collect())
+}
+
+/// Check an encoded ledger entry against a signed tree head — `path` is the audit path flattened
+/// into concatenated 32-byte hashes
+#[wasm_bindgen(js_name = verifyInclusion)]
+pub fn verify_inclusion(
+    sth: &[u8],
+    dsa_pk: &[u8],
+    entry: &[u8],
+    leaf_index: u64,
+    path: &[u8],
+) -> Result<bool, JsError> {
+    let sth = SignedTreeHead::parse(sth).map_err(js_error)?;
+    let proof = InclusionProof {
+        leaf_index,
+        tree_size: sth.tree_size,
+        path: proof_path(path)?,
+    };
+    merkle::verify_inclusion(&sth, dsa_pk, entry, &proof).map_err(js_error)
+}
+
+/// Check that `new_sth` only appended to `old_sth` (flattened consistency proof, as above)
+#[wasm_bindgen(js_name = verifyConsistency)]
+pub fn verify_consistency(
+    old_sth: &[u8],
+    new_sth: &[u8],
+    dsa_pk: &[u8],
+    path: &[u8],
+) -> Result<bool, JsError> {
+    let old = SignedTreeHead::parse(old_sth).map_err(js_error)?;
+    let new = SignedTreeHead::parse(new_sth).map_err(js_error)?;
+    let proof = ConsistencyProof {
+        old_size: old.tree_size,
+        new_size: new.tree_size,
+        path: proof_path(path)?,
+    };
+    merkle::verify_append_only(&old, &new, dsa_pk, &proof).map_err(js_error)
+}
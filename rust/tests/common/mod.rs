@@ -0,0 +1,13 @@
+// tests/common/mod.rs — helpers shared by the integration tests
+use std::path::PathBuf;
+
+use mercyshieldplus::collector::RootFs;
+
+/// Fixture tree `tests/fixtures/rootfs/<case>`
+pub fn fixture(case: &str) -> RootFs {
+    RootFs::new(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/rootfs")
+            .join(case),
+    )
+}
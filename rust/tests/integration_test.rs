@@ -1,19 +1,74 @@
-#[cfg(test)]
-mod tests {
-    use super::*;  // Import lib exports
-
-    #[test]
-    fn pq_keygen_sign_verify() {
-        let (kem_pk, dsa_pk) = generate_pq_keypair();
-        assert!(!kem_pk.is_empty());
-        assert!(!dsa_pk.is_empty());
-
-        // Full flow stub — expand eternal
-    }
-
-    #[test]
-    fn integrity_basic() {
-        let report = evaluate_integrity(vec![], vec![], false, "valid_token".to_string());
-        assert_eq!(report.risk_score, 0);
-    }
+#[cfg(all(feature = "std", target_os = "linux"))]
+mod common;
+
+use mercyshieldplus::integrity::evaluate_integrity;
+use mercyshieldplus::{generate_pq_keypair, pq_sign_data, pq_verify_data, MessageKind, PqKeyPair};
+
+#[test]
+fn pq_keygen_sign_verify() {
+    let PqKeyPair {
+        kem_pk_b64,
+        dsa_pk_b64,
+        dsa_sk_b64,
+        ..
+    } = generate_pq_keypair();
+    assert!(!kem_pk_b64.is_empty());
+
+    let sig = pq_sign_data(
+        dsa_sk_b64,
+        b"report".to_vec(),
+        MessageKind::AttestationReport,
+    )
+    .unwrap();
+    assert_eq!(
+        pq_verify_data(
+            dsa_pk_b64,
+            b"report".to_vec(),
+            sig,
+            MessageKind::AttestationReport
+        ),
+        Ok(true)
+    );
+}
+
+#[test]
+fn integrity_basic() {
+    let report = evaluate_integrity(vec![], "valid_token".to_string());
+    assert_eq!(report.risk_score, 0);
+}
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+#[test]
+fn native_collectors_feed_integrity() {
+    use common::fixture;
+    use mercyshieldplus::collector::collect_root_evidence;
+    use mercyshieldplus::integrity::IntegrityVerdict;
+
+    let clean = evaluate_integrity(
+        collect_root_evidence(&fixture("clean")),
+        "valid_token".to_string(),
+    );
+    assert_eq!(
+        (clean.risk_score, clean.verdict),
+        (0, IntegrityVerdict::Genuine)
+    );
+
+    // su (40) + Magisk (20)
+    let magisk = evaluate_integrity(
+        collect_root_evidence(&fixture("magisk")),
+        "valid_token".to_string(),
+    );
+    assert_eq!(
+        (magisk.risk_score, magisk.verdict),
+        (60, IntegrityVerdict::Compromised)
+    );
+
+    let kernelsu = evaluate_integrity(
+        collect_root_evidence(&fixture("kernelsu")),
+        "valid_token".to_string(),
+    );
+    assert_eq!(
+        (kernelsu.risk_score, kernelsu.verdict),
+        (40, IntegrityVerdict::Suspicious)
+    );
 }
@@ -0,0 +1,131 @@
+// rust/tests/wasm.rs — verify-only JS bindings under wasm-bindgen-test
+//
+//   wasm-pack test --node -- --no-default-features --features wasm
+//   wasm-pack test --headless --firefox -- --no-default-features --features wasm
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use rand_core::OsRng;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use mercyshieldplus::challenge::{ChallengedReport, NONCE_LEN};
+use mercyshieldplus::ledger::Ledger;
+use mercyshieldplus::wasm::{
+    parse_challenged_report, verify_consistency, verify_data, verify_inclusion,
+    verify_ledger_chain, verify_report_signature,
+};
+use mercyshieldplus::{pq, sign_data_with_rng, MessageKind};
+
+fn device_key() -> (Vec<u8>, Vec<u8>) {
+    let sk = pq::dsa_generate(&mut OsRng);
+    (
+        pq::dsa_secret_key_bytes(&sk).to_vec(),
+        pq::dsa_public_key_bytes(&sk),
+    )
+}
+
+#[wasm_bindgen_test]
+fn verifies_exported_report_proof() {
+    let (sk, pk) = device_key();
+    let report = ChallengedReport {
+        nonce: [9; NONCE_LEN],
+        report: b"{\"risk_score\":0}".to_vec(),
+    }
+    .encode();
+    let signature =
+        sign_data_with_rng(&sk, &report, MessageKind::AttestationReport, &mut OsRng).unwrap();
+
+    assert_eq!(
+        verify_report_signature(&pk, &report, &signature).ok(),
+        Some(true)
+    );
+    assert_eq!(
+        verify_data(&pk, &report, &signature, "attestation_report").ok(),
+        Some(true)
+    );
+    assert_eq!(
+        verify_data(&pk, &report, &signature, "ledger_entry").ok(),
+        Some(false)
+    );
+
+    let mut tampered = report.clone();
+    *tampered.last_mut().unwrap() ^= 1;
+    assert_eq!(
+        verify_report_signature(&pk, &tampered, &signature).ok(),
+        Some(false)
+    );
+
+    let parsed = parse_challenged_report(&report).ok().unwrap();
+    assert_eq!(parsed.nonce(), vec![9; NONCE_LEN]);
+    assert_eq!(parsed.report_json(), "{\"risk_score\":0}");
+}
+
+#[wasm_bindgen_test]
+fn verifies_exported_ledger() {
+    let (sk, pk) = device_key();
+    let mut ledger = Ledger::new();
+    ledger
+        .append_with_rng(b"{\"risk_score\":0}", 1, &sk, &mut OsRng)
+        .unwrap();
+    ledger
+        .append_with_rng(b"{\"risk_score\":95}", 2, &sk, &mut OsRng)
+        .unwrap();
+
+    let status = verify_ledger_chain(&ledger.export(), &pk).ok().unwrap();
+    assert!(status.intact());
+    assert_eq!(status.length(), 2);
+    assert_eq!(status.head_hash(), ledger.head().unwrap().hash().to_vec());
+
+    let (other_sk, _) = device_key();
+    ledger
+        .append_with_rng(b"{}", 3, &other_sk, &mut OsRng)
+        .unwrap();
+    let status = verify_ledger_chain(&ledger.export(), &pk).ok().unwrap();
+    assert_eq!(status.first_invalid(), Some(2));
+}
+
+#[wasm_bindgen_test]
+fn verifies_tree_head_proofs() {
+    let (sk, pk) = device_key();
+    let mut ledger = Ledger::new();
+    for i in 0..3u64 {
+        ledger
+            .append_with_rng(&i.to_be_bytes(), i, &sk, &mut OsRng)
+            .unwrap();
+    }
+    let old = ledger.sign_tree_head(3, &sk).unwrap().encode();
+    ledger.append_with_rng(b"{}", 4, &sk, &mut OsRng).unwrap();
+    let new = ledger.sign_tree_head(5, &sk).unwrap().encode();
+
+    let tree = ledger.tree();
+    let inclusion: Vec<u8> = tree.inclusion_proof(2, 4).unwrap().path.concat();
+    let entry = ledger.entries()[2].encode();
+    assert_eq!(
+        verify_inclusion(&new, &pk, &entry, 2, &inclusion).ok(),
+        Some(true)
+    );
+    assert_eq!(
+        verify_inclusion(&new, &pk, &entry, 1, &inclusion).ok(),
+        Some(false)
+    );
+
+    let consistency: Vec<u8> = tree.consistency_proof(3, 4).unwrap().path.concat();
+    assert_eq!(
+        verify_consistency(&old, &new, &pk, &consistency).ok(),
+        Some(true)
+    );
+    assert_eq!(
+        verify_consistency(&new, &old, &pk, &consistency).ok(),
+        Some(false)
+    );
+    assert!(verify_consistency(&old, &new, &pk, &consistency[1..]).is_err());
+}
+
+#[wasm_bindgen_test]
+fn malformed_input_throws() {
+    let (_, pk) = device_key();
+    assert!(verify_data(&[1, 2, 3], b"x", &[0; 5], "attestation_report").is_err());
+    assert!(verify_data(&pk, b"x", &[0; 5], "attestation_report").is_err());
+    assert!(verify_data(&pk, b"x", &[0; 5], "AttestationReport").is_err());
+    assert!(parse_challenged_report(b"{\"risk_score\":0}").is_err());
+    assert!(verify_ledger_chain(b"MSLX", &pk).is_err());
+}
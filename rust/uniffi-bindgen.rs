@@ -0,0 +1,7 @@
+// rust/uniffi-bindgen.rs — uniffi-bindgen CLI pinned to our uniffi version
+//
+//   cargo run --features bindgen-cli --bin uniffi-bindgen -- generate \
+//       --library target/release/libmercyshieldplus.so --language python --out-dir bindings/python
+fn main() {
+    uniffi::uniffi_bindgen_main()
+}